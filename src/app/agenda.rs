@@ -0,0 +1,190 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+use super::{
+    humanize::{self, Noun},
+    overlay::{self, Line},
+};
+use crate::github::Milestone;
+
+/// A milestone and the start date Zenhub gives it when it's used as a sprint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AgendaMilestone {
+    pub milestone: Milestone,
+    pub start_date: Option<DateTime<Utc>>,
+}
+
+impl AgendaMilestone {
+    // Github stores due dates as a time on the day picked, use that day
+    // regardless of the local timezone.
+    fn due_date(&self) -> Option<NaiveDate> {
+        self.milestone
+            .due_on
+            .map(|due_on| due_on.naive_utc().date())
+    }
+
+    fn start_date(&self) -> Option<NaiveDate> {
+        self.start_date
+            .map(|start_date| start_date.naive_utc().date())
+    }
+
+    fn in_sprint(&self, date: NaiveDate) -> bool {
+        match (self.start_date(), self.due_date()) {
+            (Some(start), Some(due)) => start <= date && date <= due,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Event {
+    SprintStart(String),
+    Due(String, usize),
+}
+
+/// A month grid of the current and next month, with milestone due dates and
+/// sprints highlighted, followed by the upcoming dates and the number of
+/// issues still open for each.
+pub fn agenda(
+    milestones: &[AgendaMilestone],
+    today: NaiveDate,
+    theme: &overlay::Theme,
+) -> Vec<Line> {
+    let mut events: BTreeMap<NaiveDate, Vec<Event>> = BTreeMap::new();
+    for milestone in milestones.iter() {
+        if let Some(start) = milestone.start_date() {
+            events
+                .entry(start)
+                .or_default()
+                .push(Event::SprintStart(milestone.milestone.title.clone()));
+        }
+        if let Some(due) = milestone.due_date() {
+            events.entry(due).or_default().push(Event::Due(
+                milestone.milestone.title.clone(),
+                milestone.milestone.open_issues,
+            ));
+        }
+    }
+
+    let mut lines = Vec::new();
+    let this_month = first_of_month(today);
+    for month in [this_month, next_month(this_month)].iter() {
+        lines.extend(month_grid(*month, today, milestones, &events, theme));
+        lines.push(vec![]);
+    }
+
+    lines.push(vec![("Upcoming".into(), theme.heading)]);
+    let mut num_upcoming = 0;
+    for (date, events) in events.range(today..) {
+        let num_open: usize = events
+            .iter()
+            .map(|event| match event {
+                Event::Due(_, open_issues) => *open_issues,
+                Event::SprintStart(_) => 0,
+            })
+            .sum();
+        let mut line: Line = vec![(format!("{}  ", date.format("%a %d %b %Y")), theme.text)];
+        let descriptions: Vec<_> = events
+            .iter()
+            .map(|event| match event {
+                Event::Due(title, _) => format!("{} due", title),
+                Event::SprintStart(title) => format!("{} starts", title),
+            })
+            .collect();
+        line.push((descriptions.join(", "), theme.text));
+        if num_open > 0 {
+            line.push((
+                format!("  {}", humanize::count_with(num_open, "open", Noun::Issue)),
+                theme.dim,
+            ));
+        }
+        lines.push(line);
+        num_upcoming += 1;
+    }
+    if num_upcoming == 0 {
+        lines.push(vec![("No upcoming milestones".into(), theme.dim)]);
+    }
+
+    let overdue: Vec<_> = milestones
+        .iter()
+        .filter(|milestone| milestone.due_date().map_or(false, |due| due < today))
+        .collect();
+    if !overdue.is_empty() {
+        lines.push(vec![]);
+        lines.push(vec![("Overdue".into(), theme.heading)]);
+        for milestone in overdue {
+            lines.push(vec![
+                (
+                    format!(
+                        "{}  ",
+                        milestone
+                            .due_date()
+                            .expect("overdue milestones have a due date")
+                            .format("%a %d %b %Y")
+                    ),
+                    theme.text,
+                ),
+                (milestone.milestone.title.clone(), theme.text),
+                (
+                    format!(
+                        "  {}",
+                        humanize::count_with(milestone.milestone.open_issues, "open", Noun::Issue)
+                    ),
+                    theme.dim,
+                ),
+            ]);
+        }
+    }
+    lines
+}
+
+fn month_grid(
+    month: NaiveDate,
+    today: NaiveDate,
+    milestones: &[AgendaMilestone],
+    events: &BTreeMap<NaiveDate, Vec<Event>>,
+    theme: &overlay::Theme,
+) -> Vec<Line> {
+    let mut lines = vec![
+        vec![(month.format("%B %Y").to_string(), theme.heading)],
+        vec![(" Mo  Tu  We  Th  Fr  Sa  Su".into(), theme.dim)],
+    ];
+    let mut week: Line = vec![(
+        "    ".repeat(month.weekday().num_days_from_monday() as usize),
+        theme.dim,
+    )];
+    let mut date = month;
+    while date.month() == month.month() {
+        let style = if date == today {
+            theme.selected
+        } else if events.contains_key(&date) {
+            theme.heading
+        } else if milestones.iter().any(|milestone| milestone.in_sprint(date)) {
+            theme.text
+        } else {
+            theme.dim
+        };
+        week.push((format!(" {:>2} ", date.day()), style));
+        if date.weekday().num_days_from_monday() == 6 {
+            lines.push(week);
+            week = vec![];
+        }
+        date = date + Duration::days(1);
+    }
+    if !week.is_empty() {
+        lines.push(week);
+    }
+    lines
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd(date.year(), date.month(), 1)
+}
+
+fn next_month(month: NaiveDate) -> NaiveDate {
+    if month.month() == 12 {
+        NaiveDate::from_ymd(month.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd(month.year(), month.month() + 1, 1)
+    }
+}
@@ -0,0 +1,276 @@
+use im::hashmap::HashMap;
+use std::collections::BTreeMap;
+
+use super::{
+    humanize::{self, Noun},
+    overlay::{self, Line},
+    BoardView, FutureValue,
+};
+use crate::{
+    github::{Issue, IssueNumber},
+    zenhub::PipelineKind,
+};
+
+/// Summarises how estimates are distributed in each pipeline and how many
+/// points each assignee has across the open pipelines of the board.
+pub fn estimate_distribution(
+    board: &BoardView,
+    issues: &HashMap<IssueNumber, FutureValue<Issue>>,
+    theme: &overlay::Theme,
+) -> Vec<Line> {
+    let mut lines: Vec<Line> = vec![vec![("Estimates per pipeline".into(), theme.heading)]];
+    for pipeline_view in board.pipelines.iter() {
+        let pipeline = &pipeline_view.pipeline;
+        let mut histogram = BTreeMap::new();
+        let mut total_points = 0.0;
+        let mut num_unestimated = 0;
+        for issue_ref in pipeline.issues.iter() {
+            match issue_ref.estimate {
+                Some(estimate) => {
+                    *histogram
+                        .entry(PointsKey::from(estimate.value))
+                        .or_insert(0) += 1;
+                    total_points += estimate.value;
+                }
+                None => num_unestimated += 1,
+            }
+        }
+
+        lines.push(vec![]);
+        lines.push(vec![
+            (pipeline.name.clone(), theme.heading),
+            (
+                format!(
+                    "  {} · {} · {} unestimated",
+                    humanize::count(pipeline.issues.len(), Noun::Issue),
+                    humanize::points(total_points),
+                    num_unestimated
+                ),
+                theme.dim,
+            ),
+        ]);
+        let max_count = histogram.values().copied().max().unwrap_or(0);
+        for (points, count) in histogram.iter() {
+            lines.push(vec![
+                (
+                    format!("{:>8} pts ", format_points(points.value())),
+                    theme.text,
+                ),
+                (
+                    format!(
+                        "{:<width$}",
+                        overlay::bar(*count as f64, max_count as f64, BAR_WIDTH),
+                        width = BAR_WIDTH
+                    ),
+                    theme.bar,
+                ),
+                (format!(" {}", count), theme.text),
+            ]);
+        }
+    }
+
+    let (workload, num_not_loaded) = workload(board, issues);
+    lines.push(vec![]);
+    lines.push(vec![("Points per assignee".into(), theme.heading)]);
+    let name_width = name_width(&workload);
+    let max_points = workload
+        .iter()
+        .map(|(_, load)| load.points)
+        .fold(0.0, f64::max);
+    for (name, load) in workload.iter() {
+        lines.push(vec![
+            (
+                format!("  {:<width$} ", name, width = name_width),
+                theme.text,
+            ),
+            (
+                format!(
+                    "{:<width$}",
+                    overlay::bar(load.points, max_points, BAR_WIDTH),
+                    width = BAR_WIDTH
+                ),
+                theme.bar,
+            ),
+            (
+                format!(
+                    " {} · {}",
+                    humanize::points(load.points),
+                    humanize::count(load.num_issues, Noun::Issue)
+                ),
+                theme.text,
+            ),
+        ]);
+    }
+    lines.extend(not_loaded_line(num_not_loaded, theme));
+
+    lines
+}
+
+/// Gauges of the open issues and points assigned to each person, heaviest
+/// first, to balance assignments during planning.
+pub fn workload_heat(
+    board: &BoardView,
+    issues: &HashMap<IssueNumber, FutureValue<Issue>>,
+    theme: &overlay::Theme,
+) -> Vec<Line> {
+    let (workload, num_not_loaded) = workload(board, issues);
+    if workload.is_empty() {
+        let mut lines = vec![vec![("No open issues loaded yet".into(), theme.dim)]];
+        lines.extend(not_loaded_line(num_not_loaded, theme));
+        return lines;
+    }
+
+    let name_width = name_width(&workload);
+    let max_points = workload
+        .iter()
+        .map(|(_, load)| load.points)
+        .fold(0.0, f64::max);
+    let max_issues = workload
+        .iter()
+        .map(|(_, load)| load.num_issues)
+        .max()
+        .unwrap_or(0);
+    let gauge = |label: &str, value: f64, max: f64, summary: String| {
+        vec![
+            (
+                format!("  {:<width$} ", label, width = name_width),
+                theme.text,
+            ),
+            (
+                format!(
+                    "{:<width$}",
+                    overlay::bar(value, max, BAR_WIDTH),
+                    width = BAR_WIDTH
+                ),
+                theme.bar,
+            ),
+            (format!(" {}", summary), theme.dim),
+        ]
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (name, load) in workload.iter() {
+        lines.push(vec![(name.clone(), theme.heading)]);
+        lines.push(gauge(
+            "points",
+            load.points,
+            max_points,
+            humanize::points(load.points),
+        ));
+        lines.push(gauge(
+            "issues",
+            load.num_issues as f64,
+            max_issues as f64,
+            humanize::count(load.num_issues, Noun::Issue),
+        ));
+    }
+    lines.extend(not_loaded_line(num_not_loaded, theme));
+    lines
+}
+
+/// The open issues and points assigned to one person.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Load {
+    points: f64,
+    num_issues: usize,
+}
+
+/// The load of each assignee across the open pipelines of the board, most
+/// points first, and the number of issues which aren't loaded yet so their
+/// assignees are unknown.
+fn workload(
+    board: &BoardView,
+    issues: &HashMap<IssueNumber, FutureValue<Issue>>,
+) -> (Vec<(String, Load)>, usize) {
+    let mut workload: BTreeMap<String, Load> = BTreeMap::new();
+    let mut num_not_loaded = 0;
+    for pipeline_view in board
+        .pipelines
+        .iter()
+        .filter(|pipeline_view| pipeline_view.pipeline.kind() != PipelineKind::Closed)
+    {
+        for issue_ref in pipeline_view.pipeline.issues.iter() {
+            let points = issue_ref.estimate.map_or(0.0, |estimate| estimate.value);
+            let issue = match issues.get(&issue_ref.number) {
+                Some(FutureValue::Ready(issue)) => issue,
+                _ => {
+                    num_not_loaded += 1;
+                    continue;
+                }
+            };
+            let assignees: Vec<_> = if issue.assignees.is_empty() {
+                vec![UNASSIGNED.to_owned()]
+            } else {
+                issue
+                    .assignees
+                    .iter()
+                    .map(|assignee| format!("@{}", assignee.login))
+                    .collect()
+            };
+            for assignee in assignees {
+                let load = workload.entry(assignee).or_default();
+                load.points += points;
+                load.num_issues += 1;
+            }
+        }
+    }
+
+    let mut workload: Vec<_> = workload.into_iter().collect();
+    workload.sort_by(|(_, left), (_, right)| {
+        right
+            .points
+            .partial_cmp(&left.points)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    (workload, num_not_loaded)
+}
+
+fn name_width(workload: &[(String, Load)]) -> usize {
+    workload
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
+fn not_loaded_line(num_not_loaded: usize, theme: &overlay::Theme) -> Option<Line> {
+    if num_not_loaded > 0 {
+        Some(vec![(
+            format!(
+                "  ({} not loaded yet)",
+                humanize::count(num_not_loaded, Noun::Issue)
+            ),
+            theme.dim,
+        )])
+    } else {
+        None
+    }
+}
+
+pub fn format_points(points: f64) -> String {
+    if points.fract() == 0.0 {
+        format!("{}", points as i64)
+    } else {
+        format!("{:.1}", points)
+    }
+}
+
+/// Estimates are floats, this gives them a total order (to a hundredth of a
+/// point) so they can be used as keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct PointsKey(i64);
+
+impl PointsKey {
+    fn value(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+}
+
+impl From<f64> for PointsKey {
+    fn from(points: f64) -> Self {
+        Self((points * 100.0).round() as i64)
+    }
+}
+
+const BAR_WIDTH: usize = 30;
+const UNASSIGNED: &str = "(unassigned)";
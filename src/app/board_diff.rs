@@ -0,0 +1,141 @@
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+
+use super::BoardView;
+use crate::{
+    github::{IssueNumber, RepoId},
+    zenhub::{IssueRef, PipelineKind},
+};
+
+/// How an issue changed between two loads of the board.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChangeKind {
+    /// The issue wasn't on the board before, it's now in the pipeline.
+    New(String),
+    Moved {
+        from: String,
+        to: String,
+    },
+    /// Moved to the Closed pipeline.
+    Closed,
+    /// The issue left the board, e.g. it was transferred to another repo.
+    Removed,
+    Estimated {
+        from: Option<f64>,
+        to: Option<f64>,
+    },
+}
+
+/// A change to the board noticed when reloading it, listed in the "what
+/// changed" overlay.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoardChange {
+    pub issue_ref: IssueRef,
+    pub kind: ChangeKind,
+    pub at: DateTime<Local>,
+}
+
+impl BoardChange {
+    pub fn description(&self) -> String {
+        match self.kind {
+            ChangeKind::New(ref pipeline) => format!("new in {}", pipeline),
+            ChangeKind::Moved { ref from, ref to } => format!("moved {} → {}", from, to),
+            ChangeKind::Closed => "closed".into(),
+            ChangeKind::Removed => "left the board".into(),
+            ChangeKind::Estimated { from, to } => {
+                format!("estimate {} → {}", points(from), points(to))
+            }
+        }
+    }
+}
+
+fn points(estimate: Option<f64>) -> String {
+    estimate.map_or_else(|| "none".into(), |estimate| estimate.to_string())
+}
+
+struct Location<'a> {
+    pipeline: &'a str,
+    kind: PipelineKind,
+    estimate: Option<f64>,
+}
+
+/// Where each issue of the board is, keyed by its repo as workspace boards
+/// can have issues of several repos with the same number.
+fn locations(board: &BoardView) -> HashMap<(Option<RepoId>, IssueNumber), Location<'_>> {
+    board
+        .pipelines
+        .iter()
+        .flat_map(|pipeline| {
+            let pipeline = &pipeline.pipeline;
+            pipeline.issues.iter().map(move |issue_ref| {
+                (
+                    (issue_ref.repo_id, issue_ref.number),
+                    Location {
+                        pipeline: &pipeline.name,
+                        kind: pipeline.kind(),
+                        estimate: issue_ref.estimate.map(|estimate| estimate.value),
+                    },
+                )
+            })
+        })
+        .collect()
+}
+
+/// The changes from the `before` board to the `after` one, in the order of
+/// the cards on the `after` board, followed by the issues which left it.
+pub fn changes(before: &BoardView, after: &BoardView, at: DateTime<Local>) -> Vec<BoardChange> {
+    let before_locations = locations(before);
+    let after_locations = locations(after);
+    let mut changes = Vec::new();
+    for pipeline in after.pipelines.iter() {
+        for issue_ref in pipeline.pipeline.issues.iter() {
+            let after_location = &after_locations[&(issue_ref.repo_id, issue_ref.number)];
+            let mut change = |kind| {
+                changes.push(BoardChange {
+                    issue_ref: issue_ref.clone(),
+                    kind,
+                    at,
+                })
+            };
+            let before_location = match before_locations.get(&(issue_ref.repo_id, issue_ref.number))
+            {
+                Some(before_location) => before_location,
+                None => {
+                    change(ChangeKind::New(after_location.pipeline.to_owned()));
+                    continue;
+                }
+            };
+            if before_location.pipeline != after_location.pipeline {
+                change(if after_location.kind == PipelineKind::Closed {
+                    ChangeKind::Closed
+                } else {
+                    ChangeKind::Moved {
+                        from: before_location.pipeline.to_owned(),
+                        to: after_location.pipeline.to_owned(),
+                    }
+                });
+            }
+            if before_location.estimate != after_location.estimate {
+                change(ChangeKind::Estimated {
+                    from: before_location.estimate,
+                    to: after_location.estimate,
+                });
+            }
+        }
+    }
+    changes.extend(
+        before
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| pipeline.pipeline.issues.iter())
+            .filter(|issue_ref| {
+                !after_locations.contains_key(&(issue_ref.repo_id, issue_ref.number))
+            })
+            .map(|issue_ref| BoardChange {
+                issue_ref: issue_ref.clone(),
+                kind: ChangeKind::Removed,
+                at,
+            }),
+    );
+    changes
+}
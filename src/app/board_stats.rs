@@ -0,0 +1,90 @@
+use im::hashmap::HashMap;
+
+use super::{
+    humanize::{self, Noun},
+    BoardView, FutureValue,
+};
+use crate::{
+    github::{Issue, IssueNumber, PullRequest, User},
+    zenhub::PipelineKind,
+};
+
+/// Board-wide counts for the statistics header. Counts which need the Github
+/// side of an issue only include the issues loaded so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BoardStats {
+    pub num_open: usize,
+    pub num_loaded: usize,
+    pub points: f64,
+    pub awaiting_review: usize,
+    pub blocked: usize,
+    pub assigned_to_me: usize,
+}
+
+impl BoardStats {
+    pub fn new(
+        board: &BoardView,
+        issues: &HashMap<IssueNumber, FutureValue<Issue>>,
+        pull_requests: &HashMap<IssueNumber, PullRequest>,
+        user: &User,
+    ) -> Self {
+        let mut stats = Self::default();
+        let open_pipelines = board
+            .pipelines
+            .iter()
+            .filter(|pipeline_view| pipeline_view.pipeline.kind() != PipelineKind::Closed);
+        for issue_ref in
+            open_pipelines.flat_map(|pipeline_view| pipeline_view.pipeline.issues.iter())
+        {
+            stats.num_open += 1;
+            stats.points += issue_ref.estimate.map_or(0.0, |estimate| estimate.value);
+
+            let issue = match issues.get(&issue_ref.number) {
+                Some(FutureValue::Ready(issue)) => issue,
+                _ => continue,
+            };
+            stats.num_loaded += 1;
+            let awaiting_review = pull_requests
+                .get(&issue.number)
+                .map_or(false, |pull_request| {
+                    !pull_request.requested_reviewers.is_empty()
+                        || !pull_request.requested_teams.is_empty()
+                });
+            if issue.pull_request.is_some() && awaiting_review {
+                stats.awaiting_review += 1;
+            }
+            if issue
+                .labels
+                .iter()
+                .any(|label| label.name.to_lowercase().contains("blocked"))
+            {
+                stats.blocked += 1;
+            }
+            if issue
+                .assignees
+                .iter()
+                .any(|assignee| assignee.login == user.login)
+            {
+                stats.assigned_to_me += 1;
+            }
+        }
+        stats
+    }
+
+    /// The two lines of the header.
+    pub fn lines(&self) -> String {
+        format!(
+            " {} open · {} · {} awaiting review\n \
+             {} blocked · {} assigned to me · {}/{} loaded",
+            self.num_open,
+            humanize::points(self.points),
+            humanize::count(self.awaiting_review, Noun::PullRequest),
+            self.blocked,
+            self.assigned_to_me,
+            self.num_loaded,
+            humanize::count(self.num_open, Noun::Issue)
+        )
+    }
+}
+
+pub const HEADER_HEIGHT: usize = 2;
@@ -0,0 +1,51 @@
+use crate::{
+    github::{Issue, RepoFullName},
+    settings::ChangelogGroup,
+};
+
+/// Drafts a markdown changelog of the issues, in sections by label, e.g.
+/// features and fixes. An issue goes in the first section with one of its
+/// labels; issues without any go under "Other changes".
+pub fn render(
+    title: &str,
+    repo: &RepoFullName,
+    issues: &[Issue],
+    groups: &[ChangelogGroup],
+) -> String {
+    let mut sections: Vec<(&str, Vec<&Issue>)> = groups
+        .iter()
+        .map(|group| (group.title.as_str(), Vec::new()))
+        .chain(std::iter::once((OTHER_TITLE, Vec::new())))
+        .collect();
+    for issue in issues.iter() {
+        let section = groups
+            .iter()
+            .position(|group| {
+                issue.labels.iter().any(|label| {
+                    group
+                        .labels
+                        .iter()
+                        .any(|name| name.eq_ignore_ascii_case(&label.name))
+                })
+            })
+            .unwrap_or(groups.len());
+        sections[section].1.push(issue);
+    }
+
+    let mut markdown = format!("## {}\n", title);
+    for (section_title, issues) in sections.into_iter() {
+        if issues.is_empty() {
+            continue;
+        }
+        markdown.push_str(&format!("\n### {}\n\n", section_title));
+        for issue in issues {
+            markdown.push_str(&format!(
+                "- {} ([#{}](https://github.com/{}/issues/{}))\n",
+                issue.title, issue.number.0, repo.0, issue.number.0
+            ));
+        }
+    }
+    markdown
+}
+
+const OTHER_TITLE: &str = "Other changes";
@@ -0,0 +1,98 @@
+use im::{hashmap::HashMap, Vector};
+
+use super::FutureValue;
+use crate::github::{Issue, IssueNumber};
+
+/// The pull requests connected to each issue: those connected on Zenhub or
+/// from zentui and those whose description closes the issue with a keyword,
+/// e.g. `Fixes #12`, which Zenhub shows as connected too.
+pub fn connected_pull_requests(
+    issues: &HashMap<IssueNumber, FutureValue<Issue>>,
+    connected: &HashMap<IssueNumber, Vector<IssueNumber>>,
+) -> HashMap<IssueNumber, Vector<IssueNumber>> {
+    let mut connections = connected.clone();
+    for issue in issues.values() {
+        let pull_request = match issue {
+            FutureValue::Ready(issue) if issue.pull_request.is_some() => issue,
+            _ => continue,
+        };
+        for issue_number in closing_references(&pull_request.body) {
+            let pull_requests = connections.entry(issue_number).or_default();
+            if !pull_requests.contains(&pull_request.number) {
+                pull_requests.push_back(pull_request.number);
+            }
+        }
+    }
+    for (_, pull_requests) in connections.iter_mut() {
+        pull_requests.sort();
+    }
+    connections
+}
+
+/// The issues a pull request description closes using one of Github's
+/// keywords, e.g. `closes #12` or `Resolved #3`. References to issues in
+/// other repos, e.g. `closes owner/repo#3`, are left out.
+pub fn closing_references(body: &str) -> Vec<IssueNumber> {
+    let mut references = Vec::new();
+    let mut words = body.split_whitespace().peekable();
+    while let Some(word) = words.next() {
+        let keyword = word.trim_end_matches(':').to_lowercase();
+        if !CLOSING_KEYWORDS.contains(&keyword.as_str()) {
+            continue;
+        }
+        let number = words
+            .peek()
+            .and_then(|reference| reference.strip_prefix('#'))
+            .map(|reference| {
+                reference.trim_end_matches(|character: char| !character.is_ascii_digit())
+            })
+            .and_then(|digits| digits.parse().ok());
+        if let Some(number) = number {
+            let issue_number = IssueNumber(number);
+            if !references.contains(&issue_number) {
+                references.push(issue_number);
+            }
+        }
+    }
+    references
+}
+
+const CLOSING_KEYWORDS: [&str; 9] = [
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_in_any_case_with_or_without_a_colon() {
+        assert_eq!(
+            closing_references("Fixes #12, CLOSES #3 and resolved: #7"),
+            vec![IssueNumber(12), IssueNumber(3), IssueNumber(7)]
+        );
+    }
+
+    #[test]
+    fn trailing_punctuation_is_dropped_and_duplicates_too() {
+        assert_eq!(
+            closing_references("This fixes #12.\n\nAlso closes #4), really fixes #12!"),
+            vec![IssueNumber(12), IssueNumber(4)]
+        );
+    }
+
+    #[test]
+    fn no_references_without_a_keyword_or_a_number() {
+        assert_eq!(closing_references("See #12, related to #4"), vec![]);
+        assert_eq!(closing_references("Fixes the build, closes #"), vec![]);
+        assert_eq!(closing_references("fixes"), vec![]);
+    }
+
+    #[test]
+    fn references_to_other_repos_are_left_out() {
+        assert_eq!(
+            closing_references("closes owner/repo#3, fixes https://github.com/owner/repo/issues/5"),
+            vec![]
+        );
+    }
+}
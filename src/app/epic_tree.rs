@@ -0,0 +1,130 @@
+use im::{hashmap::HashMap, hashset::HashSet, Vector};
+
+use crate::{github::IssueNumber, zenhub::IssueRef};
+
+/// Where a card sits in the epic tree of its pipeline. Only epics with
+/// children in the same pipeline, and those children, get a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeNode {
+    pub depth: usize,
+    pub epic: Option<EpicNode>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EpicNode {
+    pub num_children: usize,
+    pub expanded: bool,
+}
+
+/// Reorders the cards of a pipeline so that the children of an epic come
+/// right after it, or are left out when the epic is collapsed. An issue in
+/// several epics is nested under the first one. Children in other pipelines
+/// keep their own cards there.
+pub fn arrange(
+    issue_refs: Vector<IssueRef>,
+    epics: &HashMap<IssueNumber, Vector<IssueNumber>>,
+    collapsed: &HashSet<IssueNumber>,
+) -> (Vector<IssueRef>, HashMap<IssueNumber, TreeNode>) {
+    let in_pipeline: HashSet<IssueNumber> = issue_refs
+        .iter()
+        .map(|issue_ref| issue_ref.number)
+        .collect();
+    let mut parents = HashMap::new();
+    for issue_ref in issue_refs.iter() {
+        for child in epics.get(&issue_ref.number).into_iter().flatten() {
+            if *child != issue_ref.number
+                && in_pipeline.contains(child)
+                && !parents.contains_key(child)
+            {
+                parents.insert(*child, issue_ref.number);
+            }
+        }
+    }
+
+    let mut tree = Tree {
+        issue_refs: &issue_refs,
+        parents: &parents,
+        collapsed,
+        arranged: Vector::new(),
+        nodes: HashMap::new(),
+        visited: HashSet::new(),
+    };
+    for issue_ref in issue_refs.iter() {
+        if !parents.contains_key(&issue_ref.number) {
+            tree.visit(issue_ref, 0);
+        }
+    }
+    // Epics which are children of each other have no root, show them flat
+    // rather than losing them.
+    for issue_ref in issue_refs.iter() {
+        if !tree.visited.contains(&issue_ref.number) {
+            tree.visit(issue_ref, 0);
+        }
+    }
+    (tree.arranged, tree.nodes)
+}
+
+struct Tree<'a> {
+    issue_refs: &'a Vector<IssueRef>,
+    parents: &'a HashMap<IssueNumber, IssueNumber>,
+    collapsed: &'a HashSet<IssueNumber>,
+    arranged: Vector<IssueRef>,
+    nodes: HashMap<IssueNumber, TreeNode>,
+    visited: HashSet<IssueNumber>,
+}
+
+impl<'a> Tree<'a> {
+    fn visit(&mut self, issue_ref: &IssueRef, depth: usize) {
+        if self.visited.insert(issue_ref.number).is_some() {
+            return;
+        }
+        self.arranged.push_back(issue_ref.clone());
+
+        let issue_refs = self.issue_refs;
+        let parents = self.parents;
+        let children: Vec<_> = issue_refs
+            .iter()
+            .filter(|child| parents.get(&child.number) == Some(&issue_ref.number))
+            .collect();
+        let expanded = !self.collapsed.contains(&issue_ref.number);
+        if depth > 0 || !children.is_empty() {
+            self.nodes.insert(
+                issue_ref.number,
+                TreeNode {
+                    depth,
+                    epic: if children.is_empty() {
+                        None
+                    } else {
+                        Some(EpicNode {
+                            num_children: children.len(),
+                            expanded,
+                        })
+                    },
+                },
+            );
+        }
+        for child in children {
+            if expanded {
+                self.visit(child, depth + 1);
+            } else {
+                self.hide(child);
+            }
+        }
+    }
+
+    /// Marks the descendants of a collapsed epic as visited, so they don't
+    /// come back as roots.
+    fn hide(&mut self, issue_ref: &IssueRef) {
+        if self.visited.insert(issue_ref.number).is_some() {
+            return;
+        }
+        let issue_refs = self.issue_refs;
+        let parents = self.parents;
+        for child in issue_refs
+            .iter()
+            .filter(|child| parents.get(&child.number) == Some(&issue_ref.number))
+        {
+            self.hide(child);
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! Drives the app without a terminal: keys go through `App::input_binding`
+//! and the messages they produce through `App::update`, so selecting, hiding
+//! and showing pipelines can be checked key by key. The clients don't reach
+//! Github or Zenhub, as the runtime they're spawned on is never run.
+
+use im::Vector;
+use std::sync::{Arc, Mutex};
+use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
+use zi::{BindingTransition, Component, Key, Position, Rect, Size};
+
+use super::{App, AppLink, Message, Properties, RefreshTrigger};
+use crate::{
+    attachment::Client as AttachmentClient,
+    crash::CrashReporter,
+    github::{
+        Client as GithubClient, IssueNumber, Repo, RepoFullName, RepoId, Token as GithubToken, User,
+    },
+    webhook::Client as WebhookClient,
+    zenhub::{Board, Client as ZenhubClient, IssueRef, Pipeline, Token as ZenhubToken},
+};
+
+struct Driver {
+    app: App,
+    pressed: Vec<Key>,
+    /// The names of the messages sent by keys so far, in order.
+    messages: Vec<&'static str>,
+    /// Holds the requests spawned by the app without running them.
+    _runtime: Runtime,
+}
+
+impl Driver {
+    fn new(pipeline_names: &[&str]) -> Self {
+        let runtime = RuntimeBuilder::new()
+            .basic_scheduler()
+            .build()
+            .expect("a runtime can be built");
+        let properties = Properties {
+            async_runtime: runtime.handle().clone(),
+            github_client: Arc::new(
+                GithubClient::new(GithubToken("github-token".into()), 1)
+                    .expect("a Github client can be built"),
+            ),
+            zenhub_client: Arc::new(
+                ZenhubClient::new(ZenhubToken("zenhub-token".into()))
+                    .expect("a Zenhub client can be built"),
+            ),
+            webhook_client: Arc::new(WebhookClient::new().expect("a webhook client can be built")),
+            attachment_client: Arc::new(
+                AttachmentClient::new().expect("an attachment client can be built"),
+            ),
+            repo: Repo {
+                id: RepoId(1),
+                full_name: RepoFullName("owner/repo".into()),
+                archived: false,
+                permissions: None,
+            },
+            user: User {
+                login: "user".into(),
+            },
+            settings: Default::default(),
+            settings_path: None,
+            state: Default::default(),
+            state_path: None,
+            stats: Default::default(),
+            stats_path: None,
+            cache_path: None,
+            logs: None,
+            crash_reporter: CrashReporter::new(None),
+            workspace: None,
+            next_repo: Arc::new(Mutex::new(None)),
+            startup_view: Default::default(),
+        };
+        let mut app = App::new(
+            properties,
+            Rect::new(Position::new(0, 0), Size::new(160, 48)),
+            AppLink::Queue(Arc::new(Mutex::new(Vec::new()))),
+        );
+        app.update(Message::LoadedBoard(Ok(board(pipeline_names))));
+        Self {
+            app,
+            pressed: Vec::new(),
+            messages: Vec::new(),
+            _runtime: runtime,
+        }
+    }
+
+    fn press(&mut self, key: Key) -> &mut Self {
+        self.pressed.push(key);
+        let binding = self.app.input_binding(&self.pressed);
+        if let BindingTransition::Clear = binding.transition {
+            self.pressed.clear();
+        }
+        if let Some(message) = binding.message {
+            self.messages.push(message.name());
+            self.app.update(message);
+        }
+        self
+    }
+
+    fn press_all(&mut self, keys: &[Key]) -> &mut Self {
+        for key in keys.iter().cloned() {
+            self.press(key);
+        }
+        self
+    }
+
+    fn selected_name(&self) -> &str {
+        &self.app.board.pipelines[self.app.board.selected_pipeline]
+            .pipeline
+            .name
+    }
+
+    fn shown_names(&self) -> Vec<&str> {
+        self.app
+            .board
+            .pipelines
+            .iter()
+            .filter(|pipeline| !pipeline.hidden)
+            .map(|pipeline| pipeline.pipeline.name.as_str())
+            .collect()
+    }
+
+    /// What must hold after any key: the selection is on the board and shown,
+    /// and there is always a pipeline shown to select.
+    fn assert_consistent(&self) {
+        let board = &self.app.board;
+        if board.pipelines.is_empty() {
+            assert_eq!(board.selected_pipeline, 0);
+            return;
+        }
+        assert!(
+            board.selected_pipeline < board.pipelines.len(),
+            "selected {} of {} pipelines",
+            board.selected_pipeline,
+            board.pipelines.len()
+        );
+        assert!(
+            !board.pipelines[board.selected_pipeline].hidden,
+            "hidden pipeline selected after {:?}",
+            self.messages
+        );
+    }
+}
+
+fn board(pipeline_names: &[&str]) -> Board {
+    Board {
+        pipelines: pipeline_names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| Pipeline {
+                id: format!("pipeline-{}", index),
+                name: (*name).to_owned(),
+                issues: Vector::new(),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn moving_between_pipelines_skips_hidden_ones() {
+    let mut driver = Driver::new(&["New Issues", "Backlog", "In Progress", "Done"]);
+    driver.press_all(&[Key::Char('l'), Key::Ctrl('h')]);
+    assert_eq!(driver.selected_name(), "New Issues");
+    assert_eq!(
+        driver.shown_names(),
+        vec!["New Issues", "In Progress", "Done"]
+    );
+
+    driver.press(Key::Right);
+    assert_eq!(driver.selected_name(), "In Progress");
+    driver.press(Key::Char('h'));
+    assert_eq!(driver.selected_name(), "New Issues");
+    driver.press_all(&[Key::Left, Key::Ctrl('b')]);
+    assert_eq!(driver.selected_name(), "New Issues");
+    driver.press_all(&[Key::Ctrl('f'), Key::Ctrl('f'), Key::Char('l')]);
+    assert_eq!(driver.selected_name(), "Done");
+    assert_eq!(
+        driver.messages,
+        vec![
+            "NextPipeline",
+            "HidePipeline",
+            "NextPipeline",
+            "PreviousPipeline",
+            "PreviousPipeline",
+            "PreviousPipeline",
+            "NextPipeline",
+            "NextPipeline",
+            "NextPipeline",
+        ]
+    );
+}
+
+#[test]
+fn the_closed_pipeline_starts_collapsed() {
+    let driver = Driver::new(&["Backlog", "Closed"]);
+    assert_eq!(driver.shown_names(), vec!["Backlog", "Closed"]);
+    assert!(!driver.app.board.pipelines[0].collapsed);
+    assert!(driver.app.board.pipelines[1].collapsed);
+
+    let driver = Driver::new(&["Closed"]);
+    assert_eq!(driver.shown_names(), vec!["Closed"]);
+    assert!(driver.app.board.pipelines[0].collapsed);
+    driver.assert_consistent();
+}
+
+#[test]
+fn the_last_pipeline_shown_is_not_hidden() {
+    let mut driver = Driver::new(&["Backlog", "In Progress"]);
+    driver.press_all(&[Key::Ctrl('h'), Key::Ctrl('h'), Key::Ctrl('h')]);
+    assert_eq!(driver.shown_names(), vec!["In Progress"]);
+    assert_eq!(driver.selected_name(), "In Progress");
+    driver.assert_consistent();
+}
+
+#[test]
+fn hiding_the_last_pipeline_selects_the_one_before() {
+    let mut driver = Driver::new(&["Backlog", "Review", "In Progress"]);
+    driver.press_all(&[
+        Key::Char('l'),
+        Key::Ctrl('h'),
+        Key::Char('l'),
+        Key::Ctrl('h'),
+    ]);
+    assert_eq!(driver.shown_names(), vec!["Backlog"]);
+    assert_eq!(driver.selected_name(), "Backlog");
+}
+
+#[test]
+fn c_x_c_h_shows_every_pipeline_and_keeps_the_selection() {
+    let mut driver = Driver::new(&["Backlog", "In Progress", "Closed"]);
+    driver.press_all(&[Key::Char('l'), Key::Ctrl('x'), Key::Ctrl('h')]);
+    assert_eq!(
+        driver.shown_names(),
+        vec!["Backlog", "In Progress", "Closed"]
+    );
+    assert_eq!(driver.selected_name(), "In Progress");
+    assert_eq!(driver.messages, vec!["NextPipeline", "ShowAllPipelines"]);
+
+    // Any other key after the prefix drops it, rather than hiding
+    driver.press_all(&[Key::Ctrl('x'), Key::Char('q'), Key::Ctrl('h')]);
+    assert_eq!(driver.shown_names(), vec!["Backlog", "Closed"]);
+}
+
+#[test]
+fn keys_on_an_empty_board_do_nothing() {
+    let mut driver = Driver::new(&[]);
+    driver.press_all(&[
+        Key::Char('l'),
+        Key::Char('h'),
+        Key::Ctrl('h'),
+        Key::Ctrl('x'),
+        Key::Ctrl('h'),
+    ]);
+    driver.assert_consistent();
+}
+
+#[test]
+fn a_refresh_without_the_selected_pipeline_selects_one_shown() {
+    let mut driver = Driver::new(&["Backlog", "In Progress", "Review", "Done"]);
+    driver.press_all(&[Key::Ctrl('h'), Key::Char('l')]);
+    assert_eq!(driver.selected_name(), "Review");
+
+    let mut refreshed = board(&["Backlog", "In Progress", "Review", "Done"]);
+    refreshed.pipelines.remove(2);
+    driver.press_all(&[Key::Char('g'), Key::Char('r')]);
+    driver
+        .app
+        .update(Message::Refreshed(RefreshTrigger::Key, Ok(refreshed)));
+    assert_eq!(driver.shown_names(), vec!["In Progress", "Done"]);
+    assert_eq!(driver.selected_name(), "In Progress");
+}
+
+#[test]
+fn the_selected_pipeline_stays_in_view_when_scrolling() {
+    let mut driver = Driver::new(&["A", "B", "C", "D", "E", "F", "Closed"]);
+    driver.press_all(&[Key::Char('l'), Key::Ctrl('h')]);
+    assert_eq!(driver.app.board.pipelines_in_view(2), vec![0, 2]);
+    driver.press_all(&[Key::Char('l'), Key::Char('l')]);
+    assert_eq!(driver.selected_name(), "D");
+    assert_eq!(driver.app.board.pipelines_in_view(2), vec![3, 4]);
+    // The last page is full, rather than just the last pipeline
+    driver.press_all(&[Key::Char('l'), Key::Char('l'), Key::Char('l')]);
+    assert_eq!(driver.selected_name(), "Closed");
+    assert_eq!(driver.app.board.pipelines_in_view(2), vec![5, 6]);
+    assert_eq!(
+        driver.app.board.pipelines_in_view(10),
+        vec![0, 2, 3, 4, 5, 6]
+    );
+}
+
+#[test]
+fn pipelines_are_restored_as_they_were_left() {
+    let mut driver = Driver::new(&["Backlog", "Review", "In Progress", "Closed"]);
+    driver.press_all(&[Key::Ctrl('h'), Key::Char('l'), Key::Char('x')]);
+    let saved = driver.app.board.saved_state();
+
+    // A pipeline added since then is shown, the rest are as they were
+    let mut driver = Driver::new(&["Backlog", "Review", "In Progress", "QA", "Closed"]);
+    driver.app.board.pipelines[3].pipeline.id = "pipeline-new".into();
+    driver.app.board.restore(&saved);
+    assert_eq!(
+        driver.shown_names(),
+        vec!["Review", "In Progress", "QA", "Closed"]
+    );
+    assert_eq!(driver.selected_name(), "In Progress");
+    assert!(driver.app.board.pipelines[2].collapsed);
+    assert!(driver.app.board.pipelines[4].collapsed);
+    driver.assert_consistent();
+}
+
+#[test]
+fn moving_an_issue_leaves_other_repos_issues_with_its_number() {
+    let mut driver = Driver::new(&["Backlog", "Done"]);
+    let issue_ref = |repo_id| IssueRef {
+        number: IssueNumber(5),
+        estimate: None,
+        position: None,
+        is_epic: false,
+        repo_id: Some(RepoId(repo_id)),
+    };
+    driver.app.board.pipelines[0].pipeline.issues = Vector::from(vec![issue_ref(2), issue_ref(1)]);
+    assert_eq!(
+        driver.app.board.move_issue(RepoId(1), IssueNumber(5), 1, 0),
+        Some((0, 1))
+    );
+    assert_eq!(
+        driver.app.board.pipelines[0].pipeline.issues,
+        Vector::from(vec![issue_ref(2)])
+    );
+    assert_eq!(
+        driver.app.board.pipelines[1].pipeline.issues,
+        Vector::from(vec![issue_ref(1)])
+    );
+}
+
+/// Boards of up to 8 pipelines, some of them closed, with a few hundred
+/// keys pressed on each. Generated from a fixed seed so failures reproduce.
+#[test]
+fn generated_key_sequences_keep_the_selection_shown() {
+    const NAMES: [&str; 4] = ["Backlog", "In Progress", "Closed", "Done"];
+    const KEYS: [Key; 10] = [
+        Key::Char('l'),
+        Key::Char('h'),
+        Key::Right,
+        Key::Left,
+        Key::Ctrl('f'),
+        Key::Ctrl('b'),
+        Key::Ctrl('h'),
+        Key::Char('x'),
+        Key::Ctrl('x'),
+        Key::Char('q'),
+    ];
+
+    let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut next = move |bound: u64| {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 33) % bound
+    };
+    for _ in 0..200 {
+        let names: Vec<&str> = (0..next(9))
+            .map(|_| NAMES[next(NAMES.len() as u64) as usize])
+            .collect();
+        let mut driver = Driver::new(&names);
+        driver.assert_consistent();
+        for _ in 0..300 {
+            let num_messages = driver.messages.len();
+            let key = next(KEYS.len() as u64) as usize;
+            driver.press_all(&KEYS[key..=key]);
+            driver.assert_consistent();
+            if !names.is_empty() {
+                assert!(!driver.shown_names().is_empty());
+            }
+            if driver.messages.len() > num_messages
+                && driver.messages.last() == Some(&"ShowAllPipelines")
+            {
+                assert_eq!(driver.shown_names(), names);
+                assert!(driver
+                    .app
+                    .board
+                    .pipelines
+                    .iter()
+                    .all(|pipeline| !pipeline.collapsed));
+            }
+        }
+    }
+}
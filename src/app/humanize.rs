@@ -0,0 +1,130 @@
+//! Counts written for people, e.g. `1 issue`, `3 open issues` or `1.5 pts`.
+//!
+//! The words and plural rules come from a [`Locale`], so a translation only
+//! has to say how its nouns are spelled and which plural form a count takes.
+
+use super::analytics;
+
+/// The things zentui counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Noun {
+    Issue,
+    Card,
+    Point,
+    Day,
+    PullRequest,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PluralForm {
+    One,
+    Other,
+}
+
+pub trait Locale {
+    /// The plural form used for `count` things.
+    fn plural_form(&self, count: f64) -> PluralForm;
+
+    fn noun(&self, noun: Noun, form: PluralForm) -> &'static str;
+
+    /// A whole number, e.g. with its digits grouped.
+    fn number(&self, number: usize) -> String;
+
+    /// Puts together a count, an optional word describing the things
+    /// counted and the noun, e.g. `3` `open` `issues`.
+    fn phrase(&self, number: &str, adjective: Option<&str>, noun: &str) -> String;
+}
+
+pub struct English;
+
+impl Locale for English {
+    fn plural_form(&self, count: f64) -> PluralForm {
+        if count == 1.0 {
+            PluralForm::One
+        } else {
+            PluralForm::Other
+        }
+    }
+
+    fn noun(&self, noun: Noun, form: PluralForm) -> &'static str {
+        match (noun, form) {
+            (Noun::Issue, PluralForm::One) => "issue",
+            (Noun::Issue, PluralForm::Other) => "issues",
+            (Noun::Card, PluralForm::One) => "card",
+            (Noun::Card, PluralForm::Other) => "cards",
+            (Noun::Point, PluralForm::One) => "pt",
+            (Noun::Point, PluralForm::Other) => "pts",
+            (Noun::Day, PluralForm::One) => "day",
+            (Noun::Day, PluralForm::Other) => "days",
+            (Noun::PullRequest, PluralForm::One) => "PR",
+            (Noun::PullRequest, PluralForm::Other) => "PRs",
+        }
+    }
+
+    fn number(&self, number: usize) -> String {
+        let digits = number.to_string();
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    fn phrase(&self, number: &str, adjective: Option<&str>, noun: &str) -> String {
+        match adjective {
+            Some(adjective) => format!("{} {} {}", number, adjective, noun),
+            None => format!("{} {}", number, noun),
+        }
+    }
+}
+
+/// The locale of the interface, English until zentui is translated.
+fn locale() -> &'static dyn Locale {
+    &English
+}
+
+/// E.g. `1 issue` or `1,204 issues`.
+pub fn count(count: usize, noun: Noun) -> String {
+    describe(count, None, noun)
+}
+
+/// A count with a word describing the things counted, e.g. `3 open issues`.
+pub fn count_with(count: usize, adjective: &str, noun: Noun) -> String {
+    describe(count, Some(adjective), noun)
+}
+
+/// Part of a whole, e.g. `3 of 10 issues`. The noun agrees with the whole.
+pub fn count_of(part: usize, whole: usize, noun: Noun) -> String {
+    let locale = locale();
+    format!(
+        "{} of {}",
+        locale.number(part),
+        locale.phrase(
+            &locale.number(whole),
+            None,
+            locale.noun(noun, locale.plural_form(whole as f64))
+        )
+    )
+}
+
+/// Story points, e.g. `1 pt` or `2.5 pts`.
+pub fn points(points: f64) -> String {
+    let locale = locale();
+    locale.phrase(
+        &analytics::format_points(points),
+        None,
+        locale.noun(Noun::Point, locale.plural_form(points)),
+    )
+}
+
+fn describe(count: usize, adjective: Option<&str>, noun: Noun) -> String {
+    let locale = locale();
+    locale.phrase(
+        &locale.number(count),
+        adjective,
+        locale.noun(noun, locale.plural_form(count as f64)),
+    )
+}
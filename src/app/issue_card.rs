@@ -1,5 +1,7 @@
-use std::rc::Rc;
-use unicode_width::UnicodeWidthStr;
+use chrono::{DateTime, Utc};
+use im::Vector;
+use std::{cmp, iter, mem, rc::Rc};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use zi::{
     components::{
         border::{Border, BorderProperties},
@@ -9,22 +11,126 @@ use zi::{
     Style,
 };
 
-use super::{Base16Theme, FutureValue};
-use crate::github::{Issue, IssueNumber};
+use super::{analytics, epic_tree::TreeNode, humanize, template, Base16Theme, FutureValue};
+use crate::{
+    github::{
+        CheckStatus, Issue, IssueNumber, IssueState, Label, PullRequestStatus, RepoFullName,
+        ReviewDecision,
+    },
+    settings::{Density, FocusEmphasis},
+};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Theme {
     pub number: Style,
     pub text: Style,
     pub border: Style,
+    pub mine: Style,
+    pub moved: Style,
+    pub matched: Style,
+    /// The footer of issues which weren't updated for a while.
+    pub stale: Style,
+    pub focus: FocusStyle,
 }
 
 impl From<&Base16Theme> for Theme {
     fn from(theme: &Base16Theme) -> Self {
         Self {
+            focus: FocusStyle::new(theme, &[]),
             number: Style::normal(theme.base0f, theme.base06),
             text: Style::normal(theme.base0f, theme.base05),
             border: Style::normal(theme.base0f, theme.base02),
+            mine: Style::bold(theme.base0f, theme.base0a),
+            moved: Style::bold(theme.base0f, theme.base0b),
+            matched: Style::bold(theme.base0f, theme.base09),
+            stale: Style::normal(theme.base0f, theme.base08),
+        }
+    }
+}
+
+/// How the selected card stands out from the others, shared by every card so
+/// the emphasis chosen in the settings is applied the same way everywhere.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FocusStyle {
+    pub border: Style,
+    pub inverse: bool,
+    pub bold_title: bool,
+    pub marker: Option<Style>,
+    pub tint: Option<Colour>,
+}
+
+impl FocusStyle {
+    pub fn new(theme: &Base16Theme, emphasis: &[FocusEmphasis]) -> Self {
+        Self {
+            border: Style::normal(theme.base00, theme.base05),
+            inverse: emphasis.contains(&FocusEmphasis::Inverse),
+            bold_title: emphasis.contains(&FocusEmphasis::BoldTitle),
+            marker: if emphasis.contains(&FocusEmphasis::Marker) {
+                Some(Style::normal(theme.base00, theme.base0d))
+            } else {
+                None
+            },
+            tint: if emphasis.contains(&FocusEmphasis::Tint) {
+                Some(theme.base01)
+            } else {
+                None
+            },
+        }
+    }
+
+    /// The style of the selected card's title, given the one it would have
+    /// if it wasn't selected.
+    pub fn title(&self, style: Style) -> Style {
+        let style = if self.inverse {
+            Style {
+                background: style.foreground,
+                foreground: style.background,
+                ..style
+            }
+        } else {
+            style
+        };
+        Style {
+            bold: style.bold || self.bold_title,
+            ..style
+        }
+    }
+
+    /// The theme of the selected card's content, on a lighter background
+    /// when tinted.
+    pub fn content_theme(&self, theme: &Rc<Theme>) -> Rc<Theme> {
+        match self.tint {
+            Some(tint) => Rc::new(Theme {
+                number: Style {
+                    background: tint,
+                    ..theme.number
+                },
+                text: Style {
+                    background: tint,
+                    ..theme.text
+                },
+                ..(**theme).clone()
+            }),
+            None => theme.clone(),
+        }
+    }
+}
+
+/// How the authenticated user relates to an issue, strongest first: a
+/// pending review request needs action, an assignment is owned work.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relationship {
+    ReviewRequested,
+    Assignee,
+    Author,
+}
+
+impl Relationship {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::ReviewRequested => "review",
+            Self::Assignee => "assigned",
+            Self::Author => "author",
         }
     }
 }
@@ -33,12 +139,49 @@ impl From<&Base16Theme> for Theme {
 pub struct Properties {
     pub theme: Rc<Theme>,
     pub issue_number: IssueNumber,
+    /// The repo of the issue, when it isn't the board's.
+    pub repo: Option<RepoFullName>,
     pub issue: FutureValue<Issue>,
+    /// The estimate in story points, if the issue has one.
+    pub estimate: Option<f64>,
+    pub relationship: Option<Relationship>,
+    pub tree_node: Option<TreeNode>,
+    /// The pull requests connected to the issue.
+    pub pull_requests: Vector<IssueNumber>,
+    /// Whether the pull request is approved and its checks pass, if the
+    /// issue is a pull request.
+    pub pull_request_status: Option<PullRequestStatus>,
+    /// The titles of the open releases the issue is part of.
+    pub releases: Vector<String>,
+    /// When the issue is due, if it has an SLA label.
+    pub sla_deadline: Option<DateTime<Utc>>,
+    /// Whether the issue waits on another issue which isn't closed.
+    pub blocked: bool,
+    /// Whether the issue wasn't updated for longer than the stale sweep
+    /// allows.
+    pub stale: bool,
+    /// Whether the card was just moved here by someone else.
+    pub moved: bool,
+    /// Whether the card is part of a multi-selection.
+    pub marked: bool,
+    /// How many of the epic's children in other pipelines are folded into
+    /// this card.
+    pub num_folded_children: usize,
+    /// Whether the card matches the search typed in the prompt row.
+    pub matched: bool,
+    /// The code to type to jump to the card, when jump codes are shown.
+    pub jump_code: Option<String>,
+    /// The template of the card's last line, if it has one.
+    pub footer: Option<Rc<String>>,
+    pub density: Density,
+    /// The most lines the title takes, `0` for no limit.
+    pub max_title_lines: usize,
     pub focused: bool,
 }
 
 pub struct IssueCard {
     properties: Properties,
+    frame: Rect,
 }
 
 pub enum Message {}
@@ -47,8 +190,8 @@ impl Component for IssueCard {
     type Message = Message;
     type Properties = Properties;
 
-    fn create(properties: Self::Properties, _frame: Rect, _link: ComponentLink<Self>) -> Self {
-        Self { properties }
+    fn create(properties: Self::Properties, frame: Rect, _link: ComponentLink<Self>) -> Self {
+        Self { properties, frame }
     }
 
     fn change(&mut self, properties: Self::Properties) -> ShouldRender {
@@ -60,21 +203,53 @@ impl Component for IssueCard {
         }
     }
 
+    fn resize(&mut self, frame: Rect) -> ShouldRender {
+        self.frame = frame;
+        ShouldRender::Yes
+    }
+
     fn view(&self) -> Layout {
         let Self {
             properties:
                 Properties {
-                    ref theme,
+                    theme: ref card_theme,
                     ref issue,
+                    ref pull_requests,
+                    ref releases,
+                    sla_deadline,
+                    blocked,
+                    stale,
+                    ref jump_code,
+                    ref repo,
+                    ref footer,
+                    density,
+                    max_title_lines,
+                    estimate,
+                    pull_request_status,
+                    relationship,
+                    tree_node,
+                    moved,
+                    marked,
+                    num_folded_children,
+                    matched,
                     focused,
                     issue_number,
                 },
-            ..
+            frame,
         } = *self;
+        let theme = if focused {
+            card_theme.focus.content_theme(card_theme)
+        } else {
+            card_theme.clone()
+        };
 
-        let (title, content) = match issue {
+        let reference = match repo {
+            Some(repo) => format!("{}#{}", repo.0, issue_number.0),
+            None => format!("#{}", issue_number.0),
+        };
+        let (mut title, content) = match issue {
             FutureValue::Pending => (
-                format!(" #{} ", issue_number.0),
+                format!(" {} ", reference),
                 layout::component_with_key_str::<Text>(
                     "issue-loading",
                     TextProperties::new()
@@ -82,22 +257,86 @@ impl Component for IssueCard {
                         .style(theme.number),
                 ),
             ),
+            FutureValue::Ready(issue) if density == Density::Minimal => (
+                format!(" {} ", reference),
+                layout::component_with_key_str::<Text>(
+                    "issue-text",
+                    TextProperties::new()
+                        .content(issue.title.clone())
+                        .style(theme.number),
+                ),
+            ),
             FutureValue::Ready(issue) => (
-                if issue.pull_request.is_some() {
-                    format!(" #{} ⎇  ", issue_number.0)
-                } else {
-                    format!(" #{} ", issue_number.0)
+                {
+                    let mut title = format!(" {} ", reference);
+                    if let Some(points) = estimate {
+                        title.push_str(&format!("{} ", humanize::points(points)));
+                    }
+                    if let Some(epic) = tree_node.and_then(|node| node.epic) {
+                        title.push_str(&format!(
+                            "{} {} ",
+                            if epic.expanded { "▾" } else { "▸" },
+                            epic.num_children
+                        ));
+                    }
+                    if num_folded_children > 0 {
+                        title.push_str(&format!("+{} elsewhere ", num_folded_children));
+                    }
+                    if issue.pull_request.is_some() {
+                        match pull_request_status.and_then(status_indicator) {
+                            Some(indicator) => title.push_str(&format!("⎇ {} ", indicator)),
+                            None => title.push_str("⎇  "),
+                        }
+                    }
+                    if !pull_requests.is_empty() {
+                        title.push_str(&format!(
+                            "⎇ {} ",
+                            pull_requests
+                                .iter()
+                                .map(|pull_request| format!("#{}", pull_request.0))
+                                .collect::<Vec<_>>()
+                                .join(",")
+                        ));
+                    }
+                    if !releases.is_empty() {
+                        title.push_str(&format!(
+                            "⚑ {} ",
+                            releases.iter().cloned().collect::<Vec<_>>().join(",")
+                        ));
+                    }
+                    if blocked {
+                        title.push_str("⊘ blocked ");
+                    }
+                    if let Some(deadline) = sla_deadline {
+                        let remaining = deadline - Utc::now();
+                        if remaining < chrono::Duration::zero() {
+                            title.push_str(&format!("⏱ {} over ", format_age(-remaining)));
+                        } else {
+                            title.push_str(&format!("⏱ {} left ", format_age(remaining)));
+                        }
+                    }
+                    title.push_str(&format!("@{} ", issue.user.login));
+                    if let Some(relationship) = relationship {
+                        title.push_str(&format!("[{}] ", relationship.label()));
+                    }
+                    title
                 },
                 layout::component_with_key_str::<IssueContent>(
                     "issue-content",
                     IssueContentProperties {
                         theme: theme.clone(),
                         issue: issue.clone(),
+                        footer: footer
+                            .as_ref()
+                            .map(|footer| render_footer(footer, issue, estimate, Utc::now())),
+                        stale,
+                        density,
+                        max_title_lines,
                     },
                 ),
             ),
             FutureValue::Error(message) => (
-                format!(" #{} ", issue_number.0),
+                format!(" {} ", reference),
                 layout::component_with_key_str::<Text>(
                     "issue-error",
                     TextProperties::new()
@@ -107,11 +346,57 @@ impl Component for IssueCard {
             ),
         };
 
-        layout::component::<Border>(
+        if marked {
+            title.insert_str(0, " ✓");
+        }
+        if let Some(jump_code) = jump_code {
+            title.insert_str(0, &format!(" [{}]", jump_code));
+        }
+
+        let title_style = if matched {
+            card_theme.matched
+        } else if relationship.is_some() {
+            card_theme.mine
+        } else {
+            card_theme.text
+        };
+        let card = layout::component::<Border>(
             BorderProperties::new(content)
-                .style(if focused { theme.text } else { theme.border })
-                .title(Some((title, theme.text))),
-        )
+                .style(match (focused, relationship) {
+                    (true, _) => card_theme.focus.border,
+                    (false, _) if moved => card_theme.moved,
+                    (false, _) if matched => card_theme.matched,
+                    (false, Some(_)) => card_theme.mine,
+                    (false, None) => card_theme.border,
+                })
+                .title(Some((
+                    title,
+                    if focused {
+                        card_theme.focus.title(title_style)
+                    } else {
+                        title_style
+                    },
+                ))),
+        );
+
+        // Unselected cards leave the marker's column blank, so that cards
+        // don't shift sideways as the selection moves.
+        match card_theme.focus.marker {
+            Some(marker_style) => {
+                let mut marker = Canvas::new(Size::new(MARKER_WIDTH, frame.size.height));
+                marker.clear(card_theme.border);
+                if focused {
+                    for y in 0..frame.size.height {
+                        marker.draw_str(0, y, marker_style, "▌");
+                    }
+                }
+                layout::row([
+                    layout::fixed(MARKER_WIDTH, marker.into()),
+                    layout::auto(card),
+                ])
+            }
+            None => card,
+        }
     }
 }
 
@@ -119,6 +404,10 @@ impl Component for IssueCard {
 pub struct IssueContentProperties {
     pub theme: Rc<Theme>,
     pub issue: Issue,
+    pub footer: Option<String>,
+    pub stale: bool,
+    pub density: Density,
+    pub max_title_lines: usize,
 }
 
 pub struct IssueContent {
@@ -154,28 +443,65 @@ impl Component for IssueContent {
                 IssueContentProperties {
                     ref theme,
                     ref issue,
+                    ref footer,
+                    stale,
+                    density,
+                    max_title_lines,
                 },
             frame,
             ..
         } = *self;
 
-        let issue_text = layout::auto(layout::component_with_key_str::<Text>(
-            "issue-text",
-            TextProperties::new()
-                .content(issue.title.clone())
-                .style(theme.number)
-                .wrap(TextWrap::Word),
-        ));
+        let issue_text = if max_title_lines == 0 || frame.size.width == 0 {
+            layout::auto(layout::component_with_key_str::<Text>(
+                "issue-text",
+                TextProperties::new()
+                    .content(issue.title.clone())
+                    .style(theme.number)
+                    .wrap(TextWrap::Word),
+            ))
+        } else {
+            let lines = truncated_title(&issue.title, frame.size.width, max_title_lines);
+            layout::fixed(
+                lines.len(),
+                layout::component_with_key_str::<Text>(
+                    "issue-text",
+                    TextProperties::new()
+                        .content(lines.join("\n"))
+                        .style(theme.number),
+                ),
+            )
+        };
+
+        // There is no room for anything but the title on a tiny card.
+        if frame.size.width == 0 || frame.size.height <= 2 {
+            return layout::column([issue_text]);
+        }
 
         let mut position = Position::zero();
         let mut label_canvas = Canvas::new(frame.size);
         label_canvas.clear(theme.text);
 
-        for label in issue.labels.iter() {
-            let label_name = format!(" {} ", label.name);
+        // Assignees follow the labels, by login or by their initials when the
+        // login doesn't fit on the card
+        let mut chips: Vec<_> = issue
+            .labels
+            .iter()
+            .map(|label| (format!(" {} ", label.name), label_colour(label)))
+            .collect();
+        chips.extend(issue.assignees.iter().map(|assignee| {
+            let login = format!(" @{} ", assignee.login);
+            let chip = if UnicodeWidthStr::width(login.as_str()) <= frame.size.width {
+                login
+            } else {
+                format!(" {} ", initials(&assignee.login))
+            };
+            (chip, user_colour(&assignee.login))
+        }));
+        for (label_name, label_colour) in chips {
             let label_style = Style::bold(
-                label.color,
-                if is_light_colour(&label.color) {
+                label_colour,
+                if is_light_colour(&label_colour) {
                     Colour::black()
                 } else {
                     Colour::white()
@@ -203,13 +529,265 @@ impl Component for IssueContent {
             label_canvas.min_size().height + 1,
         ));
 
-        layout::column([
-            issue_text,
-            layout::fixed(label_canvas.min_size().height + 1, label_canvas.into()),
-        ])
+        let labels = layout::fixed(label_canvas.min_size().height + 1, label_canvas.into());
+        let mut items = vec![issue_text, labels];
+        if density == Density::Detailed && frame.size.height > BODY_EXCERPT_HEIGHT + 2 {
+            items.push(layout::fixed(
+                BODY_EXCERPT_HEIGHT,
+                layout::component_with_key_str::<Text>(
+                    "issue-body",
+                    TextProperties::new()
+                        .content(body_excerpt(&issue.body))
+                        .style(theme.text)
+                        .wrap(TextWrap::Word),
+                ),
+            ));
+        }
+        // The footer needs a line of its own below the title and labels
+        if let Some(footer) = footer.as_ref().filter(|_| frame.size.height > 3) {
+            items.push(layout::fixed(
+                1,
+                layout::component_with_key_str::<Text>(
+                    "issue-footer",
+                    TextProperties::new()
+                        .content(footer.clone())
+                        .style(if stale { theme.stale } else { theme.number }),
+                ),
+            ));
+        }
+        layout::column_iter(items)
+    }
+}
+
+/// Wraps a title at word boundaries to at most `max_lines` lines of `width`,
+/// cutting out its middle if it's longer, as both the start and the end of a
+/// title tend to say the most, e.g. `Crash when … on Windows`.
+pub fn truncated_title(title: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let lines = wrap_words(title, width);
+    if lines.len() <= max_lines {
+        return lines;
+    }
+
+    // Wrapping wastes some of each line, shrink the kept text until it fits
+    let characters: Vec<char> = title.chars().collect();
+    let mut budget = (width * max_lines).min(characters.len());
+    while budget > 1 {
+        let head = budget / 2;
+        let tail = budget - head - 1;
+        let truncated: String = characters[..head]
+            .iter()
+            .collect::<String>()
+            .trim_end()
+            .chars()
+            .chain(iter::once('…'))
+            .chain(
+                characters[characters.len() - tail..]
+                    .iter()
+                    .collect::<String>()
+                    .trim_start()
+                    .chars(),
+            )
+            .collect();
+        let lines = wrap_words(&truncated, width);
+        if lines.len() <= max_lines {
+            return lines;
+        }
+        budget -= cmp::max(1, width / 4);
+    }
+    vec!["…".into()]
+}
+
+/// Greedily wraps text at spaces to lines of at most `width` columns,
+/// splitting words wider than a line.
+pub fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let width = cmp::max(width, 1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+    for word in text.split_whitespace() {
+        let mut word = word;
+        loop {
+            let word_width = UnicodeWidthStr::width(word);
+            let separator = if line.is_empty() { 0 } else { 1 };
+            if line_width + separator + word_width <= width {
+                if separator == 1 {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                line_width += separator + word_width;
+                break;
+            } else if !line.is_empty() {
+                lines.push(mem::take(&mut line));
+                line_width = 0;
+            } else {
+                // A word wider than a whole line is split wherever it ends
+                let mut split = 0;
+                let mut split_width = 0;
+                for (index, character) in word.char_indices() {
+                    let character_width = UnicodeWidthChar::width(character).unwrap_or(0);
+                    if split_width + character_width > width && split > 0 {
+                        break;
+                    }
+                    split = index + character.len_utf8();
+                    split_width += character_width;
+                }
+                lines.push(word[..split].to_owned());
+                word = &word[split..];
+                if word.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// `✗` if the pull request has failing checks or changes requested, `●` if it
+/// waits for either and `✓` if it's good to merge. `None` when there's
+/// nothing to wait for, e.g. no checks and no required reviews.
+fn status_indicator(status: PullRequestStatus) -> Option<char> {
+    let PullRequestStatus { review, checks } = status;
+    if checks == Some(CheckStatus::Failure) || review == Some(ReviewDecision::ChangesRequested) {
+        Some('✗')
+    } else if checks == Some(CheckStatus::Pending) || review == Some(ReviewDecision::ReviewRequired)
+    {
+        Some('●')
+    } else if checks.is_some() || review.is_some() {
+        Some('✓')
+    } else {
+        None
+    }
+}
+
+/// The start of the issue's description shown on detailed cards, without the
+/// blank lines between its paragraphs.
+fn body_excerpt(body: &str) -> String {
+    body.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(BODY_EXCERPT_HEIGHT)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders the footer template of a card, see `Settings::card_footer` for the
+/// placeholders.
+fn render_footer(
+    template: &str,
+    issue: &Issue,
+    estimate: Option<f64>,
+    now: DateTime<Utc>,
+) -> String {
+    let non_empty = |text: String| Some(text).filter(|text| !text.is_empty());
+    template::render(template, |name| match name {
+        "number" => Some(issue.number.0.to_string()),
+        "author" => non_empty(issue.user.login.clone()).map(|login| format!("@{}", login)),
+        "assignees" => non_empty(
+            issue
+                .assignees
+                .iter()
+                .map(|assignee| format!("@{}", assignee.login))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        "estimate" => estimate.map(analytics::format_points),
+        "age" => issue
+            .created_at
+            .map(|created_at| format_age(now.signed_duration_since(created_at))),
+        "updated" => issue
+            .updated_at
+            .map(|updated_at| format_age(now.signed_duration_since(updated_at))),
+        "comments" => Some(issue.comments.to_string()),
+        "reactions" => Some(issue.reactions.total_count.to_string()),
+        "state" => Some(
+            match issue.state {
+                IssueState::Open => "open",
+                IssueState::Closed => "closed",
+            }
+            .into(),
+        ),
+        "labels" => non_empty(
+            issue
+                .labels
+                .iter()
+                .map(|label| label.name.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        // Unknown placeholders are shown as typed, to make typos easy to spot
+        unknown => Some(format!("{{{}}}", unknown)),
+    })
+}
+
+/// A short age, e.g. `3d`, in the largest unit it has at least one of.
+pub fn format_age(age: chrono::Duration) -> String {
+    if age.num_weeks() > 0 {
+        format!("{}w", age.num_weeks())
+    } else if age.num_days() > 0 {
+        format!("{}d", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}m", age.num_minutes().max(0))
+    }
+}
+
+pub fn label_colour(label: &Label) -> Colour {
+    Colour {
+        red: label.color.red,
+        green: label.color.green,
+        blue: label.color.blue,
     }
 }
 
+/// The colour of a user's chip on cards, the same for a login every time.
+fn user_colour(login: &str) -> Colour {
+    // FNV-1a, as the standard library's hashers are seeded per process
+    let hash = login.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let (red, green, blue) = USER_COLOURS[(hash % USER_COLOURS.len() as u64) as usize];
+    Colour::rgb(red, green, blue)
+}
+
+/// Up to two letters standing for a login, e.g. `MC` for `mcobzarenco` or
+/// `JD` for `jane-doe`.
+fn initials(login: &str) -> String {
+    let mut parts = login
+        .split(|character: char| !character.is_alphanumeric())
+        .filter(|part| !part.is_empty());
+    let initials: String = match (parts.next(), parts.next()) {
+        (Some(first), Some(second)) => first
+            .chars()
+            .take(1)
+            .chain(second.chars().take(1))
+            .collect(),
+        (Some(first), None) => first.chars().take(2).collect(),
+        _ => login.chars().take(2).collect(),
+    };
+    initials.to_uppercase()
+}
+
 fn is_light_colour(colour: &Colour) -> bool {
     (colour.red as f32 * 0.299 + colour.green as f32 * 0.587 + colour.blue as f32 * 0.114) > 146.0
 }
+
+/// The height of the description's excerpt on detailed cards.
+pub const BODY_EXCERPT_HEIGHT: usize = 3;
+
+const MARKER_WIDTH: usize = 1;
+
+/// The colours of assignee chips, picked by login.
+const USER_COLOURS: [(u8, u8, u8); 8] = [
+    (0x1f, 0x77, 0xb4),
+    (0xff, 0x7f, 0x0e),
+    (0x2c, 0xa0, 0x2c),
+    (0xd6, 0x27, 0x28),
+    (0x94, 0x67, 0xbd),
+    (0x8c, 0x56, 0x4b),
+    (0xe3, 0x77, 0xc2),
+    (0x17, 0xbe, 0xcf),
+];
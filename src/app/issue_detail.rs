@@ -0,0 +1,340 @@
+use chrono::Local;
+use im::Vector;
+use std::rc::Rc;
+use zi::{
+    components::text::{Text, TextProperties, TextWrap},
+    layout, Component, ComponentLink, Layout, Rect, ShouldRender, Style,
+};
+
+use super::{Base16Theme, FutureValue};
+use crate::github::{Comment, Issue, IssueNumber, IssueState};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub title: Style,
+    pub meta: Style,
+    pub body: Style,
+}
+
+impl From<&Base16Theme> for Theme {
+    fn from(theme: &Base16Theme) -> Self {
+        Self {
+            title: Style::bold(theme.base00, theme.base0d),
+            meta: Style::normal(theme.base00, theme.base04),
+            body: Style::normal(theme.base00, theme.base05),
+        }
+    }
+}
+
+/// An image embedded in the body of an issue, usually a screenshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageLink {
+    pub alt: String,
+    pub url: String,
+}
+
+/// The images in an issue's markdown body, embedded either as `![alt](url)`,
+/// as `![alt][label]` with the URL defined further down, or as an `<img>`
+/// tag, which is what Github inserts for pasted screenshots. Relative URLs
+/// are left out, there is nothing they could be downloaded from.
+pub fn image_links(body: &str) -> Vec<ImageLink> {
+    let mut links = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("![") {
+        rest = &rest[start + 2..];
+        let alt_end = match rest.find(']') {
+            Some(alt_end) => alt_end,
+            None => break,
+        };
+        let alt = &rest[..alt_end];
+        rest = &rest[alt_end + 1..];
+        let url = if let Some(inline) = rest.strip_prefix('(') {
+            let url_end = match inline.find(')') {
+                Some(url_end) => url_end,
+                None => break,
+            };
+            rest = &inline[url_end + 1..];
+            // Drop the optional title, e.g. `![alt](url "title")`
+            inline[..url_end].split_whitespace().next()
+        } else if let Some(reference) = rest.strip_prefix('[') {
+            let label_end = match reference.find(']') {
+                Some(label_end) => label_end,
+                None => break,
+            };
+            rest = &reference[label_end + 1..];
+            // `![alt][]` is labelled by its alt text
+            let label = &reference[..label_end];
+            link_definition(body, if label.is_empty() { alt } else { label })
+        } else {
+            link_definition(body, alt)
+        };
+        if let Some(url) = url.filter(|url| is_absolute(url)) {
+            links.push(ImageLink {
+                alt: alt.into(),
+                url: url.into(),
+            });
+        }
+    }
+
+    let mut rest = body;
+    while let Some(start) = rest.find("<img") {
+        rest = &rest[start + 4..];
+        let tag_end = rest.find('>').unwrap_or_else(|| rest.len());
+        let tag = &rest[..tag_end];
+        if let Some(url) = attribute(tag, "src").filter(|url| is_absolute(url)) {
+            links.push(ImageLink {
+                alt: attribute(tag, "alt").unwrap_or_default().into(),
+                url: url.into(),
+            });
+        }
+        rest = &rest[tag_end..];
+    }
+    links
+}
+
+/// The URL of a reference-style link, defined on a line of its own as
+/// `[label]: url`. Labels are matched regardless of case, as on Github.
+fn link_definition<'a>(body: &'a str, label: &str) -> Option<&'a str> {
+    body.lines().find_map(|line| {
+        let definition = line.trim_start().strip_prefix('[')?;
+        let label_end = definition.find("]:")?;
+        if !definition[..label_end]
+            .trim()
+            .eq_ignore_ascii_case(label.trim())
+        {
+            return None;
+        }
+        let url = definition[label_end + 2..].split_whitespace().next()?;
+        Some(url.trim_start_matches('<').trim_end_matches('>'))
+    })
+}
+
+fn is_absolute(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let prefix = format!("{}=\"", name);
+    let start = tag.find(&prefix)? + prefix.len();
+    let end = tag[start..].find('"')?;
+    Some(&tag[start..start + end])
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Properties {
+    pub theme: Rc<Theme>,
+    pub issue_number: Option<IssueNumber>,
+    /// Written out in full, as zi can't attach an OSC 8 hyperlink to the
+    /// cells of the pane, so that terminals which detect URLs make it
+    /// clickable.
+    pub url: Option<String>,
+    pub issue: FutureValue<Issue>,
+    pub comments: FutureValue<Vector<Comment>>,
+}
+
+/// The comment thread of an issue, appended to its body.
+fn comment_thread(comments: &FutureValue<Vector<Comment>>) -> String {
+    let comments = match comments {
+        FutureValue::Pending => return "\n\nLoading comments...".into(),
+        FutureValue::Error(error) => return format!("\n\nCould not load comments: {}", error),
+        FutureValue::Ready(comments) if comments.is_empty() => return String::new(),
+        FutureValue::Ready(comments) => comments,
+    };
+    let mut thread = String::new();
+    for comment in comments.iter() {
+        thread.push_str(&format!(
+            "\n\n── @{} · {}\n{}",
+            comment.user.login,
+            comment
+                .created_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M"),
+            comment.body.trim()
+        ));
+    }
+    thread
+}
+
+pub struct IssueDetail {
+    properties: Properties,
+}
+
+impl Component for IssueDetail {
+    type Message = ();
+    type Properties = Properties;
+
+    fn create(properties: Self::Properties, _frame: Rect, _link: ComponentLink<Self>) -> Self {
+        Self { properties }
+    }
+
+    fn change(&mut self, properties: Self::Properties) -> ShouldRender {
+        if self.properties != properties {
+            self.properties = properties;
+            ShouldRender::Yes
+        } else {
+            ShouldRender::No
+        }
+    }
+
+    fn view(&self) -> Layout {
+        let Self {
+            properties:
+                Properties {
+                    ref theme,
+                    ref issue,
+                    ref comments,
+                    ref url,
+                    issue_number,
+                },
+        } = *self;
+
+        let message = |content: String| {
+            layout::component_with_key_str::<Text>(
+                "detail-message",
+                TextProperties::new()
+                    .content(content)
+                    .style(theme.meta)
+                    .wrap(TextWrap::Word),
+            )
+        };
+
+        let issue = match (issue_number, issue) {
+            (None, _) => return message(" No issue selected".into()),
+            (Some(issue_number), FutureValue::Pending) => {
+                return message(format!(" Loading issue #{}...", issue_number.0))
+            }
+            (_, FutureValue::Error(error)) => return message(format!(" {}", error)),
+            (_, FutureValue::Ready(issue)) => issue,
+        };
+
+        let mut meta = format!(
+            " #{} · {}",
+            issue.number.0,
+            match issue.state {
+                IssueState::Open => "open",
+                IssueState::Closed => "closed",
+            }
+        );
+        if issue.pull_request.is_some() {
+            meta.push_str(" · pull request");
+        }
+        if !issue.assignees.is_empty() {
+            meta.push_str(" · ");
+            meta.push_str(
+                &issue
+                    .assignees
+                    .iter()
+                    .map(|assignee| format!("@{}", assignee.login))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
+        if let Some(url) = url {
+            meta.push_str(&format!("\n {}", url));
+        }
+
+        let images = image_links(&issue.body);
+        let mut images_text = String::from(" Images (p to preview)\n");
+        for (index, image) in images.iter().enumerate() {
+            images_text.push_str(&format!(" {}. {} {}\n", index + 1, image.alt, image.url));
+        }
+
+        layout::column([
+            layout::fixed(
+                2,
+                layout::component_with_key_str::<Text>(
+                    "detail-title",
+                    TextProperties::new()
+                        .content(format!(" {}", issue.title))
+                        .style(theme.title)
+                        .wrap(TextWrap::Word),
+                ),
+            ),
+            layout::fixed(
+                if url.is_some() { 3 } else { 2 },
+                layout::component_with_key_str::<Text>(
+                    "detail-meta",
+                    TextProperties::new().content(meta).style(theme.meta),
+                ),
+            ),
+            layout::auto(layout::component_with_key_str::<Text>(
+                "detail-body",
+                TextProperties::new()
+                    .content(format!("{}{}", issue.body, comment_thread(comments)))
+                    .style(theme.body)
+                    .wrap(TextWrap::Word),
+            )),
+            layout::fixed(
+                if images.is_empty() {
+                    0
+                } else {
+                    images.len() + 1
+                },
+                layout::component_with_key_str::<Text>(
+                    "detail-images",
+                    TextProperties::new().content(images_text).style(theme.meta),
+                ),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(alt: &str, url: &str) -> ImageLink {
+        ImageLink {
+            alt: alt.into(),
+            url: url.into(),
+        }
+    }
+
+    #[test]
+    fn inline_images_and_img_tags() {
+        let body = "Broken:\n![the board](https://example.com/board.png \"Board\")\n\
+                    <img width=\"300\" alt=\"Screenshot\" src=\"https://example.com/shot.png\">";
+        assert_eq!(
+            image_links(body),
+            vec![
+                image("the board", "https://example.com/board.png"),
+                image("Screenshot", "https://example.com/shot.png"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reference_style_images() {
+        let body =
+            "![full][Shot] ![collapsed][] ![shortcut] [not an image](https://example.com)\n\n\
+                    [shot]: https://example.com/full.png\n\
+                    [collapsed]: <https://example.com/collapsed.png>\n  \
+                    [shortcut]: https://example.com/shortcut.png \"Title\"";
+        assert_eq!(
+            image_links(body),
+            vec![
+                image("full", "https://example.com/full.png"),
+                image("collapsed", "https://example.com/collapsed.png"),
+                image("shortcut", "https://example.com/shortcut.png"),
+            ]
+        );
+    }
+
+    #[test]
+    fn relative_and_undefined_images_are_left_out() {
+        let body =
+            "![relative](docs/board.png) <img src=\"/assets/logo.png\"> ![missing][nowhere] \
+                    ![after](https://example.com/after.png)";
+        assert_eq!(
+            image_links(body),
+            vec![image("after", "https://example.com/after.png")]
+        );
+    }
+
+    #[test]
+    fn unterminated_images_are_ignored() {
+        assert_eq!(image_links("![alt](https://example.com/a.png"), vec![]);
+        assert_eq!(image_links("![alt"), vec![]);
+        assert_eq!(image_links("<img src=\"https://example.com/a.png"), vec![]);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    github::Issue,
+    settings::{PipelineLabel, Settings},
+    zenhub::{Estimate, Pipeline},
+};
+
+use super::analytics;
+
+/// Labels to remove from and add to an issue to mirror its place on the board.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LabelChanges {
+    pub remove: Vec<String>,
+    pub add: Vec<String>,
+}
+
+impl LabelChanges {
+    pub fn is_empty(&self) -> bool {
+        self.remove.is_empty() && self.add.is_empty()
+    }
+}
+
+/// Computes the label changes which make the estimate label (e.g. `sp:5`) and
+/// the pipeline label of an issue match the board, as configured in the
+/// settings. Labels not managed by any setting are left alone.
+pub fn label_changes(
+    settings: &Settings,
+    issue: &Issue,
+    pipeline: Option<&Pipeline>,
+    estimate: Option<Estimate>,
+) -> LabelChanges {
+    let mut managed = Vec::new();
+    let mut wanted = Vec::new();
+
+    let estimate_labels = &settings.estimate_labels;
+    if estimate_labels.sync {
+        managed.extend(
+            issue
+                .labels
+                .iter()
+                .map(|label| label.name.clone())
+                .filter(|name| name.starts_with(&estimate_labels.prefix)),
+        );
+        wanted.extend(estimate.map(|estimate| {
+            format!(
+                "{}{}",
+                estimate_labels.prefix,
+                analytics::format_points(estimate.value)
+            )
+        }));
+    }
+
+    if let Some(pipeline) = pipeline {
+        managed.extend(
+            settings
+                .pipeline_labels
+                .iter()
+                .map(|rule| rule.label.clone()),
+        );
+        wanted.extend(
+            settings
+                .pipeline_labels
+                .iter()
+                .filter(|rule| rule.matches(&pipeline.name))
+                .map(|rule| rule.label.clone()),
+        );
+    }
+
+    let has_label = |name: &str| issue.labels.iter().any(|label| label.name == name);
+    let mut changes = LabelChanges::default();
+    for name in managed {
+        if has_label(&name) && !wanted.contains(&name) && !changes.remove.contains(&name) {
+            changes.remove.push(name);
+        }
+    }
+    for name in wanted {
+        if !has_label(&name) && !changes.add.contains(&name) {
+            changes.add.push(name);
+        }
+    }
+    changes
+}
+
+/// If an issue was given the label of a pipeline outside zentui, returns the
+/// rule for the pipeline it should be moved to. Issues already carrying the
+/// label of their current pipeline stay where they are.
+pub fn labelled_pipeline<'a>(
+    rules: &'a [PipelineLabel],
+    issue: &Issue,
+    pipeline: &Pipeline,
+) -> Option<&'a PipelineLabel> {
+    let has_label = |name: &str| issue.labels.iter().any(|label| label.name == name);
+    if rules
+        .iter()
+        .any(|rule| rule.matches(&pipeline.name) && has_label(&rule.label))
+    {
+        return None;
+    }
+    rules
+        .iter()
+        .find(|rule| !rule.matches(&pipeline.name) && has_label(&rule.label))
+}
+
+#[cfg(test)]
+mod tests {
+    use im::Vector;
+
+    use super::*;
+    use crate::settings::EstimateLabelSettings;
+
+    fn issue(labels: &[&str]) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "number": 1,
+            "pull_request": null,
+            "labels": labels
+                .iter()
+                .map(|name| serde_json::json!({ "name": name }))
+                .collect::<Vec<_>>(),
+        }))
+        .expect("issue parses")
+    }
+
+    fn pipeline(name: &str) -> Pipeline {
+        Pipeline {
+            id: "pipeline-0".into(),
+            name: name.into(),
+            issues: Vector::new(),
+        }
+    }
+
+    fn rule(pipeline: &str, label: &str) -> PipelineLabel {
+        PipelineLabel {
+            pipeline: pipeline.into(),
+            label: label.into(),
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            estimate_labels: EstimateLabelSettings {
+                sync: true,
+                prefix: "sp:".into(),
+            },
+            pipeline_labels: vec![
+                rule("In Progress", "status/in-progress"),
+                rule("Review", "status/review"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn the_estimate_and_pipeline_labels_follow_the_board() {
+        let changes = label_changes(
+            &settings(),
+            &issue(&["sp:3", "status/review", "bug"]),
+            Some(&pipeline("In Progress")),
+            Some(Estimate { value: 5.0 }),
+        );
+        assert_eq!(
+            changes,
+            LabelChanges {
+                remove: vec!["sp:3".into(), "status/review".into()],
+                add: vec!["sp:5".into(), "status/in-progress".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn labels_already_matching_the_board_are_left_alone() {
+        let changes = label_changes(
+            &settings(),
+            &issue(&["sp:5", "status/in-progress"]),
+            Some(&pipeline("in progress")),
+            Some(Estimate { value: 5.0 }),
+        );
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn estimate_labels_are_left_alone_unless_synced() {
+        let mut settings = settings();
+        settings.estimate_labels.sync = false;
+        let changes = label_changes(&settings, &issue(&["sp:3"]), None, None);
+        assert!(changes.is_empty());
+
+        settings.estimate_labels.sync = true;
+        let changes = label_changes(&settings, &issue(&["sp:3"]), None, None);
+        assert_eq!(changes.remove, vec!["sp:3".to_owned()]);
+        assert!(changes.add.is_empty());
+    }
+
+    #[test]
+    fn a_label_added_outside_zentui_moves_the_issue() {
+        let rules = settings().pipeline_labels;
+        let labelled =
+            labelled_pipeline(&rules, &issue(&["status/review"]), &pipeline("In Progress"));
+        assert_eq!(labelled.map(|rule| rule.pipeline.as_str()), Some("Review"));
+    }
+
+    #[test]
+    fn an_issue_labelled_for_its_pipeline_stays() {
+        let rules = settings().pipeline_labels;
+        let labelled = issue(&["status/in-progress", "status/review"]);
+        assert!(labelled_pipeline(&rules, &labelled, &pipeline("In Progress")).is_none());
+        assert!(labelled_pipeline(&rules, &issue(&["bug"]), &pipeline("Backlog")).is_none());
+    }
+}
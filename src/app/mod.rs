@@ -1,45 +1,153 @@
+mod agenda;
+mod analytics;
+mod board_diff;
+mod board_stats;
+mod changelog;
+mod connections;
+mod epic_tree;
+mod humanize;
 mod issue_card;
+mod issue_detail;
+mod label_sync;
+mod overlay;
+mod picker;
 mod pipeline;
+mod preload;
 mod prompt;
+mod rename;
+mod share;
+mod sla;
+mod snooze;
+mod splash;
+mod sweep;
+mod template;
+mod themes;
+
+#[cfg(test)]
+mod headless;
 
 use anyhow::Result;
-use futures::future::FutureExt;
-use im::hashmap::HashMap;
-use std::{cmp, iter, rc::Rc, sync::Arc};
+use chrono::{Local, Utc};
+use futures::future::{self, BoxFuture, Future, FutureExt};
+use im::{hashmap::HashMap, hashset::HashSet, Vector};
+use regex::Regex;
+use std::{
+    cmp,
+    collections::{BTreeMap, VecDeque},
+    fs, iter, mem,
+    path::PathBuf,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 use tokio::runtime::Handle as RuntimeHandle;
+use tracing::Level;
+use zentui_client::Result as ClientResult;
 use zi::{
-    components::text::{Text, TextProperties},
-    layout, BindingMatch, BindingTransition, Colour, Component, ComponentLink, Key, Layout, Rect,
-    ShouldRender, Style,
+    components::text::{Text, TextAlign, TextProperties},
+    layout, BindingMatch, BindingTransition, Callback, Colour, Component, ComponentLink, Key,
+    Layout, Rect, ShouldRender, Style,
 };
 
 use crate::{
+    attachment::Client as AttachmentClient,
+    cache::{self, BoardCache},
+    clipboard,
+    crash::{AppSummary, CrashReporter},
     edit,
-    github::{Client as GithubClient, Issue, IssueNumber, Repo},
-    zenhub::{Board, Client as ZenhubClient, Pipeline},
+    follow::{self, Selection},
+    github::{
+        Client as GithubClient, Comment, Issue, IssueNumber, IssueState, Label, Notification,
+        PullRequest, PullRequestStatus, ReleaseDraft, Repo, RepoFullName, RepoId, Team, User,
+        ISSUES_PER_QUERY,
+    },
+    logging::LogBuffer,
+    project,
+    settings::{
+        self, Density, FocusEmphasis, NewIssuePosition, QuitKey, Settings, StartupView,
+        StatusSegment, StatusSegmentKind, TriageAction, Webhook, WipLimit,
+    },
+    state::{self, BoardState, FilterState, PipelineState, Snooze, State},
+    stats::{self, Activity, Stats},
+    terminal::{self, GraphicsProtocol},
+    webhook::Client as WebhookClient,
+    zenhub::{
+        Board, Client as ZenhubClient, Dependency, Epic, EpicIssue, Estimate, IssueData,
+        IssueEvent, IssueEventKind, IssueRef, Pipeline, PipelineKind, PipelineName, Position,
+        Release, ReleaseId, ReleaseState, Workspace, WorkspaceId,
+    },
 };
+use agenda::AgendaMilestone;
+use board_diff::BoardChange;
+use board_stats::BoardStats;
+use epic_tree::TreeNode;
+use humanize::Noun;
+use issue_card::Relationship;
+use issue_detail::IssueDetail;
+use overlay::{Line, Overlay};
+use picker::{Picker, PickerView};
+use prompt::{Answer, Outcome, Question, QuestionView};
+use rename::{Rename, TitleReplace};
+use splash::Splash;
+use sweep::SweepAction;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Theme {
     divider: Style,
+    stats_header: Style,
     prompt: Rc<prompt::Theme>,
+    picker: Rc<picker::Theme>,
+    detail: Rc<issue_detail::Theme>,
+    overlay: Rc<overlay::Theme>,
     pipeline_focused: Rc<pipeline::Theme>,
     pipeline_unfocused: Rc<pipeline::Theme>,
+    /// The segments of the status row from the settings, with their styles.
+    status_segments: Rc<Vec<(StatusSegmentKind, Style)>>,
 }
 
-impl From<&Base16Theme> for Theme {
-    fn from(theme: &Base16Theme) -> Self {
+impl Theme {
+    fn new(
+        theme: &Base16Theme,
+        focus_emphasis: &[FocusEmphasis],
+        status_segments: &[StatusSegment],
+    ) -> Self {
+        let focus = issue_card::FocusStyle::new(theme, focus_emphasis);
+        let unfocused_issue: issue_card::Theme = theme.into();
         Self {
+            status_segments: Rc::new(
+                status_segments
+                    .iter()
+                    .map(|segment| (segment.segment, status_segment_style(theme, segment)))
+                    .collect(),
+            ),
             divider: Style::bold(theme.base0f, theme.base0f),
+            stats_header: Style::normal(theme.base01, theme.base05),
             prompt: Rc::new(theme.into()),
-            pipeline_unfocused: Rc::new(theme.into()),
+            picker: Rc::new(theme.into()),
+            detail: Rc::new(theme.into()),
+            overlay: Rc::new(theme.into()),
+            pipeline_unfocused: Rc::new(pipeline::Theme {
+                issue: Rc::new(issue_card::Theme {
+                    focus: focus.clone(),
+                    ..unfocused_issue
+                }),
+                ..pipeline::Theme::from(theme)
+            }),
             pipeline_focused: Rc::new(pipeline::Theme {
                 title: Style::bold(theme.base00, theme.base0d),
                 subtitle: Style::normal(theme.base00, theme.base04),
+                over_limit: Style::bold(theme.base00, theme.base08),
+                highlighted: Style::bold(theme.base0b, theme.base00),
                 issue: Rc::new(issue_card::Theme {
                     number: Style::normal(theme.base00, theme.base06),
                     text: Style::normal(theme.base00, theme.base05),
                     border: Style::normal(theme.base00, theme.base02),
+                    mine: Style::bold(theme.base00, theme.base0a),
+                    moved: Style::bold(theme.base00, theme.base0b),
+                    matched: Style::bold(theme.base00, theme.base09),
+                    stale: Style::normal(theme.base00, theme.base08),
+                    focus,
                 }),
             }),
         }
@@ -57,95 +165,530 @@ pub enum FutureValue<T> {
 pub struct PipelineView {
     pub pipeline: Pipeline,
     pub hidden: bool,
+    /// Shown as a narrow strip with its name and number of issues, which
+    /// takes little room but keeps the pipeline in sight.
+    pub collapsed: bool,
+    pub bots_expanded: bool,
     pub selected_issue: IssueIndex,
+    /// The order cards are shown in instead of their priority on Zenhub.
+    /// Only changes what's shown, Zenhub is left alone.
+    pub sort: Option<IssueSort>,
 }
 
 impl PipelineView {
-    fn select_issue(&mut self, issue_index: usize) {
-        self.selected_issue = cmp::min(issue_index, self.pipeline.issues.len().saturating_sub(1));
+    fn select_issue(&mut self, issue_index: usize, num_issues: usize) {
+        self.selected_issue = cmp::min(issue_index, num_issues.saturating_sub(1));
     }
 }
 
 impl From<Pipeline> for PipelineView {
-    fn from(pipeline: Pipeline) -> Self {
+    fn from(mut pipeline: Pipeline) -> Self {
+        // Show issues in the priority order set in Zenhub, rather than the
+        // order the API happens to return them in
+        pipeline.sort_issues();
         Self {
             pipeline,
             hidden: false,
+            collapsed: false,
+            bots_expanded: false,
             selected_issue: 0,
+            sort: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TeamFilter {
+    pub team: Team,
+    pub members: HashSet<String>,
+}
+
+/// Restricts which issues are visible on the board. Issues which haven't
+/// loaded yet are always visible, unless only epics are shown.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BoardFilter {
+    pub team: Option<TeamFilter>,
+    pub label: Option<String>,
+    /// Only show the issues assigned to this login.
+    pub assignee: Option<String>,
+    pub state: Option<IssueState>,
+    pub kind: Option<CardKind>,
+}
+
+impl BoardFilter {
+    fn matches(&self, issue_ref: &IssueRef, issue: Option<&FutureValue<Issue>>) -> bool {
+        if self.kind == Some(CardKind::Epics) && !issue_ref.is_epic {
+            return false;
+        }
+        let issue = match issue {
+            Some(FutureValue::Ready(issue)) => issue,
+            _ => return true,
+        };
+        let is_pull_request = issue.pull_request.is_some();
+        let kind_matches = match self.kind {
+            None | Some(CardKind::Epics) => true,
+            Some(CardKind::Issues) => !is_pull_request && !issue_ref.is_epic,
+            Some(CardKind::PullRequests) => is_pull_request,
+        };
+        if !kind_matches {
+            return false;
+        }
+        self.team.as_ref().map_or(true, |filter| {
+            issue
+                .assignees
+                .iter()
+                .any(|assignee| filter.members.contains(&assignee.login))
+        }) && self.label.as_ref().map_or(true, |name| {
+            issue.labels.iter().any(|label| &label.name == name)
+        }) && self.assignee.as_ref().map_or(true, |login| {
+            issue
+                .assignees
+                .iter()
+                .any(|assignee| &assignee.login == login)
+        }) && self.state.map_or(true, |state| issue.state == state)
+    }
+
+    /// The active filters, for the status line, e.g. `label:bug state:open`.
+    fn describe(&self) -> Vec<String> {
+        let mut filters = Vec::new();
+        if let Some(ref filter) = self.team {
+            filters.push(format!("team:{}", filter.team.name));
+        }
+        if let Some(ref label) = self.label {
+            filters.push(format!("label:{}", label));
+        }
+        if let Some(ref login) = self.assignee {
+            filters.push(format!("assignee:@{}", login));
+        }
+        if let Some(state) = self.state {
+            filters.push(format!("state:{}", state_name(state)));
+        }
+        if let Some(kind) = self.kind {
+            filters.push(format!("type:{}", kind.name()));
+        }
+        filters
+    }
+}
+
+/// The kinds of card the board can be narrowed down to, cycled with `g t`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CardKind {
+    Issues,
+    PullRequests,
+    Epics,
+}
+
+impl CardKind {
+    /// The kind after this one when cycling through them, back to showing
+    /// every card after epics.
+    fn next(kind: Option<Self>) -> Option<Self> {
+        match kind {
+            None => Some(Self::Issues),
+            Some(Self::Issues) => Some(Self::PullRequests),
+            Some(Self::PullRequests) => Some(Self::Epics),
+            Some(Self::Epics) => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Issues => "issues",
+            Self::PullRequests => "prs",
+            Self::Epics => "epics",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Self::Issues, Self::PullRequests, Self::Epics]
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == name)
+    }
+}
+
+/// What the cards of a pipeline can be sorted by, chosen with `s` and a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IssueSort {
+    /// Most recently updated first.
+    Updated,
+    /// Largest estimate first, unestimated cards last.
+    Estimate,
+    /// Oldest first.
+    Number,
+    Title,
+}
+
+impl IssueSort {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Updated => "updated",
+            Self::Estimate => "estimate",
+            Self::Number => "number",
+            Self::Title => "title",
         }
     }
 }
 
+fn state_name(state: IssueState) -> &'static str {
+    match state {
+        IssueState::Open => "open",
+        IssueState::Closed => "closed",
+    }
+}
+
+/// What the filter bar keys (`f l`, `f a`, `f s`) filter the board by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterField {
+    Label,
+    Assignee,
+    State,
+}
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct BoardView {
     pub pipelines: Vec<PipelineView>,
     pub selected_pipeline: PipelineIndex,
+    pub filter: BoardFilter,
 }
 
 impl BoardView {
     fn select_next_pipeline(&mut self) {
-        let mut selected_pipeline = self.selected_pipeline;
-        let max_selected_pipline = self.pipelines.len().saturating_sub(1);
-        loop {
-            selected_pipeline = cmp::min(selected_pipeline + 1, max_selected_pipline);
-            if !self.pipelines[selected_pipeline].hidden {
-                self.selected_pipeline = selected_pipeline;
-                break;
-            } else if selected_pipeline == max_selected_pipline {
-                break;
-            }
+        if let Some(next) = self.adjacent_pipeline(self.selected_pipeline, Direction::Next) {
+            self.selected_pipeline = next;
         }
     }
 
     fn select_previous_pipeline(&mut self) {
-        let mut selected_pipeline = self.selected_pipeline;
-        loop {
-            selected_pipeline = selected_pipeline.saturating_sub(1);
-            if !self.pipelines[selected_pipeline].hidden {
-                self.selected_pipeline = selected_pipeline;
-                break;
-            } else if selected_pipeline == 0 {
-                break;
+        if let Some(previous) = self.adjacent_pipeline(self.selected_pipeline, Direction::Previous)
+        {
+            self.selected_pipeline = previous;
+        }
+    }
+
+    /// The closest pipeline which isn't hidden before or after a pipeline.
+    fn adjacent_pipeline(
+        &self,
+        pipeline_index: PipelineIndex,
+        direction: Direction,
+    ) -> Option<PipelineIndex> {
+        match direction {
+            Direction::Previous => self.pipelines[..cmp::min(pipeline_index, self.pipelines.len())]
+                .iter()
+                .rposition(|pipeline| !pipeline.hidden),
+            Direction::Next => self
+                .pipelines
+                .iter()
+                .enumerate()
+                .skip(pipeline_index + 1)
+                .find(|(_, pipeline)| !pipeline.hidden)
+                .map(|(index, _)| index),
+        }
+    }
+
+    /// Hides a pipeline, moving the selection off it. Returns `false`
+    /// without hiding anything when it's the last pipeline shown, as a board
+    /// with every pipeline hidden has nothing left to select.
+    fn hide_pipeline(&mut self, pipeline_index: PipelineIndex) -> bool {
+        let num_shown = self
+            .pipelines
+            .iter()
+            .filter(|pipeline| !pipeline.hidden)
+            .count();
+        match self.pipelines.get_mut(pipeline_index) {
+            Some(pipeline) if !pipeline.hidden => {
+                if num_shown <= 1 {
+                    return false;
+                }
+                pipeline.hidden = true;
             }
+            _ => return true,
+        }
+        self.select_shown_pipeline();
+        true
+    }
+
+    /// Moves the selection to the closest pipeline shown, preferring the one
+    /// before, when the selected pipeline is hidden or no longer exists.
+    fn select_shown_pipeline(&mut self) {
+        let selected_pipeline = cmp::min(
+            self.selected_pipeline,
+            self.pipelines.len().saturating_sub(1),
+        );
+        self.selected_pipeline = match self.pipelines.get(selected_pipeline) {
+            Some(pipeline) if !pipeline.hidden => selected_pipeline,
+            _ => self
+                .adjacent_pipeline(selected_pipeline, Direction::Previous)
+                .or_else(|| self.adjacent_pipeline(selected_pipeline, Direction::Next))
+                .unwrap_or(selected_pipeline),
+        };
+    }
+
+    /// The pipelines in view when only `num_columns` of them fit: a page of
+    /// the ones shown which has the selected pipeline, so the board scrolls
+    /// a page at a time as the selection moves.
+    fn pipelines_in_view(&self, num_columns: usize) -> Vec<PipelineIndex> {
+        let shown: Vec<_> = self
+            .pipelines
+            .iter()
+            .enumerate()
+            .filter(|(_, pipeline)| !pipeline.hidden)
+            .map(|(pipeline_index, _)| pipeline_index)
+            .collect();
+        let num_columns = cmp::max(num_columns, 1);
+        if shown.len() <= num_columns {
+            return shown;
         }
+        let selected = shown
+            .iter()
+            .position(|&pipeline_index| pipeline_index == self.selected_pipeline)
+            .unwrap_or(0);
+        let first = cmp::min(
+            selected / num_columns * num_columns,
+            shown.len() - num_columns,
+        );
+        shown[first..first + num_columns].to_vec()
     }
 
-    fn hide_pipeline(&mut self, pipeline_index: PipelineIndex) {
+    fn toggle_collapsed(&mut self, pipeline_index: PipelineIndex) {
         if let Some(pipeline) = self.pipelines.get_mut(pipeline_index) {
-            pipeline.hidden = true;
-            if self.pipelines[self.selected_pipeline].hidden {
-                self.select_previous_pipeline();
-            }
-            if self.pipelines[self.selected_pipeline].hidden {
-                self.select_next_pipeline();
-            }
-            if self.pipelines[self.selected_pipeline].hidden {
-                self.selected_pipeline = 0;
-            }
+            pipeline.collapsed = !pipeline.collapsed;
         }
     }
 
+    /// Shows every pipeline in full, hidden and collapsed ones alike.
     fn show_all_pipelines(&mut self) {
+        self.pipelines.iter_mut().for_each(|pipeline| {
+            pipeline.hidden = false;
+            pipeline.collapsed = false;
+        });
+    }
+
+    fn pipeline_with_id(&self, id: &str) -> Option<PipelineIndex> {
         self.pipelines
-            .iter_mut()
-            .for_each(|pipeline| pipeline.hidden = false);
+            .iter()
+            .position(|pipeline| pipeline.pipeline.id == id)
+    }
+
+    fn pipeline_of_kind(&self, kind: PipelineKind) -> Option<PipelineIndex> {
+        self.pipelines
+            .iter()
+            .position(|pipeline| pipeline.pipeline.kind() == kind)
+    }
+
+    /// Moves an issue of the board's repo to the pipeline `to_pipeline` at
+    /// `position`, returning the pipeline and position it was moved from.
+    fn move_issue(
+        &mut self,
+        board_repo: RepoId,
+        issue_number: IssueNumber,
+        to_pipeline: PipelineIndex,
+        position: usize,
+    ) -> Option<(PipelineIndex, usize)> {
+        if to_pipeline >= self.pipelines.len() {
+            return None;
+        }
+        let (from_pipeline, from_position) =
+            self.pipelines
+                .iter()
+                .enumerate()
+                .find_map(|(pipeline_index, pipeline)| {
+                    pipeline
+                        .pipeline
+                        .issues
+                        .iter()
+                        .position(|issue_ref| issue_ref.refers_to(board_repo, issue_number))
+                        .map(|issue_position| (pipeline_index, issue_position))
+                })?;
+
+        let source = &mut self.pipelines[from_pipeline];
+        let issue_ref = source.pipeline.issues.remove(from_position);
+        let num_issues = source.pipeline.issues.len();
+        source.select_issue(source.selected_issue, num_issues);
+
+        let issues = &mut self.pipelines[to_pipeline].pipeline.issues;
+        issues.insert(cmp::min(position, issues.len()), issue_ref);
+        Some((from_pipeline, from_position))
     }
 
     fn selected_pipeline(&self) -> Option<&PipelineView> {
         self.pipelines.get(self.selected_pipeline)
     }
 
+    /// Replaces the pipelines with a reloaded board, keeping the filter and
+    /// which pipelines are hidden, expanded and selected. Cards recently
+    /// `moved` or `estimated` from zentui keep their place and estimate, as
+    /// the reloaded board may predate the change.
+    fn refresh(
+        &mut self,
+        board_repo: RepoId,
+        board: Board,
+        moved: &HashSet<IssueNumber>,
+        estimated: &HashSet<IssueNumber>,
+    ) {
+        let selected_id = self
+            .selected_pipeline()
+            .map(|pipeline| pipeline.pipeline.id.clone());
+        let mut refreshed = BoardView::from(board);
+        for pipeline in refreshed.pipelines.iter_mut() {
+            if let Some(previous) = self
+                .pipelines
+                .iter()
+                .find(|previous| previous.pipeline.id == pipeline.pipeline.id)
+            {
+                pipeline.hidden = previous.hidden;
+                pipeline.collapsed = previous.collapsed;
+                pipeline.bots_expanded = previous.bots_expanded;
+                pipeline.selected_issue = previous.selected_issue;
+                pipeline.sort = previous.sort;
+            }
+        }
+        refreshed.selected_pipeline = selected_id
+            .and_then(|id| {
+                refreshed
+                    .pipelines
+                    .iter()
+                    .position(|pipeline| pipeline.pipeline.id == id)
+            })
+            .unwrap_or(0);
+        refreshed.select_shown_pipeline();
+        refreshed.filter = self.filter.clone();
+
+        for &issue_number in moved.iter() {
+            let (pipeline_id, position, issue_ref) =
+                match self.pipelines.iter().find_map(|pipeline| {
+                    pipeline
+                        .pipeline
+                        .issues
+                        .iter()
+                        .position(|issue_ref| issue_ref.refers_to(board_repo, issue_number))
+                        .map(|position| {
+                            (
+                                pipeline.pipeline.id.clone(),
+                                position,
+                                pipeline.pipeline.issues[position].clone(),
+                            )
+                        })
+                }) {
+                    Some(location) => location,
+                    None => continue,
+                };
+            let to_pipeline = match refreshed
+                .pipelines
+                .iter()
+                .position(|pipeline| pipeline.pipeline.id == pipeline_id)
+            {
+                Some(to_pipeline) => to_pipeline,
+                None => continue,
+            };
+            // Issues created from zentui may not be on the reloaded board yet
+            if refreshed
+                .move_issue(board_repo, issue_number, to_pipeline, position)
+                .is_none()
+            {
+                let issues = &mut refreshed.pipelines[to_pipeline].pipeline.issues;
+                issues.insert(cmp::min(position, issues.len()), issue_ref);
+            }
+        }
+
+        for issue_ref in refreshed
+            .pipelines
+            .iter_mut()
+            .flat_map(|pipeline| pipeline.pipeline.issues.iter_mut())
+            .filter(|issue_ref| {
+                issue_ref.foreign_repo(board_repo).is_none()
+                    && estimated.contains(&issue_ref.number)
+            })
+        {
+            if let Some(local) = self
+                .pipelines
+                .iter()
+                .flat_map(|pipeline| pipeline.pipeline.issues.iter())
+                .find(|local| local.refers_to(board_repo, issue_ref.number))
+            {
+                issue_ref.estimate = local.estimate;
+            }
+        }
+        *self = refreshed;
+    }
+
     fn selected_pipeline_mut(&mut self) -> Option<&mut PipelineView> {
         self.pipelines.get_mut(self.selected_pipeline)
     }
+
+    /// What's remembered of the board between sessions, except the selected
+    /// issue which depends on what's visible.
+    fn saved_state(&self) -> BoardState {
+        BoardState {
+            pipelines: self
+                .pipelines
+                .iter()
+                .map(|pipeline| PipelineState {
+                    id: pipeline.pipeline.id.clone(),
+                    hidden: pipeline.hidden,
+                    collapsed: pipeline.collapsed,
+                })
+                .collect(),
+            selected_pipeline: self
+                .selected_pipeline()
+                .map(|pipeline| pipeline.pipeline.id.clone()),
+            selected_issue: None,
+            filter: FilterState {
+                team: self.filter.team.as_ref().map(|filter| filter.team.clone()),
+                label: self.filter.label.clone(),
+                assignee: self.filter.assignee.clone(),
+                state: self.filter.state,
+                kind: self.filter.kind.map(|kind| kind.name().to_owned()),
+            },
+        }
+    }
+
+    /// Hides, collapses and selects pipelines as they were in a previous
+    /// session and applies its filters, but for the team whose members have
+    /// to be loaded first. Pipelines new since then are left as they are.
+    fn restore(&mut self, saved: &BoardState) {
+        for pipeline in self.pipelines.iter_mut() {
+            if let Some(saved) = saved
+                .pipelines
+                .iter()
+                .find(|saved| saved.id == pipeline.pipeline.id)
+            {
+                pipeline.hidden = saved.hidden;
+                pipeline.collapsed = saved.collapsed;
+            }
+        }
+        if self.pipelines.iter().all(|pipeline| pipeline.hidden) {
+            if let Some(pipeline) = self.pipelines.first_mut() {
+                pipeline.hidden = false;
+            }
+        }
+        if let Some(selected_pipeline) = saved.selected_pipeline.as_ref().and_then(|id| {
+            self.pipelines
+                .iter()
+                .position(|pipeline| &pipeline.pipeline.id == id)
+        }) {
+            self.selected_pipeline = selected_pipeline;
+        }
+        self.select_shown_pipeline();
+        self.filter.label = saved.filter.label.clone();
+        self.filter.assignee = saved.filter.assignee.clone();
+        self.filter.state = saved.filter.state;
+        self.filter.kind = saved.filter.kind.as_deref().and_then(CardKind::from_name);
+    }
 }
 
 impl From<Board> for BoardView {
     fn from(board: Board) -> Self {
-        Self {
+        let mut board_view = Self {
             pipelines: board.pipelines.into_iter().map(Into::into).collect(),
             selected_pipeline: 0,
+            filter: BoardFilter::default(),
+        };
+        // The closed pipeline is rarely interesting and often the longest,
+        // start with it collapsed out of the way, still one key from view.
+        if let Some(closed) = board_view.pipeline_of_kind(PipelineKind::Closed) {
+            board_view.pipelines[closed].collapsed = true;
         }
+        board_view
     }
 }
 
@@ -154,133 +697,6019 @@ pub struct Properties {
     pub async_runtime: RuntimeHandle,
     pub github_client: Arc<GithubClient>,
     pub zenhub_client: Arc<ZenhubClient>,
+    pub webhook_client: Arc<WebhookClient>,
+    pub attachment_client: Arc<AttachmentClient>,
     pub repo: Repo,
+    pub user: User,
+    pub settings: Settings,
+    pub settings_path: Option<PathBuf>,
+    pub state: State,
+    pub state_path: Option<PathBuf>,
+    pub stats: Stats,
+    pub stats_path: Option<PathBuf>,
+    /// Where the board and its issues are cached between sessions.
+    pub cache_path: Option<PathBuf>,
+    pub logs: Option<LogBuffer>,
+    pub crash_reporter: CrashReporter,
+    /// The name or id of the workspace whose board to open. When not set,
+    /// the workspace picked last time is used, or else the oldest one.
+    pub workspace: Option<String>,
+    /// Set before exiting to have the board of another repository opened
+    /// instead of quitting.
+    pub next_repo: Arc<Mutex<Option<RepoFullName>>>,
+    /// The view to open once the board has loaded.
+    pub startup_view: StartupView,
 }
 
 type PipelineIndex = usize;
 type IssueIndex = usize;
 
+/// An issue moved between pipelines outside zentui, e.g. by a teammate,
+/// noticed when polling the board. The card and both pipelines are
+/// highlighted for a moment.
+#[derive(Clone, Copy, Debug)]
+struct RemoteMove {
+    issue_number: IssueNumber,
+    from_pipeline: PipelineIndex,
+    to_pipeline: PipelineIndex,
+    at: Instant,
+}
+
+/// Estimating the marked issues, a batch of API calls at a time. The cards
+/// show the new estimate straight away and go back to their `previous` one
+/// if Zenhub rejects the change.
+#[derive(Clone, Debug)]
+struct BulkEstimate {
+    points: Option<f64>,
+    pending: VecDeque<(IssueNumber, Option<Estimate>)>,
+    num_issues: usize,
+    num_done: usize,
+    failed: Vec<IssueNumber>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TeamAction {
+    RequestReview(IssueNumber),
+    Filter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Previous,
+    Next,
+}
+
+/// Where to move the selected card: within its pipeline (changing its
+/// priority) or to an adjacent pipeline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CardMove {
+    Within(Direction),
+    Across(Direction),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverlayKind {
+    Notifications,
+    Analytics,
+    Workload,
+    Labels,
+    Logs,
+    Sweep,
+    Agenda,
+    Rename,
+    /// The full title of the selected issue, as cards may truncate it.
+    Title,
+    /// What blocks the selected issue and what it blocks.
+    Dependencies,
+    /// What changed on the board since the list was last cleared.
+    Changes,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverlayState {
+    pub kind: OverlayKind,
+    pub selected: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PickerAction {
+    Team(TeamAction, Vector<Team>),
+    Share(Issue, Vec<Webhook>),
+    ConnectPullRequest(IssueNumber, Vec<IssueNumber>),
+    ToggleLabel(IssueNumber, Vector<Label>),
+    ToggleRelease(IssueNumber, Vector<Release>),
+    SwitchRepo(Vec<RepoFullName>),
+    Scheme(Vec<String>),
+    SelectWorkspace(Vector<Workspace>),
+    /// The workspace to open the board of at startup.
+    ChooseWorkspace(Vector<Workspace>),
+}
+
+/// Where a changelog draft goes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangelogOutput {
+    Clipboard,
+    File,
+    /// A Github release draft for the tag.
+    Release(String),
+}
+
+/// What started reloading the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefreshTrigger {
+    Key,
+    Timer,
+}
+
+/// Why the workspaces of the repo were requested.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WorkspaceIntent {
+    /// Open the board of the workspace with this name or id.
+    Open(String),
+    Pick,
+    /// Ask which board to open when the repo is in several workspaces and
+    /// none was remembered, at startup.
+    Choose,
+}
+
+/// What to do with the answer to a question asked in the prompt row.
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuestionAction {
+    GoToIssue,
+    MoveIssue(IssueNumber, Vec<PipelineIndex>),
+    Sweep(SweepAction),
+    /// Searches the board as the query is typed.
+    Search,
+    Filter(FilterField),
+    FindTitles,
+    /// Drafts a changelog of the issues in the pipeline.
+    Changelog(PipelineIndex),
+    /// Drafts a Github release of the pipeline with the tag.
+    ReleaseTag(PipelineIndex),
+    /// Replaces the titles matching the pattern.
+    ReplaceTitles(String),
+    Rename,
+    /// Remembers the workspace chosen at startup for the next time.
+    RememberWorkspace(Workspace),
+    /// Assigns the current user to the issue, as set up with `auto_assign`.
+    AssignToMe(IssueNumber),
+    Snooze(IssueNumber),
+    /// Creates an issue in the selected pipeline at the chosen position.
+    NewIssue(Vec<NewIssuePosition>),
+    /// Moves the issue into a pipeline already at its WIP limit.
+    MoveOverWipLimit(IssueNumber, PipelineIndex, Position),
+}
+
+/// How the app sends messages to itself. The headless driver in the tests
+/// runs the app without zi's event loop and queues them instead.
+#[derive(Clone)]
+enum AppLink {
+    Zi(ComponentLink<App>),
+    #[cfg(test)]
+    Queue(Arc<Mutex<Vec<Message>>>),
+}
+
+impl AppLink {
+    fn send(&self, message: Message) {
+        match self {
+            Self::Zi(link) => link.send(message),
+            #[cfg(test)]
+            Self::Queue(queue) => queue
+                .lock()
+                .expect("message queue lock is not poisoned")
+                .push(message),
+        }
+    }
+
+    fn callback<InputT>(&self, callback: impl Fn(InputT) -> Message + 'static) -> Callback<InputT> {
+        match self {
+            Self::Zi(link) => link.callback(callback),
+            #[cfg(test)]
+            Self::Queue(_) => {
+                let link = self.clone();
+                (move |input| link.send(callback(input))).into()
+            }
+        }
+    }
+
+    /// Suspends the UI to run e.g. the editor. Without a terminal, as in the
+    /// tests, nothing is run.
+    fn run_exclusive(&self, process: impl FnOnce() -> Option<Message> + Send + 'static) {
+        match self {
+            Self::Zi(link) => link.run_exclusive(process),
+            #[cfg(test)]
+            Self::Queue(_) => {}
+        }
+    }
+
+    fn exit(&self) {
+        match self {
+            Self::Zi(link) => link.exit(),
+            #[cfg(test)]
+            Self::Queue(_) => {}
+        }
+    }
+}
+
 pub struct App {
     properties: Properties,
-    link: ComponentLink<Self>,
+    link: AppLink,
     theme: Rc<Theme>,
+    frame: Rect,
     board: BoardView,
     issues: HashMap<IssueNumber, FutureValue<Issue>>,
+    /// Issues read from the cache which weren't reloaded yet.
+    stale_issues: HashSet<IssueNumber>,
+    /// Issues on the board from repos other than the board's.
+    foreign_issues: HashMap<(RepoId, IssueNumber), FutureValue<Issue>>,
+    foreign_repos: HashMap<RepoId, FutureValue<RepoFullName>>,
+    comments: HashMap<IssueNumber, FutureValue<Vector<Comment>>>,
+    pull_requests: HashMap<IssueNumber, PullRequest>,
+    pull_request_statuses: HashMap<IssueNumber, PullRequestStatus>,
+    /// The pull requests connected to issues on Zenhub, including the ones
+    /// connected from zentui.
+    connected_pull_requests: HashMap<IssueNumber, Vector<IssueNumber>>,
+    /// Whether the status row said that connections made on Zenhub can't be
+    /// loaded with the Zenhub token, which it says once.
+    noted_missing_connections: bool,
+    repo_labels: Option<Vector<Label>>,
+    releases: Option<Vector<Release>>,
+    release_issues: HashMap<ReleaseId, HashSet<IssueNumber>>,
+    /// Which issues block which, from Zenhub.
+    dependencies: Vector<Dependency>,
+    /// Whether the boards of the pinned repos were preloaded, which happens
+    /// once, after the first board loads.
+    preloaded_pinned_repos: bool,
+    remote_moves: Vec<RemoteMove>,
+    /// What changed when the board was reloaded, oldest first, until cleared
+    /// by closing the list of changes.
+    board_changes: Vec<BoardChange>,
+    local_moves: HashMap<IssueNumber, Instant>,
+    local_estimates: HashMap<IssueNumber, Instant>,
+    /// When `q` was last pressed, to quit when it's pressed again.
+    quit_pressed_at: Option<Instant>,
+    polling_board: bool,
+    last_board_poll: Instant,
+    refreshing: bool,
+    last_cache_write: Instant,
+    /// Whether the board or the issues changed since the cache was written.
+    cache_dirty: bool,
+    /// The cache being written in the background, if any.
+    cache_writer: Option<thread::JoinHandle<()>>,
+    /// Whether the splash is shown instead of the board, until the board and
+    /// the issues at the top of each pipeline have loaded.
+    splash: bool,
+    board_error: Option<String>,
+    /// Where the selection is written, while focus-follow mode is on.
+    focus_follow: Option<PathBuf>,
+    /// The selection last written to the focus-follow file.
+    followed: Option<Selection>,
+    epics: HashMap<IssueNumber, Vector<IssueNumber>>,
+    collapsed_epics: HashSet<IssueNumber>,
+    marked_issues: HashSet<IssueNumber>,
+    /// Issues back from a snooze, highlighted until they're selected.
+    resurfaced: HashSet<IssueNumber>,
+    /// How the board was left last session, until the board has loaded and
+    /// it can be restored.
+    saved_board: Option<BoardState>,
+    bulk_estimate: Option<BulkEstimate>,
+    title_replace: Option<TitleReplace>,
+    rename_skipped: HashSet<IssueNumber>,
+    /// Renames waiting for the batch before them to be applied.
+    pending_renames: VecDeque<Rename>,
+    sweep_skipped: HashSet<IssueNumber>,
+    agenda: FutureValue<Vec<AgendaMilestone>>,
+    notifications: FutureValue<Vector<Notification>>,
+    /// Taken when the first board loads, so switching workspaces keeps
+    /// the current view.
+    startup_view: Option<StartupView>,
+    workspace: Option<Workspace>,
+    workspaces: Option<Vector<Workspace>>,
+    picker: Option<Picker<PickerAction>>,
+    question: Option<Question<QuestionAction>>,
+    /// The query of the search highlighting cards, if any.
+    search: Option<String>,
+    overlay: Option<OverlayState>,
+    state: State,
+    stats: Stats,
+    show_detail: bool,
+    show_stats_header: bool,
+    /// How much of each card is shown, the same for all pipelines.
+    density: Density,
+    /// Whether children of an epic shown in another pipeline are left out of
+    /// their own pipeline and counted on the epic's card instead.
+    fold_epic_children: bool,
+    /// Whether issues are loaded in batches with Github's GraphQL API, until
+    /// it fails once and zentui falls back to loading them one by one.
+    batch_issue_loads: bool,
+    show_jump_codes: bool,
+    // The settings watcher stops once this is dropped
+    _settings_watcher: Arc<()>,
+    log_level: Level,
+    log_generation: usize,
+    num_log_lines: usize,
+    status: String,
     num_pending_tasks: usize,
+    needs_redraw: bool,
+    last_redraw: Instant,
 }
 
 #[derive(Debug)]
 pub enum Message {
+    Redraw,
+    SettingsChanged,
     NextPipeline,
     PreviousPipeline,
     SelectIssue(usize),
-    LoadedIssue(IssueNumber, Result<Issue>),
+    PageIssues(Direction),
+    LoadedIssue(IssueNumber, ClientResult<Issue>),
+    LoadedIssues(Vec<IssueNumber>, ClientResult<Vec<Issue>>),
+    LoadedForeignRepo(RepoId, ClientResult<Repo>),
+    LoadedForeignIssue(RepoId, IssueNumber, ClientResult<Issue>),
+    LoadedPullRequest(IssueNumber, ClientResult<PullRequest>),
+    LoadedPullRequestStatus(IssueNumber, ClientResult<PullRequestStatus>),
+    LoadComments(IssueNumber),
+    LoadedComments(IssueNumber, ClientResult<Vector<Comment>>),
     EditIssue(IssueNumber, Result<Issue>),
-    LoadedBoard(Result<Board>),
+    OpenProject,
+    OpenedProject(IssueNumber, Result<PathBuf>),
+    WriteCommitTemplate,
+    PickWebhook,
+    SharedIssue(IssueNumber, String, ClientResult<()>),
+    PickIssueToConnect,
+    PickLabel,
+    OpenInBrowser,
+    LoadedLabels(ClientResult<Vector<Label>>),
+    ToggledLabel(Issue, ClientResult<Issue>),
+    LoadedReleases(ClientResult<Vector<(Release, Vector<EpicIssue>)>>),
+    CopyPipelineMarkdown,
+    LoadedDependencies(ClientResult<Vector<Dependency>>),
+    PreloadedBoards(Vec<(RepoFullName, Result<()>)>),
+    PickRelease,
+    ToggledRelease(IssueNumber, ReleaseId, bool, ClientResult<()>),
+    ConnectedPullRequest(IssueNumber, IssueNumber, ClientResult<()>),
+    LoadedConnectedPullRequests(
+        ClientResult<std::collections::HashMap<IssueNumber, Vector<IssueNumber>>>,
+    ),
+    TriageSelectedIssue(char),
+    TriagedIssue(TriageAction, ClientResult<Issue>),
+    ShowSweep,
+    SweptIssue(SweepAction, IssueNumber, ClientResult<Issue>),
+    PreviewImages,
+    LoadedImages(
+        GraphicsProtocol,
+        Vec<(String, String, ClientResult<Vec<u8>>)>,
+    ),
+    PreviewedImages(Result<()>),
+    ShowAgenda,
+    LoadedAgenda(ClientResult<Vec<AgendaMilestone>>),
+    ShowNotifications,
+    /// `q` was pressed, quitting or asking for it again first.
+    PressedQuit,
+    Exit,
+    LoadedNotifications(ClientResult<Vector<Notification>>),
+    ToggleMyWork,
+    ToggleEpicsOnly,
+    CycleKindFilter,
+    /// Sorts the cards of the selected pipeline, or goes back to their order
+    /// on Zenhub with `None`.
+    SortPipeline(Option<IssueSort>),
+    ToggleFoldEpicChildren,
+    ToggleFocusFollow,
+    LoadedBoard(ClientResult<Board>),
+    WriteCache,
+    PollBoard,
+    Refresh(RefreshTrigger),
+    Refreshed(RefreshTrigger, ClientResult<Board>),
+    PolledBoard(ClientResult<Board>),
+    LoadedIssueEvents(IssueNumber, ClientResult<Vector<IssueEvent>>),
+    ExpireMoveHighlights,
+    LoadedEpic(IssueNumber, ClientResult<Epic>),
     HidePipeline(usize),
+    CollapsePipeline(PipelineIndex),
     ShowAllPipelines,
+    LoadTeams(TeamAction),
+    LoadedTeams(TeamAction, ClientResult<Vector<Team>>),
+    LoadedTeamMembers(Team, ClientResult<Vector<User>>),
+    RequestedTeamReview(IssueNumber, Team, ClientResult<()>),
+    PromoteIssue(IssueNumber),
+    MoveSelectedIssue(CardMove),
+    SyncedLabels(IssueNumber, ClientResult<Vector<Label>>),
+    MovedIssue(IssueNumber, (PipelineIndex, usize), ClientResult<()>),
+    AssignedIssue(IssueNumber, ClientResult<Issue>),
+    ClearTeamFilter,
+    ToggleBots(PipelineIndex),
+    ToggleEpic(IssueNumber),
+    ToggleMark(IssueNumber),
+    ClearMarks,
+    CreateEpicFromMarked,
+    NewIssue,
+    AskNewIssuePosition,
+    /// The pipeline is given by id, as the board may reload while the
+    /// issue is written.
+    EditedNewIssue(String, Position, Result<String>),
+    CreatedIssue(String, Position, ClientResult<Issue>),
+    EditedEpic(Result<String>),
+    CreatedEpic(Vector<IssueNumber>, ClientResult<Issue>),
+    SetBookmark(char),
+    AskSnooze,
+    /// Brings back the snoozed issues whose time is up.
+    ExpireSnoozes,
+    WakeAllSnoozed,
+    JumpToBookmark(char),
+    ToggleOverlay(OverlayKind),
+    OverlayNext,
+    OverlayPrevious,
+    OverlayAccept,
+    ShowLogs,
+    LogsChanged,
+    SetLogLevel(Level),
+    ToggleDetailPane,
+    ToggleStatsHeader,
+    CycleDensity,
+    ToggleJumpCodes,
+    JumpToCode(String),
+    PickRepo,
+    PickScheme,
+    SwitchRepo(RepoFullName),
+    PickWorkspace,
+    LoadedWorkspaces(ClientResult<Vector<Workspace>>, WorkspaceIntent),
+    SetEstimate(IssueNumber, Option<f64>),
+    EstimatedIssue(IssueNumber, Option<Estimate>, ClientResult<()>),
+    EstimateMarked(Option<f64>),
+    AskFindTitles,
+    ConfirmRenames,
+    AskChangelog,
+    DraftChangelog(
+        PipelineIndex,
+        ChangelogOutput,
+        Vec<(IssueNumber, ClientResult<Issue>)>,
+    ),
+    CreatedReleaseDraft(ClientResult<ReleaseDraft>),
+    RenamedBatch(Vec<(IssueNumber, ClientResult<Issue>)>),
+    EstimatedBatch(Vec<(IssueNumber, Option<Estimate>, ClientResult<()>)>),
+    LoadedIssueData(IssueNumber, ClientResult<IssueData>),
+    ResizeDetailPane(isize),
+    ResetDetailPane,
+    PickerInsertChar(char),
+    PickerDeleteChar,
+    PickerNext,
+    PickerPrevious,
+    PickerAccept,
+    PickerCancel,
+    AskGoToIssue,
+    AskMoveIssue,
+    ConfirmSweep(SweepAction),
+    QuestionKey(Key),
+    AskSearch,
+    JumpToMatch(Direction),
+    ClearSearch,
+    AskFilter(FilterField),
+    ClearFilters,
 }
 
-impl Component for App {
-    type Message = Message;
-    type Properties = Properties;
+impl Message {
+    /// The name of the message, used to label its span in the logs without
+    /// formatting the (potentially large) payload.
+    fn name(&self) -> &'static str {
+        match self {
+            Message::Redraw => "Redraw",
+            Message::SettingsChanged => "SettingsChanged",
+            Message::NextPipeline => "NextPipeline",
+            Message::PreviousPipeline => "PreviousPipeline",
+            Message::SelectIssue(..) => "SelectIssue",
+            Message::PageIssues(..) => "PageIssues",
+            Message::LoadedIssue(..) => "LoadedIssue",
+            Message::LoadedIssues(..) => "LoadedIssues",
+            Message::LoadedForeignRepo(..) => "LoadedForeignRepo",
+            Message::LoadedForeignIssue(..) => "LoadedForeignIssue",
+            Message::LoadedPullRequest(..) => "LoadedPullRequest",
+            Message::LoadedPullRequestStatus(..) => "LoadedPullRequestStatus",
+            Message::LoadComments(..) => "LoadComments",
+            Message::LoadedComments(..) => "LoadedComments",
+            Message::EditIssue(..) => "EditIssue",
+            Message::OpenProject => "OpenProject",
+            Message::OpenedProject(..) => "OpenedProject",
+            Message::WriteCommitTemplate => "WriteCommitTemplate",
+            Message::PickWebhook => "PickWebhook",
+            Message::SharedIssue(..) => "SharedIssue",
+            Message::PickIssueToConnect => "PickIssueToConnect",
+            Message::PickLabel => "PickLabel",
+            Message::OpenInBrowser => "OpenInBrowser",
+            Message::LoadedLabels(..) => "LoadedLabels",
+            Message::ToggledLabel(..) => "ToggledLabel",
+            Message::LoadedReleases(..) => "LoadedReleases",
+            Message::CopyPipelineMarkdown => "CopyPipelineMarkdown",
+            Message::LoadedDependencies(..) => "LoadedDependencies",
+            Message::PreloadedBoards(..) => "PreloadedBoards",
+            Message::PickRelease => "PickRelease",
+            Message::ToggledRelease(..) => "ToggledRelease",
+            Message::ConnectedPullRequest(..) => "ConnectedPullRequest",
+            Message::LoadedConnectedPullRequests(..) => "LoadedConnectedPullRequests",
+            Message::TriageSelectedIssue(..) => "TriageSelectedIssue",
+            Message::TriagedIssue(..) => "TriagedIssue",
+            Message::ShowSweep => "ShowSweep",
+            Message::SweptIssue(..) => "SweptIssue",
+            Message::PreviewImages => "PreviewImages",
+            Message::LoadedImages(..) => "LoadedImages",
+            Message::PreviewedImages(..) => "PreviewedImages",
+            Message::ShowAgenda => "ShowAgenda",
+            Message::LoadedAgenda(..) => "LoadedAgenda",
+            Message::ShowNotifications => "ShowNotifications",
+            Message::PressedQuit => "PressedQuit",
+            Message::Exit => "Exit",
+            Message::LoadedNotifications(..) => "LoadedNotifications",
+            Message::ToggleMyWork => "ToggleMyWork",
+            Message::ToggleEpicsOnly => "ToggleEpicsOnly",
+            Message::CycleKindFilter => "CycleKindFilter",
+            Message::SortPipeline(..) => "SortPipeline",
+            Message::ToggleFoldEpicChildren => "ToggleFoldEpicChildren",
+            Message::ToggleFocusFollow => "ToggleFocusFollow",
+            Message::LoadedBoard(..) => "LoadedBoard",
+            Message::WriteCache => "WriteCache",
+            Message::PollBoard => "PollBoard",
+            Message::Refresh(..) => "Refresh",
+            Message::Refreshed(..) => "Refreshed",
+            Message::PolledBoard(..) => "PolledBoard",
+            Message::LoadedIssueEvents(..) => "LoadedIssueEvents",
+            Message::ExpireMoveHighlights => "ExpireMoveHighlights",
+            Message::LoadedEpic(..) => "LoadedEpic",
+            Message::HidePipeline(..) => "HidePipeline",
+            Message::CollapsePipeline(..) => "CollapsePipeline",
+            Message::ShowAllPipelines => "ShowAllPipelines",
+            Message::LoadTeams(..) => "LoadTeams",
+            Message::LoadedTeams(..) => "LoadedTeams",
+            Message::LoadedTeamMembers(..) => "LoadedTeamMembers",
+            Message::RequestedTeamReview(..) => "RequestedTeamReview",
+            Message::PromoteIssue(..) => "PromoteIssue",
+            Message::MoveSelectedIssue(..) => "MoveSelectedIssue",
+            Message::SyncedLabels(..) => "SyncedLabels",
+            Message::MovedIssue(..) => "MovedIssue",
+            Message::AssignedIssue(..) => "AssignedIssue",
+            Message::ClearTeamFilter => "ClearTeamFilter",
+            Message::ToggleBots(..) => "ToggleBots",
+            Message::ToggleEpic(..) => "ToggleEpic",
+            Message::ToggleMark(..) => "ToggleMark",
+            Message::ClearMarks => "ClearMarks",
+            Message::CreateEpicFromMarked => "CreateEpicFromMarked",
+            Message::NewIssue => "NewIssue",
+            Message::AskNewIssuePosition => "AskNewIssuePosition",
+            Message::EditedNewIssue(..) => "EditedNewIssue",
+            Message::CreatedIssue(..) => "CreatedIssue",
+            Message::EditedEpic(..) => "EditedEpic",
+            Message::CreatedEpic(..) => "CreatedEpic",
+            Message::SetBookmark(..) => "SetBookmark",
+            Message::AskSnooze => "AskSnooze",
+            Message::ExpireSnoozes => "ExpireSnoozes",
+            Message::WakeAllSnoozed => "WakeAllSnoozed",
+            Message::JumpToBookmark(..) => "JumpToBookmark",
+            Message::ToggleOverlay(..) => "ToggleOverlay",
+            Message::OverlayNext => "OverlayNext",
+            Message::OverlayPrevious => "OverlayPrevious",
+            Message::OverlayAccept => "OverlayAccept",
+            Message::ShowLogs => "ShowLogs",
+            Message::LogsChanged => "LogsChanged",
+            Message::SetLogLevel(..) => "SetLogLevel",
+            Message::ToggleDetailPane => "ToggleDetailPane",
+            Message::ToggleStatsHeader => "ToggleStatsHeader",
+            Message::CycleDensity => "CycleDensity",
+            Message::ToggleJumpCodes => "ToggleJumpCodes",
+            Message::JumpToCode(..) => "JumpToCode",
+            Message::PickRepo => "PickRepo",
+            Message::PickScheme => "PickScheme",
+            Message::SwitchRepo(..) => "SwitchRepo",
+            Message::PickWorkspace => "PickWorkspace",
+            Message::LoadedWorkspaces(..) => "LoadedWorkspaces",
+            Message::SetEstimate(..) => "SetEstimate",
+            Message::EstimatedIssue(..) => "EstimatedIssue",
+            Message::EstimateMarked(..) => "EstimateMarked",
+            Message::AskFindTitles => "AskFindTitles",
+            Message::ConfirmRenames => "ConfirmRenames",
+            Message::AskChangelog => "AskChangelog",
+            Message::DraftChangelog(..) => "DraftChangelog",
+            Message::CreatedReleaseDraft(..) => "CreatedReleaseDraft",
+            Message::RenamedBatch(..) => "RenamedBatch",
+            Message::EstimatedBatch(..) => "EstimatedBatch",
+            Message::LoadedIssueData(..) => "LoadedIssueData",
+            Message::ResizeDetailPane(..) => "ResizeDetailPane",
+            Message::ResetDetailPane => "ResetDetailPane",
+            Message::PickerInsertChar(..) => "PickerInsertChar",
+            Message::PickerDeleteChar => "PickerDeleteChar",
+            Message::PickerNext => "PickerNext",
+            Message::PickerPrevious => "PickerPrevious",
+            Message::PickerAccept => "PickerAccept",
+            Message::PickerCancel => "PickerCancel",
+            Message::AskGoToIssue => "AskGoToIssue",
+            Message::AskMoveIssue => "AskMoveIssue",
+            Message::ConfirmSweep(..) => "ConfirmSweep",
+            Message::QuestionKey(..) => "QuestionKey",
+            Message::AskSearch => "AskSearch",
+            Message::JumpToMatch(..) => "JumpToMatch",
+            Message::ClearSearch => "ClearSearch",
+            Message::AskFilter(..) => "AskFilter",
+            Message::ClearFilters => "ClearFilters",
+        }
+    }
 
-    fn create(properties: Self::Properties, _frame: Rect, link: ComponentLink<Self>) -> Self {
-        {
-            let link = link.clone();
-            let zenhub_client = properties.zenhub_client.clone();
-            properties
-                .async_runtime
-                .spawn(
-                    zenhub_client
-                        .get_oldest_board(properties.repo.id)
-                        .map(move |board| {
-                            link.send(Message::LoadedBoard(board));
-                        }),
-                );
+    /// Whether the message starts a change to the repo on Github, which
+    /// fails when the repo is archived or read-only. Checked before asking
+    /// for any input, so nothing typed is lost.
+    fn writes_to_github(&self) -> bool {
+        matches!(
+            self,
+            Message::NewIssue
+                | Message::AskNewIssuePosition
+                | Message::CreateEpicFromMarked
+                | Message::PickLabel
+                | Message::ShowSweep
+                | Message::AskFindTitles
+                | Message::TriageSelectedIssue(..)
+                | Message::LoadTeams(TeamAction::RequestReview(..))
+        )
+    }
+}
+
+impl App {
+    /// Why the repo can't be changed, if it can't.
+    fn read_only_reason(&self) -> Option<&'static str> {
+        let repo = &self.properties.repo;
+        if repo.archived {
+            Some("archived")
+        } else if !repo.is_writable() {
+            Some("read-only")
+        } else {
+            None
         }
+    }
 
-        Self {
-            properties,
-            link,
-            theme: Rc::new((&ICY).into()),
-            board: BoardView::default(),
-            issues: HashMap::new(),
-            num_pending_tasks: 1,
+    fn is_bot_issue(&self, issue_ref: &IssueRef) -> bool {
+        match self.issue(issue_ref) {
+            Some(FutureValue::Ready(issue)) => {
+                self.properties.settings.bots.is_bot(&issue.user.login)
+            }
+            _ => false,
         }
     }
 
-    fn update(&mut self, message: Self::Message) -> ShouldRender {
-        match message {
-            Message::NextPipeline => self.board.select_next_pipeline(),
-            Message::PreviousPipeline => self.board.select_previous_pipeline(),
-            Message::SelectIssue(issue_index) => {
-                eprintln!("msg: {:?}", message);
-                if let Some(pipeline) = self.board.selected_pipeline_mut() {
-                    pipeline.select_issue(issue_index);
+    /// The issue of a card, which may be from another repo on the board.
+    fn issue(&self, issue_ref: &IssueRef) -> Option<&FutureValue<Issue>> {
+        match issue_ref.foreign_repo(self.properties.repo.id) {
+            Some(repo_id) => self.foreign_issues.get(&(repo_id, issue_ref.number)),
+            None => self.issues.get(&issue_ref.number),
+        }
+    }
+
+    /// The repo of a card's issue, unless it's from another repo whose name
+    /// hasn't loaded yet.
+    fn issue_repo(&self, issue_ref: &IssueRef) -> Option<&RepoFullName> {
+        match issue_ref.foreign_repo(self.properties.repo.id) {
+            Some(repo_id) => match self.foreign_repos.get(&repo_id) {
+                Some(FutureValue::Ready(full_name)) => Some(full_name),
+                _ => None,
+            },
+            None => Some(&self.properties.repo.full_name),
+        }
+    }
+
+    /// The children of epics shown in another pipeline, each with the epic
+    /// whose card they are folded into, when folding is on. Children in the
+    /// epic's own pipeline are nested under it instead.
+    fn folded_epic_children(&self) -> HashMap<IssueNumber, IssueNumber> {
+        let mut folded = HashMap::new();
+        if !self.fold_epic_children {
+            return folded;
+        }
+        let board_repo = self.properties.repo.id;
+        let mut pipelines = HashMap::new();
+        for (pipeline_index, pipeline) in self.board.pipelines.iter().enumerate() {
+            for issue_ref in pipeline.pipeline.issues.iter() {
+                if issue_ref.foreign_repo(board_repo).is_none() {
+                    let visible = !pipeline.hidden
+                        && self.board.filter.matches(issue_ref, self.issue(issue_ref));
+                    pipelines.insert(issue_ref.number, (pipeline_index, visible));
                 }
             }
-            Message::LoadedBoard(new_board) => {
-                let Self {
-                    ref mut num_pending_tasks,
-                    ref mut board,
-                    ref properties,
-                    ref link,
-                    ..
-                } = *self;
-                *num_pending_tasks -= 1;
-                *board = new_board.unwrap().into();
-                let repo = Arc::new(properties.repo.full_name.clone());
-                for pipeline in board.pipelines.iter() {
-                    pipeline
-                        .pipeline
-                        .issues
-                        .iter()
-                        .take(7)
-                        .cloned()
-                        .for_each(|issue_ref| {
-                            *num_pending_tasks += 1;
-                            let link = link.clone();
-                            let github_client = properties.github_client.clone();
-                            let repo = repo.clone();
-                            properties.async_runtime.spawn(
-                                github_client
-                                    .get_issue(repo, issue_ref.number)
-                                    .map(move |issue| {
-                                        link.send(Message::LoadedIssue(issue_ref.number, issue));
-                                    }),
-                            );
-                        })
+        }
+        for (epic, children) in self.epics.iter() {
+            let epic_pipeline = match pipelines.get(epic) {
+                Some(&(pipeline_index, true)) => pipeline_index,
+                _ => continue,
+            };
+            for child in children.iter().filter(|child| *child != epic) {
+                if let Some(&(pipeline_index, _)) = pipelines.get(child) {
+                    if pipeline_index != epic_pipeline {
+                        folded.entry(*child).or_insert(*epic);
+                    }
                 }
             }
-            Message::LoadedIssue(issue_number, result) => {
-                let issue = match result {
-                    Ok(issue) => FutureValue::Ready(issue),
-                    Err(error) => {
-                        log::error!("{:?}", error);
-                        FutureValue::Error(format!("{:?}", error))
-                    }
-                };
-                self.issues.insert(issue_number, issue);
-                self.num_pending_tasks -= 1;
+        }
+        folded
+    }
+
+    fn visible_issues(&self, pipeline: &PipelineView) -> Vector<IssueRef> {
+        self.arranged_issues(pipeline).0
+    }
+
+    /// The visible issues of a pipeline with epic children nested under their
+    /// epic, and where each card sits in the tree.
+    fn arranged_issues(
+        &self,
+        pipeline: &PipelineView,
+    ) -> (Vector<IssueRef>, HashMap<IssueNumber, TreeNode>) {
+        let board_repo = self.properties.repo.id;
+        let folded = self.folded_epic_children();
+        let snoozed = self.snoozed_issues();
+        let mut issue_refs: Vec<_> = pipeline
+            .pipeline
+            .issues
+            .iter()
+            .filter(|issue_ref| {
+                self.board.filter.matches(issue_ref, self.issue(issue_ref))
+                    && (issue_ref.foreign_repo(board_repo).is_some()
+                        || !snoozed.contains(&issue_ref.number))
+                    && (pipeline.bots_expanded || !self.is_bot_issue(issue_ref))
+                    && (issue_ref.foreign_repo(board_repo).is_some()
+                        || !folded.contains_key(&issue_ref.number))
+            })
+            .cloned()
+            .collect();
+        // Icebox issues are ideas waiting for interest, surface the most
+        // popular ones first.
+        if pipeline.pipeline.kind() == PipelineKind::Icebox && pipeline.sort.is_none() {
+            issue_refs.sort_by_key(|issue_ref| match self.issue(issue_ref) {
+                Some(FutureValue::Ready(issue)) => cmp::Reverse(issue.reactions.total_count),
+                _ => cmp::Reverse(0),
+            });
+        }
+        if let Some(sort) = pipeline.sort {
+            self.sort_issues(&mut issue_refs, sort);
+        }
+        epic_tree::arrange(
+            issue_refs.into_iter().collect(),
+            &self.epics,
+            &self.collapsed_epics,
+        )
+    }
+
+    fn snoozes(&self) -> &[Snooze] {
+        self.state
+            .repo(&self.properties.repo.full_name.0)
+            .map(|repo_state| repo_state.snoozed.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The issues hidden from the board until their snooze is up.
+    fn snoozed_issues(&self) -> HashSet<IssueNumber> {
+        let now = Utc::now().timestamp();
+        self.snoozes()
+            .iter()
+            .filter(|snooze| snooze.until > now)
+            .map(|snooze| snooze.issue_number)
+            .collect()
+    }
+
+    fn ask_snooze(&mut self) {
+        if let Some(issue_ref) = self.selected_local_issue() {
+            self.question = Some(Question::text(
+                format!("Snooze #{} for (e.g. 4h, 2d, 1w)", issue_ref.number.0),
+                "1d",
+                QuestionAction::Snooze(issue_ref.number),
+            ));
+        }
+    }
+
+    fn snooze(&mut self, issue_number: IssueNumber, text: &str) {
+        let duration = match snooze::parse_duration(text) {
+            Some(duration) => duration,
+            None => {
+                self.status = format!("`{}` is not a duration like 4h, 2d or 1w", text.trim());
+                return;
             }
-            Message::EditIssue(issue_number, result) => {
-                let issue = match result {
-                    Ok(issue) => FutureValue::Ready(issue),
-                    Err(error) => {
-                        log::error!("{:?}", error);
-                        FutureValue::Error(format!("{:?}", error))
+        };
+        let until = Utc::now() + duration;
+        let snoozed = &mut self
+            .state
+            .repo_mut(&self.properties.repo.full_name.0)
+            .snoozed;
+        snoozed.retain(|snooze| snooze.issue_number != issue_number);
+        snoozed.push(Snooze {
+            issue_number,
+            until: until.timestamp(),
+        });
+        self.save_state();
+        self.resurfaced.remove(&issue_number);
+        self.status = format!(
+            "Snoozed #{} until {}, C-x z to wake every snoozed issue",
+            issue_number.0,
+            until.with_timezone(&Local).format("%a %H:%M")
+        );
+        self.clamp_selection();
+    }
+
+    /// Puts the issues whose snooze is up back on the board, highlighted.
+    /// With `all`, every snoozed issue is woken up.
+    fn wake_snoozed(&mut self, all: bool) {
+        let now = Utc::now().timestamp();
+        let snoozed = &mut self
+            .state
+            .repo_mut(&self.properties.repo.full_name.0)
+            .snoozed;
+        let (woken, still_snoozed): (Vec<_>, Vec<_>) = snoozed
+            .drain(..)
+            .partition(|snooze| all || snooze.until <= now);
+        *snoozed = still_snoozed;
+        if woken.is_empty() {
+            self.status = "No snoozed issues".into();
+            return;
+        }
+        self.save_state();
+        self.status = match woken.as_slice() {
+            [snooze] => format!("#{} is back from its snooze", snooze.issue_number.0),
+            _ => format!(
+                "{} back from their snooze",
+                humanize::count(woken.len(), Noun::Issue)
+            ),
+        };
+        self.resurfaced
+            .extend(woken.into_iter().map(|snooze| snooze.issue_number));
+    }
+
+    /// Sorts cards locally, keeping their order on Zenhub among equals. Cards
+    /// whose issue hasn't loaded go last when sorting by what's in the issue.
+    fn sort_issues(&self, issue_refs: &mut Vec<IssueRef>, sort: IssueSort) {
+        let loaded = |issue_ref: &IssueRef| match self.issue(issue_ref) {
+            Some(FutureValue::Ready(issue)) => Some(issue),
+            _ => None,
+        };
+        match sort {
+            IssueSort::Updated => issue_refs.sort_by_key(|issue_ref| {
+                cmp::Reverse(loaded(issue_ref).and_then(|issue| issue.updated_at))
+            }),
+            IssueSort::Estimate => issue_refs.sort_by(|left, right| {
+                let points =
+                    |issue_ref: &IssueRef| issue_ref.estimate.map(|estimate| estimate.value);
+                match (points(left), points(right)) {
+                    (Some(left), Some(right)) => {
+                        right.partial_cmp(&left).unwrap_or(cmp::Ordering::Equal)
                     }
-                };
-                self.issues.insert(issue_number, issue);
+                    (Some(_), None) => cmp::Ordering::Less,
+                    (None, Some(_)) => cmp::Ordering::Greater,
+                    (None, None) => cmp::Ordering::Equal,
+                }
+            }),
+            IssueSort::Number => issue_refs.sort_by_key(|issue_ref| issue_ref.number),
+            IssueSort::Title => issue_refs.sort_by_key(|issue_ref| {
+                loaded(issue_ref)
+                    .map_or((1, String::new()), |issue| (0, issue.title.to_lowercase()))
+            }),
+        }
+    }
+
+    /// Summarises the bot issues folded away in a pipeline, if any.
+    fn bot_fold(&self, pipeline: &PipelineView) -> Option<pipeline::BotFold> {
+        if pipeline.bots_expanded {
+            return None;
+        }
+        let mut bot_fold = pipeline::BotFold::default();
+        for issue_ref in pipeline.pipeline.issues.iter() {
+            let issue = match self.issue(issue_ref) {
+                Some(FutureValue::Ready(issue)) => issue,
+                _ => continue,
+            };
+            if self.properties.settings.bots.is_bot(&issue.user.login)
+                && self.board.filter.matches(issue_ref, self.issue(issue_ref))
+            {
+                bot_fold.num_issues += 1;
+                if !bot_fold.accounts.contains(&issue.user.login) {
+                    bot_fold.accounts.push(issue.user.login.clone());
+                }
             }
-            Message::HidePipeline(pipeline_index) => self.board.hide_pipeline(pipeline_index),
-            Message::ShowAllPipelines => self.board.show_all_pipelines(),
         }
-        ShouldRender::Yes
+        if bot_fold.num_issues > 0 {
+            Some(bot_fold)
+        } else {
+            None
+        }
+    }
+
+    /// The number of selectable cards in a pipeline, including the folded
+    /// bot issues card.
+    fn num_cards(&self, pipeline: &PipelineView) -> usize {
+        self.visible_issues(pipeline).len() + self.bot_fold(pipeline).map_or(0, |_| 1)
+    }
+
+    /// How often to do background work, like polling the board, which is
+    /// done every `interval` during working hours. zi doesn't report whether
+    /// the terminal has focus, so only the time of day slows it down.
+    /// How often the board is polled in the background: it's reloaded in
+    /// full when `auto_refresh_secs` is set, otherwise only moves made
+    /// elsewhere are picked up.
+    fn board_poll_interval(&self) -> Duration {
+        self.properties
+            .settings
+            .auto_refresh_secs
+            .map_or(BOARD_POLL_INTERVAL, Duration::from_secs)
+    }
+
+    fn background_interval(&self, interval: Duration) -> Duration {
+        match self.properties.settings.working_hours {
+            Some(ref working_hours) => working_hours.interval(interval, Local::now()),
+            None => interval,
+        }
+    }
+
+    /// How many cards fit in a pipeline, below the stats header and above the
+    /// prompt row.
+    fn page_size(&self) -> usize {
+        let mut pipeline_height = self.frame.size.height.saturating_sub(1);
+        if self.show_stats_header {
+            pipeline_height = pipeline_height.saturating_sub(board_stats::HEADER_HEIGHT);
+        }
+        pipeline::page_size(
+            pipeline_height,
+            self.properties.settings.card_height,
+            self.density,
+        )
+    }
+
+    /// Keeps the selected card of every pipeline in range after cards were
+    /// folded away.
+    fn clamp_selection(&mut self) {
+        let num_cards: Vec<_> = self
+            .board
+            .pipelines
+            .iter()
+            .map(|pipeline| self.num_cards(pipeline))
+            .collect();
+        for (pipeline, num_cards) in self.board.pipelines.iter_mut().zip(num_cards) {
+            pipeline.select_issue(pipeline.selected_issue, num_cards);
+        }
+    }
+
+    fn is_bot_fold_selected(&self) -> bool {
+        self.board.selected_pipeline().map_or(false, |pipeline| {
+            self.bot_fold(pipeline).is_some()
+                && pipeline.selected_issue == self.visible_issues(pipeline).len()
+        })
+    }
+
+    /// The selected card, none while its pipeline is collapsed.
+    fn selected_issue(&self) -> Option<IssueRef> {
+        self.board
+            .selected_pipeline()
+            .filter(|pipeline| !pipeline.collapsed)
+            .and_then(|pipeline| {
+                self.visible_issues(pipeline)
+                    .get(pipeline.selected_issue)
+                    .cloned()
+            })
+    }
+
+    /// The selected issue, unless it's from another repo on the board. Issues
+    /// of other repos are shown but can only be changed from their own board.
+    fn selected_local_issue(&self) -> Option<IssueRef> {
+        self.selected_issue()
+            .filter(|issue_ref| issue_ref.foreign_repo(self.properties.repo.id).is_none())
+    }
+
+    /// The issue shown in the detail pane, if its comments haven't been
+    /// requested yet. Comments are only loaded for issues which are viewed.
+    fn unloaded_detail_comments(&self) -> Option<IssueNumber> {
+        if !self.show_detail || self.overlay.is_some() {
+            return None;
+        }
+        self.selected_local_issue()
+            .map(|issue_ref| issue_ref.number)
+            .filter(|issue_number| !self.comments.contains_key(issue_number))
+    }
+
+    /// How many pipelines fit side by side at `min_column_width`, with the
+    /// dividers between them.
+    fn num_columns(&self) -> usize {
+        let min_column_width = self.properties.settings.min_column_width;
+        if min_column_width == 0 {
+            return usize::MAX;
+        }
+        let mut width = self.frame.size.width;
+        if self.show_detail && self.overlay.is_none() {
+            width = width.saturating_sub(self.detail_pane_width() + 1);
+        }
+        cmp::max((width + 1) / (min_column_width + 1), 1)
+    }
+
+    fn detail_pane_width(&self) -> usize {
+        self.clamp_detail_pane_width(
+            self.state
+                .detail_pane_width
+                .unwrap_or(DEFAULT_DETAIL_PANE_WIDTH),
+        )
+    }
+
+    fn clamp_detail_pane_width(&self, width: usize) -> usize {
+        cmp::max(
+            cmp::min(width, self.frame.size.width.saturating_sub(MIN_BOARD_WIDTH)),
+            MIN_DETAIL_PANE_WIDTH,
+        )
+    }
+
+    fn too_small_view(&self) -> Layout {
+        let blank = |key| {
+            layout::auto(layout::component_with_key::<Text>(
+                key,
+                TextProperties::new().style(self.theme.divider),
+            ))
+        };
+        layout::column([
+            blank(0),
+            layout::fixed(
+                1,
+                layout::component_with_key::<Text>(
+                    1,
+                    TextProperties::new()
+                        .content(format!(
+                            "Terminal too small: need {}x{}, have {}x{}",
+                            MIN_TERMINAL_WIDTH,
+                            MIN_TERMINAL_HEIGHT,
+                            self.frame.size.width,
+                            self.frame.size.height
+                        ))
+                        .style(self.theme.prompt.text)
+                        .align(TextAlign::Centre),
+                ),
+            ),
+            blank(2),
+        ])
+    }
+
+    fn save_state(&self) {
+        if let Some(path) = self.properties.state_path.as_ref() {
+            if let Err(error) = state::write_state(path, &self.state) {
+                log::error!("{:?}", error);
+            }
+        }
+    }
+
+    /// Leaves the board, remembering how it was for the next session.
+    fn exit(&mut self) {
+        self.write_cache();
+        self.wait_for_cache_writer();
+        self.save_board();
+        self.link.exit();
+    }
+
+    fn save_board(&mut self) {
+        if self.board.pipelines.is_empty() {
+            return;
+        }
+        let board = BoardState {
+            selected_issue: self.selected_issue().map(|issue_ref| issue_ref.number),
+            ..self.board.saved_state()
+        };
+        self.state.repo_mut(&self.properties.repo.full_name.0).board = Some(board);
+        self.save_state();
+    }
+
+    /// Restores the board as it was left last session, once it has loaded.
+    fn restore_board(&mut self) {
+        if self.board.pipelines.is_empty() {
+            return;
+        }
+        let saved = match self.saved_board.take() {
+            Some(saved) => saved,
+            None => return,
+        };
+        self.board.restore(&saved);
+        if let Some(team) = saved.filter.team {
+            self.load_team_members(team);
+        }
+        if let Some(issue_number) = saved.selected_issue {
+            self.focus_issue(issue_number);
+        }
+        self.clamp_selection();
+    }
+
+    /// Caches the board and the issues loaded so far for the next session,
+    /// if they changed since the last write. Serializing a large board takes
+    /// a while, so the cache is written on a thread of its own.
+    fn write_cache(&mut self) {
+        let path = match self.properties.cache_path {
+            Some(ref path) if self.cache_dirty && !self.board.pipelines.is_empty() => path.clone(),
+            _ => return,
+        };
+        let cache = BoardCache {
+            board: Board {
+                pipelines: self
+                    .board
+                    .pipelines
+                    .iter()
+                    .map(|pipeline| pipeline.pipeline.clone())
+                    .collect(),
+            },
+            issues: self
+                .issues
+                .values()
+                .filter_map(|issue| match issue {
+                    FutureValue::Ready(issue) => Some(issue.clone()),
+                    _ => None,
+                })
+                .collect(),
+            saved_at: Some(Utc::now()),
+        };
+        self.cache_dirty = false;
+        // One write at a time, so an older board never lands last
+        self.wait_for_cache_writer();
+        self.cache_writer = Some(thread::spawn(move || {
+            if let Err(error) = cache::write_cache(path, &cache) {
+                log::error!("{:?}", error);
+            }
+        }));
+    }
+
+    fn wait_for_cache_writer(&mut self) {
+        if let Some(cache_writer) = self.cache_writer.take() {
+            if cache_writer.join().is_err() {
+                log::error!("Writing the cache panicked");
+            }
+        }
+    }
+
+    /// Stores a loaded issue, or the error loading it.
+    fn insert_issue(&mut self, issue_number: IssueNumber, issue: FutureValue<Issue>) {
+        self.issues.insert(issue_number, issue);
+        self.cache_dirty = true;
+    }
+
+    fn record_activity(&mut self, activity: Activity) {
+        self.stats.record(activity);
+        if let Some(path) = self.properties.stats_path.as_ref() {
+            if let Err(error) = stats::write_stats(path, &self.stats) {
+                log::error!("{:?}", error);
+            }
+        }
+    }
+
+    /// Selects an issue wherever it is on the board, showing its pipeline if
+    /// it was hidden. Returns `false` if the issue isn't visible on the board.
+    fn focus_issue(&mut self, issue_number: IssueNumber) -> bool {
+        let board_repo = self.properties.repo.id;
+        let location =
+            self.board
+                .pipelines
+                .iter()
+                .enumerate()
+                .find_map(|(pipeline_index, pipeline)| {
+                    self.visible_issues(pipeline)
+                        .iter()
+                        .position(|issue_ref| issue_ref.refers_to(board_repo, issue_number))
+                        .map(|issue_index| (pipeline_index, issue_index))
+                });
+        match location {
+            Some((pipeline_index, issue_index)) => {
+                let pipeline = &mut self.board.pipelines[pipeline_index];
+                pipeline.hidden = false;
+                pipeline.selected_issue = issue_index;
+                self.board.selected_pipeline = pipeline_index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Short codes for the cards on screen, in board order: `aa`, `as`, `ad`,
+    /// ... Typing a code jumps to its card. While the codes are shown, each
+    /// pipeline shows the page of its selected card, see `jump_page_size`.
+    /// Cards from other repos get no code, as they're keyed by number.
+    fn jump_codes(&self) -> HashMap<IssueNumber, String> {
+        let board_repo = self.properties.repo.id;
+        let page_size = self.page_size();
+        self.board
+            .pipelines_in_view(self.num_columns())
+            .into_iter()
+            .map(|pipeline_index| &self.board.pipelines[pipeline_index])
+            .filter(|pipeline| !pipeline.collapsed)
+            .flat_map(|pipeline| {
+                let issue_refs = self.visible_issues(pipeline);
+                let num_cards = issue_refs.len() + self.bot_fold(pipeline).map_or(0, |_| 1);
+                let first_card = cmp::min(pipeline.selected_issue, num_cards.saturating_sub(1))
+                    / page_size
+                    * page_size;
+                issue_refs
+                    .into_iter()
+                    .skip(first_card)
+                    .take(page_size)
+                    .filter(move |issue_ref| issue_ref.foreign_repo(board_repo).is_none())
+            })
+            .take(JUMP_CODE_ALPHABET.len() * JUMP_CODE_ALPHABET.len())
+            .enumerate()
+            .map(|(index, issue_ref)| {
+                let first = JUMP_CODE_ALPHABET[index / JUMP_CODE_ALPHABET.len()];
+                let second = JUMP_CODE_ALPHABET[index % JUMP_CODE_ALPHABET.len()];
+                (issue_ref.number, format!("{}{}", first, second))
+            })
+            .collect()
+    }
+
+    fn overlay_content(&self, kind: OverlayKind) -> (String, Vec<Line>) {
+        match kind {
+            OverlayKind::Notifications => (
+                "Unread notifications (Enter to go to the issue)".into(),
+                match self.notifications {
+                    FutureValue::Pending => {
+                        vec![vec![(
+                            "Loading notifications...".into(),
+                            self.theme.overlay.dim,
+                        )]]
+                    }
+                    FutureValue::Ready(ref notifications) if notifications.is_empty() => {
+                        vec![vec![(
+                            "No unread notifications".into(),
+                            self.theme.overlay.dim,
+                        )]]
+                    }
+                    FutureValue::Ready(ref notifications) => notifications
+                        .iter()
+                        .map(|notification| {
+                            vec![
+                                (
+                                    format!("{:<18} ", notification.reason.replace('_', " ")),
+                                    self.theme.overlay.dim,
+                                ),
+                                (
+                                    notification
+                                        .issue_number()
+                                        .map_or_else(String::new, |number| {
+                                            format!("#{} ", number.0)
+                                        }),
+                                    self.theme.overlay.heading,
+                                ),
+                                (notification.subject.title.clone(), self.theme.overlay.text),
+                                (
+                                    format!(
+                                        "  {}",
+                                        notification
+                                            .updated_at
+                                            .with_timezone(&Local)
+                                            .format("%Y-%m-%d %H:%M")
+                                    ),
+                                    self.theme.overlay.dim,
+                                ),
+                            ]
+                        })
+                        .collect(),
+                    FutureValue::Error(ref message) => {
+                        vec![vec![(message.clone(), self.theme.overlay.heading)]]
+                    }
+                },
+            ),
+            OverlayKind::Analytics => (
+                "Estimate distribution".into(),
+                analytics::estimate_distribution(&self.board, &self.issues, &self.theme.overlay),
+            ),
+            OverlayKind::Workload => (
+                "Workload per assignee".into(),
+                analytics::workload_heat(&self.board, &self.issues, &self.theme.overlay),
+            ),
+            OverlayKind::Labels => (
+                "Labels on the board (Enter to filter)".into(),
+                self.visible_labels()
+                    .into_iter()
+                    .map(|(label, count)| {
+                        let active = self.board.filter.label.as_ref() == Some(&label.name);
+                        vec![
+                            (
+                                "  ".into(),
+                                Style::normal(
+                                    issue_card::label_colour(&label),
+                                    issue_card::label_colour(&label),
+                                ),
+                            ),
+                            (
+                                format!(" {}", label.name),
+                                if active {
+                                    self.theme.overlay.heading
+                                } else {
+                                    self.theme.overlay.text
+                                },
+                            ),
+                            (
+                                format!("  {}{}", count, if active { " (filtering)" } else { "" }),
+                                self.theme.overlay.dim,
+                            ),
+                        ]
+                    })
+                    .collect(),
+            ),
+            OverlayKind::Agenda => (
+                "Milestones and sprints".into(),
+                match self.agenda {
+                    FutureValue::Pending => {
+                        vec![vec![(
+                            "Loading milestones...".into(),
+                            self.theme.overlay.dim,
+                        )]]
+                    }
+                    FutureValue::Ready(ref milestones) => agenda::agenda(
+                        milestones,
+                        Local::today().naive_local(),
+                        &self.theme.overlay,
+                    ),
+                    FutureValue::Error(ref message) => {
+                        vec![vec![(message.clone(), self.theme.overlay.heading)]]
+                    }
+                },
+            ),
+            OverlayKind::Rename => (
+                match self.title_replace {
+                    Some(ref replace) => format!(
+                        "Replacing /{}/ with `{}` (Enter to skip, a to apply)",
+                        replace.pattern, replace.replacement
+                    ),
+                    None => String::new(),
+                },
+                self.renames()
+                    .into_iter()
+                    .map(|rename| {
+                        let skipped = self.rename_skipped.contains(&rename.issue_number);
+                        vec![
+                            (
+                                if skipped { "[ ] " } else { "[x] " }.into(),
+                                self.theme.overlay.heading,
+                            ),
+                            (
+                                format!("#{} {}", rename.issue_number.0, rename.old_title),
+                                self.theme.overlay.dim,
+                            ),
+                            (
+                                format!("  →  {}", rename.new_title),
+                                if skipped {
+                                    self.theme.overlay.dim
+                                } else {
+                                    self.theme.overlay.text
+                                },
+                            ),
+                        ]
+                    })
+                    .collect(),
+            ),
+            OverlayKind::Sweep => {
+                let stale = &self.properties.settings.stale;
+                (
+                    format!(
+                        "Issues untouched for {} (Enter to skip, c to nudge, s to label {}, \
+                         I to move to the Icebox, x to close)",
+                        humanize::count(stale.days.max(0) as usize, Noun::Day),
+                        stale.label
+                    ),
+                    sweep::stale_issues(&self.board, &self.issues, stale, Utc::now())
+                        .into_iter()
+                        .map(|stale_issue| {
+                            let skipped = self.sweep_skipped.contains(&stale_issue.issue.number);
+                            vec![
+                                (
+                                    if skipped { "[ ] " } else { "[x] " }.into(),
+                                    self.theme.overlay.heading,
+                                ),
+                                (
+                                    format!(
+                                        "#{} {}",
+                                        stale_issue.issue.number.0, stale_issue.issue.title
+                                    ),
+                                    if skipped {
+                                        self.theme.overlay.dim
+                                    } else {
+                                        self.theme.overlay.text
+                                    },
+                                ),
+                                (
+                                    format!(
+                                        "  {}, {}",
+                                        self.board.pipelines[stale_issue.pipeline].pipeline.name,
+                                        humanize::count(
+                                            stale_issue.days.max(0) as usize,
+                                            Noun::Day
+                                        )
+                                    ),
+                                    self.theme.overlay.dim,
+                                ),
+                            ]
+                        })
+                        .collect(),
+                )
+            }
+            OverlayKind::Changes => {
+                let title = "What changed since you last looked (Enter to go to the issue, \
+                             Esc to clear)"
+                    .into();
+                if self.board_changes.is_empty() {
+                    return (
+                        title,
+                        vec![vec![(
+                            "Nothing changed on the board".into(),
+                            self.theme.overlay.dim,
+                        )]],
+                    );
+                }
+                (
+                    title,
+                    self.board_changes
+                        .iter()
+                        .rev()
+                        .map(|change| {
+                            let title = match self.issue(&change.issue_ref) {
+                                Some(FutureValue::Ready(issue)) => issue.title.clone(),
+                                _ => String::new(),
+                            };
+                            vec![
+                                (
+                                    format!("{} ", change.at.format("%H:%M")),
+                                    self.theme.overlay.dim,
+                                ),
+                                (
+                                    format!("#{} ", change.issue_ref.number.0),
+                                    self.theme.overlay.heading,
+                                ),
+                                (
+                                    format!("{}  ", change.description()),
+                                    self.theme.overlay.text,
+                                ),
+                                (title, self.theme.overlay.dim),
+                            ]
+                        })
+                        .collect(),
+                )
+            }
+            OverlayKind::Title => {
+                let issue_ref = self.selected_issue();
+                match issue_ref
+                    .as_ref()
+                    .and_then(|issue_ref| self.issue(issue_ref))
+                {
+                    Some(FutureValue::Ready(issue)) => (
+                        format!("#{} by {}", issue.number.0, issue.user.login),
+                        issue_card::wrap_words(&issue.title, TITLE_OVERLAY_WIDTH)
+                            .into_iter()
+                            .map(|line| vec![(line, self.theme.overlay.text)])
+                            .collect(),
+                    ),
+                    _ => (
+                        issue_ref.map_or_else(
+                            || "No issue selected".into(),
+                            |issue_ref| format!("#{}", issue_ref.number.0),
+                        ),
+                        vec![vec![(
+                            "The issue hasn't loaded yet".into(),
+                            self.theme.overlay.dim,
+                        )]],
+                    ),
+                }
+            }
+            OverlayKind::Dependencies => {
+                let dependencies = self.selected_dependencies();
+                let title = match self.selected_issue() {
+                    Some(issue_ref) => format!(
+                        "Dependencies of #{} (Enter to go to the issue)",
+                        issue_ref.number.0
+                    ),
+                    None => "Dependencies".into(),
+                };
+                if dependencies.is_empty() {
+                    return (
+                        title,
+                        vec![vec![(
+                            "Nothing blocks the issue and it blocks nothing".into(),
+                            self.theme.overlay.dim,
+                        )]],
+                    );
+                }
+                (
+                    title,
+                    dependencies
+                        .into_iter()
+                        .map(|(relation, issue)| {
+                            let (title, closed) = match self.dependency_issue(&issue) {
+                                Some(FutureValue::Ready(loaded)) => {
+                                    (loaded.title.clone(), loaded.state == IssueState::Closed)
+                                }
+                                _ => (String::new(), false),
+                            };
+                            vec![
+                                (format!("{:<11}", relation), self.theme.overlay.dim),
+                                (format!("#{} ", issue.number.0), self.theme.overlay.heading),
+                                (
+                                    title,
+                                    if closed {
+                                        self.theme.overlay.dim
+                                    } else {
+                                        self.theme.overlay.text
+                                    },
+                                ),
+                                (
+                                    if closed {
+                                        " (closed)".into()
+                                    } else {
+                                        String::new()
+                                    },
+                                    self.theme.overlay.dim,
+                                ),
+                            ]
+                        })
+                        .collect(),
+                )
+            }
+            OverlayKind::Logs => (
+                format!("Logs at {} and above (1-5 to change level)", self.log_level),
+                self.properties
+                    .logs
+                    .as_ref()
+                    .map(|logs| logs.records(self.log_level))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|record| {
+                        let style = match record.level {
+                            Level::ERROR | Level::WARN => self.theme.overlay.heading,
+                            Level::INFO => self.theme.overlay.text,
+                            _ => self.theme.overlay.dim,
+                        };
+                        vec![
+                            (format!("{:<5} ", record.level.to_string()), style),
+                            (format!("{} ", record.target), self.theme.overlay.dim),
+                            (record.message, style),
+                        ]
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// All labels on the issues visible on the board, with the number of
+    /// issues they're on, most used first.
+    fn visible_labels(&self) -> Vec<(Label, usize)> {
+        let mut labels: HashMap<String, (Label, usize)> = HashMap::new();
+        for pipeline in self
+            .board
+            .pipelines
+            .iter()
+            .filter(|pipeline| !pipeline.hidden)
+        {
+            for issue_ref in self.visible_issues(pipeline).iter() {
+                if let Some(FutureValue::Ready(issue)) = self.issue(issue_ref) {
+                    for label in issue.labels.iter() {
+                        labels
+                            .entry(label.name.clone())
+                            .or_insert_with(|| (label.clone(), 0))
+                            .1 += 1;
+                    }
+                }
+            }
+        }
+        let mut labels: Vec<_> = labels.into_iter().map(|(_, entry)| entry).collect();
+        labels.sort_by(|(left, left_count), (right, right_count)| {
+            right_count
+                .cmp(left_count)
+                .then_with(|| left.name.cmp(&right.name))
+        });
+        labels
+    }
+
+    /// Selects the last log line if it was selected before new lines came in,
+    /// such that the log overlay follows the tail of the logs.
+    fn follow_logs(&mut self, force: bool) {
+        let num_lines = self.overlay_content(OverlayKind::Logs).1.len();
+        if let Some(overlay) = self.overlay.as_mut() {
+            if overlay.kind == OverlayKind::Logs
+                && (force || overlay.selected + 1 >= self.num_log_lines)
+            {
+                overlay.selected = num_lines.saturating_sub(1);
+            }
+        }
+        self.num_log_lines = num_lines;
+    }
+
+    fn update_crash_summary(&self, last_message: &'static str) {
+        let (num_loaded_issues, num_failed_issues) =
+            self.issues
+                .values()
+                .fold((0, 0), |(loaded, failed), issue| match issue {
+                    FutureValue::Ready(_) => (loaded + 1, failed),
+                    FutureValue::Error(_) => (loaded, failed + 1),
+                    FutureValue::Pending => (loaded, failed),
+                });
+        self.properties.crash_reporter.set_summary(AppSummary {
+            repo: self.properties.repo.full_name.0.clone(),
+            num_pipelines: self.board.pipelines.len(),
+            num_issues: self
+                .board
+                .pipelines
+                .iter()
+                .map(|pipeline| pipeline.pipeline.issues.len())
+                .sum(),
+            num_loaded_issues,
+            num_failed_issues,
+            num_pending_tasks: self.num_pending_tasks,
+            last_message,
+        });
+    }
+
+    fn accept_overlay(&mut self) {
+        let overlay = match self.overlay {
+            Some(overlay) => overlay,
+            None => return,
+        };
+        match overlay.kind {
+            OverlayKind::Notifications => {
+                let issue_number = match self.notifications {
+                    FutureValue::Ready(ref notifications) => notifications
+                        .get(overlay.selected)
+                        .and_then(|notification| notification.issue_number()),
+                    _ => None,
+                };
+                if let Some(issue_number) = issue_number {
+                    if self.focus_issue(issue_number) {
+                        self.overlay = None;
+                    } else {
+                        self.status = format!("#{} is not on the board", issue_number.0);
+                    }
+                }
+            }
+            OverlayKind::Analytics
+            | OverlayKind::Workload
+            | OverlayKind::Logs
+            | OverlayKind::Agenda
+            | OverlayKind::Title => {}
+            OverlayKind::Changes => {
+                let issue_ref = self
+                    .board_changes
+                    .iter()
+                    .rev()
+                    .nth(overlay.selected)
+                    .map(|change| change.issue_ref.clone());
+                if let Some(issue_ref) = issue_ref {
+                    if issue_ref.foreign_repo(self.properties.repo.id).is_none()
+                        && self.focus_issue(issue_ref.number)
+                    {
+                        self.overlay = None;
+                    } else {
+                        self.status = format!("#{} is not on the board", issue_ref.number.0);
+                    }
+                }
+            }
+            OverlayKind::Dependencies => {
+                if let Some((_, issue)) = self.selected_dependencies().get(overlay.selected) {
+                    if issue.repo_id == self.properties.repo.id && self.focus_issue(issue.number) {
+                        self.overlay = None;
+                    } else {
+                        self.status = format!("#{} is not on the board", issue.number.0);
+                    }
+                }
+            }
+            OverlayKind::Rename => {
+                if let Some(rename) = self.renames().get(overlay.selected) {
+                    let issue_number = rename.issue_number;
+                    if self.rename_skipped.remove(&issue_number).is_none() {
+                        self.rename_skipped.insert(issue_number);
+                    }
+                }
+            }
+            OverlayKind::Sweep => {
+                let stale_issues = sweep::stale_issues(
+                    &self.board,
+                    &self.issues,
+                    &self.properties.settings.stale,
+                    Utc::now(),
+                );
+                if let Some(stale_issue) = stale_issues.get(overlay.selected) {
+                    let issue_number = stale_issue.issue.number;
+                    if self.sweep_skipped.remove(&issue_number).is_none() {
+                        self.sweep_skipped.insert(issue_number);
+                    }
+                }
+            }
+            OverlayKind::Labels => {
+                if let Some((label, _)) = self.visible_labels().into_iter().nth(overlay.selected) {
+                    if self.board.filter.label.as_ref() == Some(&label.name) {
+                        self.board.filter.label = None;
+                        self.status.clear();
+                    } else {
+                        self.status = format!("Showing issues labelled {}", label.name);
+                        self.board.filter.label = Some(label.name);
+                    }
+                    self.board
+                        .pipelines
+                        .iter_mut()
+                        .for_each(|pipeline| pipeline.selected_issue = 0);
+                    self.overlay = None;
+                }
+            }
+        }
+    }
+
+    fn relationship(&self, issue: &Issue) -> Option<Relationship> {
+        let login = &self.properties.user.login;
+        let review_requested =
+            self.pull_requests
+                .get(&issue.number)
+                .map_or(false, |pull_request| {
+                    pull_request
+                        .requested_reviewers
+                        .iter()
+                        .any(|reviewer| &reviewer.login == login)
+                });
+        if review_requested {
+            Some(Relationship::ReviewRequested)
+        } else if issue
+            .assignees
+            .iter()
+            .any(|assignee| &assignee.login == login)
+        {
+            Some(Relationship::Assignee)
+        } else if &issue.user.login == login {
+            Some(Relationship::Author)
+        } else {
+            None
+        }
+    }
+
+    fn relationships(&self) -> HashMap<IssueNumber, Relationship> {
+        self.issues
+            .iter()
+            .filter_map(|(issue_number, issue)| match issue {
+                FutureValue::Ready(issue) => self
+                    .relationship(issue)
+                    .map(|relationship| (*issue_number, relationship)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn spawn<FutureT>(&mut self, future: FutureT)
+    where
+        FutureT: Future<Output = Message> + Send + 'static,
+    {
+        let link = self.link.clone();
+        self.num_pending_tasks += 1;
+        self.properties
+            .async_runtime
+            .spawn(future.map(move |message| link.send(message)));
+    }
+
+    /// Opens the local checkout of the repo in the editor, with a scratch file
+    /// for the selected issue.
+    fn open_project(&mut self) {
+        let issue = match self
+            .selected_local_issue()
+            .and_then(|issue_ref| self.issues.get(&issue_ref.number))
+        {
+            Some(FutureValue::Ready(issue)) => issue.clone(),
+            _ => return,
+        };
+        let repo = self.properties.repo.full_name.clone();
+        let local_repo = match self.properties.settings.local_repo(&repo.0) {
+            Some(local_repo) => local_repo.clone(),
+            None => {
+                self.status = format!(
+                    "No local checkout of {} in the settings, add it to `local_repos`",
+                    repo.0
+                );
+                return;
+            }
+        };
+        let editor = self.properties.settings.editor.clone();
+        self.link.run_exclusive(move || {
+            Some(Message::OpenedProject(
+                issue.number,
+                project::open_in_editor(editor.as_deref(), &local_repo.path, &repo, &issue),
+            ))
+        });
+    }
+
+    /// Makes the next commit in the local checkout of the repo reference the
+    /// selected issue.
+    fn write_commit_template(&mut self) {
+        let issue = match self
+            .selected_local_issue()
+            .and_then(|issue_ref| self.issues.get(&issue_ref.number))
+        {
+            Some(FutureValue::Ready(issue)) => issue.clone(),
+            _ => return,
+        };
+        let repo = &self.properties.repo.full_name;
+        self.status = match self.properties.settings.local_repo(&repo.0) {
+            Some(local_repo) => match project::write_commit_template(&local_repo.path, &issue) {
+                Ok(path) => format!(
+                    "Next commit will reference #{} (template in `{}`)",
+                    issue.number.0,
+                    path.display()
+                ),
+                Err(error) => {
+                    log::error!("{:?}", error);
+                    format!("Could not write commit template: {}", error)
+                }
+            },
+            None => format!(
+                "No local checkout of {} in the settings, add it to `local_repos`",
+                repo.0
+            ),
+        };
+    }
+
+    /// Asks which label to add to or remove from the selected issue. The
+    /// repo's labels are loaded the first time.
+    fn pick_label(&mut self) {
+        let issue = match self
+            .selected_local_issue()
+            .and_then(|issue_ref| self.issues.get(&issue_ref.number))
+        {
+            Some(FutureValue::Ready(issue)) => issue,
+            _ => return,
+        };
+        let labels = match self.repo_labels.as_ref() {
+            Some(labels) => labels.clone(),
+            None => {
+                let repo = Arc::new(self.properties.repo.full_name.clone());
+                self.status = "Loading labels...".into();
+                self.spawn(
+                    self.properties
+                        .github_client
+                        .clone()
+                        .get_labels(repo)
+                        .map(Message::LoadedLabels),
+                );
+                return;
+            }
+        };
+        let items = labels
+            .iter()
+            .map(|label| {
+                let applied = issue
+                    .labels
+                    .iter()
+                    .any(|applied| applied.name == label.name);
+                format!("{} {}", if applied { "✓" } else { " " }, label.name)
+            })
+            .collect();
+        self.picker = Some(Picker::new(
+            format!("Toggle label on #{}", issue.number.0),
+            PickerAction::ToggleLabel(issue.number, labels),
+            items,
+        ));
+    }
+
+    /// Adds the label to the issue, or removes it if the issue has it. The
+    /// card is updated straight away and reverted if Github refuses.
+    fn toggle_label(&mut self, issue_number: IssueNumber, label: Label) {
+        let issue = match self.issues.get_mut(&issue_number) {
+            Some(FutureValue::Ready(issue)) => issue,
+            _ => return,
+        };
+        self.cache_dirty = true;
+        let previous = issue.clone();
+        match issue
+            .labels
+            .iter()
+            .position(|applied| applied.name == label.name)
+        {
+            Some(index) => {
+                issue.labels.remove(index);
+            }
+            None => issue.labels.push_back(label),
+        }
+        let labels = issue
+            .labels
+            .iter()
+            .map(|label| label.name.clone())
+            .collect();
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        self.spawn(
+            self.properties
+                .github_client
+                .clone()
+                .set_labels(repo, issue_number, labels)
+                .map(move |result| Message::ToggledLabel(previous, result)),
+        );
+    }
+
+    /// Sets the estimate of an issue in story points, or clears it. The card
+    /// is updated straight away and reverted if Zenhub refuses.
+    fn set_estimate(&mut self, issue_number: IssueNumber, points: Option<f64>) {
+        let previous =
+            match self.replace_estimate(issue_number, points.map(|value| Estimate { value })) {
+                Some(previous) => previous,
+                None => return,
+            };
+        self.local_estimates.insert(issue_number, Instant::now());
+        self.status = match points {
+            Some(points) => format!(
+                "Estimating #{} at {}",
+                issue_number.0,
+                humanize::points(points)
+            ),
+            None => format!("Clearing the estimate of #{}", issue_number.0),
+        };
+        self.spawn(
+            self.properties
+                .zenhub_client
+                .clone()
+                .set_estimate(self.properties.repo.id, issue_number, points)
+                .map(move |result| Message::EstimatedIssue(issue_number, previous, result)),
+        );
+    }
+
+    /// Gives all the marked issues the same estimate, or clears theirs.
+    fn estimate_marked(&mut self, points: Option<f64>) {
+        if self.bulk_estimate.is_some() {
+            self.status = "Still estimating the previously marked issues".into();
+            return;
+        }
+        let mut pending = VecDeque::new();
+        for issue_number in self.marked_in_board_order() {
+            if let Some(previous) =
+                self.replace_estimate(issue_number, points.map(|value| Estimate { value }))
+            {
+                self.local_estimates.insert(issue_number, Instant::now());
+                pending.push_back((issue_number, previous));
+            }
+        }
+        self.bulk_estimate = Some(BulkEstimate {
+            points,
+            num_issues: pending.len(),
+            pending,
+            num_done: 0,
+            failed: Vec::new(),
+        });
+        self.estimate_next_batch();
+    }
+
+    /// Sends the next batch of estimates of a bulk estimate, or reports how
+    /// it went once all are done.
+    fn estimate_next_batch(&mut self) {
+        let bulk = match self.bulk_estimate {
+            Some(ref mut bulk) => bulk,
+            None => return,
+        };
+        let batch_size = cmp::min(ESTIMATE_BATCH_SIZE, bulk.pending.len());
+        let batch: Vec<_> = bulk.pending.drain(..batch_size).collect();
+
+        if batch.is_empty() {
+            let bulk = self
+                .bulk_estimate
+                .take()
+                .expect("a bulk estimate is running");
+            let num_estimated = bulk.num_issues - bulk.failed.len();
+            self.status = if bulk.failed.is_empty() {
+                self.marked_issues.clear();
+                match bulk.points {
+                    Some(points) => format!(
+                        "Estimated {} at {}",
+                        humanize::count(num_estimated, Noun::Issue),
+                        humanize::points(points)
+                    ),
+                    None => format!(
+                        "Cleared the estimates of {}",
+                        humanize::count(num_estimated, Noun::Issue)
+                    ),
+                }
+            } else {
+                // The issues which failed stay marked, to try them again
+                self.marked_issues
+                    .retain(|issue_number| bulk.failed.contains(issue_number));
+                format!(
+                    "Could not change the estimates of {}, they are still marked",
+                    bulk.failed
+                        .iter()
+                        .map(|issue_number| format!("#{}", issue_number.0))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+            return;
+        }
+
+        self.status = format!(
+            "Estimating {}/{}...",
+            bulk.num_done + batch.len(),
+            humanize::count(bulk.num_issues, Noun::Issue)
+        );
+        let zenhub_client = self.properties.zenhub_client.clone();
+        let repo_id = self.properties.repo.id;
+        let points = bulk.points;
+        self.spawn(
+            future::join_all(batch.into_iter().map(move |(issue_number, previous)| {
+                zenhub_client
+                    .clone()
+                    .set_estimate(repo_id, issue_number, points)
+                    .map(move |result| (issue_number, previous, result))
+            }))
+            .map(Message::EstimatedBatch),
+        );
+    }
+
+    /// Steps the estimate of the selected issue up or down the sequence in
+    /// the settings, if that changes it.
+    fn step_selected_estimate(&self, up: bool) -> Option<Message> {
+        let issue_ref = self.selected_local_issue()?;
+        let current = issue_ref.estimate.map(|estimate| estimate.value);
+        let points = self.properties.settings.step_estimate(current, up);
+        if points == current {
+            None
+        } else {
+            Some(Message::SetEstimate(issue_ref.number, points))
+        }
+    }
+
+    /// Changes the estimate of an issue on the board, returning the previous
+    /// one, or `None` if the issue isn't on the board.
+    fn replace_estimate(
+        &mut self,
+        issue_number: IssueNumber,
+        estimate: Option<Estimate>,
+    ) -> Option<Option<Estimate>> {
+        let board_repo = self.properties.repo.id;
+        let previous = self.board.pipelines.iter_mut().find_map(|pipeline| {
+            pipeline
+                .pipeline
+                .issues
+                .iter_mut()
+                .find(|issue_ref| issue_ref.refers_to(board_repo, issue_number))
+                .map(|issue_ref| mem::replace(&mut issue_ref.estimate, estimate))
+        });
+        self.cache_dirty |= previous.is_some();
+        previous
+    }
+
+    /// Loads the open releases of the repo, together with their issues.
+    fn load_releases(&mut self) {
+        let zenhub_client = self.properties.zenhub_client.clone();
+        let repo_id = self.properties.repo.id;
+        self.spawn(
+            async move {
+                let releases = zenhub_client.clone().get_releases(repo_id).await?;
+                let mut open_releases = Vector::new();
+                for release in releases
+                    .into_iter()
+                    .filter(|release| release.state == ReleaseState::Open)
+                {
+                    let issues = zenhub_client
+                        .clone()
+                        .get_release_issues(release.id.clone())
+                        .await?;
+                    open_releases.push_back((release, issues));
+                }
+                Ok::<_, zentui_client::Error>(open_releases)
+            }
+            .map(Message::LoadedReleases),
+        );
+    }
+
+    fn load_dependencies(&mut self) {
+        self.spawn(
+            self.properties
+                .zenhub_client
+                .clone()
+                .get_dependencies(self.properties.repo.id)
+                .map(Message::LoadedDependencies),
+        );
+    }
+
+    /// The issue at one end of a dependency, which may be from another repo.
+    fn dependency_issue(&self, issue: &EpicIssue) -> Option<&FutureValue<Issue>> {
+        if issue.repo_id == self.properties.repo.id {
+            self.issues.get(&issue.number)
+        } else {
+            self.foreign_issues.get(&(issue.repo_id, issue.number))
+        }
+    }
+
+    /// The issues of the board waiting on another issue which isn't closed.
+    /// Blocking issues which haven't loaded are assumed to be open.
+    fn blocked_issues(&self) -> HashSet<IssueNumber> {
+        let repo_id = self.properties.repo.id;
+        self.dependencies
+            .iter()
+            .filter(|dependency| dependency.blocked.repo_id == repo_id)
+            .filter(
+                |dependency| match self.dependency_issue(&dependency.blocking) {
+                    Some(FutureValue::Ready(issue)) => issue.state != IssueState::Closed,
+                    _ => true,
+                },
+            )
+            .map(|dependency| dependency.blocked.number)
+            .collect()
+    }
+
+    /// What blocks the selected issue, then what it blocks.
+    fn selected_dependencies(&self) -> Vec<(&'static str, EpicIssue)> {
+        let selected = match self.selected_issue() {
+            Some(issue_ref) => EpicIssue {
+                number: issue_ref.number,
+                repo_id: issue_ref.repo_id.unwrap_or(self.properties.repo.id),
+            },
+            None => return Vec::new(),
+        };
+        let blocked_by = self
+            .dependencies
+            .iter()
+            .filter(|dependency| dependency.blocked == selected)
+            .map(|dependency| ("blocked by", dependency.blocking.clone()));
+        let blocks = self
+            .dependencies
+            .iter()
+            .filter(|dependency| dependency.blocking == selected)
+            .map(|dependency| ("blocks", dependency.blocked.clone()));
+        blocked_by.chain(blocks).collect()
+    }
+
+    /// The titles of the open releases each issue is part of.
+    fn issue_releases(&self) -> HashMap<IssueNumber, Vector<String>> {
+        let mut issue_releases: HashMap<IssueNumber, Vector<String>> = HashMap::new();
+        for release in self.releases.iter().flatten() {
+            for issue_number in self.release_issues.get(&release.id).into_iter().flatten() {
+                issue_releases
+                    .entry(*issue_number)
+                    .or_default()
+                    .push_back(release.title.clone());
+            }
+        }
+        issue_releases
+    }
+
+    /// Opens a picker of the open releases to add the selected issue to, or
+    /// remove it from.
+    fn pick_release(&mut self) {
+        let issue_number = match self.selected_local_issue() {
+            Some(issue_ref) => issue_ref.number,
+            None => return,
+        };
+        let releases = match self.releases.as_ref() {
+            Some(releases) if releases.is_empty() => {
+                self.status = format!("{} has no open releases", self.properties.repo.full_name.0);
+                return;
+            }
+            Some(releases) => releases.clone(),
+            None => {
+                self.status = "Releases are still loading".into();
+                return;
+            }
+        };
+        let items = releases
+            .iter()
+            .map(|release| {
+                let included = self
+                    .release_issues
+                    .get(&release.id)
+                    .map_or(false, |issues| issues.contains(&issue_number));
+                format!("{} {}", if included { "✓" } else { " " }, release.title)
+            })
+            .collect();
+        self.picker = Some(Picker::new(
+            format!("Toggle release of #{}", issue_number.0),
+            PickerAction::ToggleRelease(issue_number, releases),
+            items,
+        ));
+    }
+
+    /// Adds the issue to the release, or removes it if it's already part of
+    /// it. The card is updated straight away and reverted if Zenhub refuses.
+    fn toggle_release(&mut self, issue_number: IssueNumber, release: Release) {
+        let issues = self.release_issues.entry(release.id.clone()).or_default();
+        let added = issues.remove(&issue_number).is_none();
+        if added {
+            issues.insert(issue_number);
+        }
+        let issue = vec![EpicIssue {
+            number: issue_number,
+            repo_id: self.properties.repo.id,
+        }];
+        let (add_issues, remove_issues) = if added {
+            (issue, Vec::new())
+        } else {
+            (Vec::new(), issue)
+        };
+        let release_id = release.id;
+        self.spawn(
+            self.properties
+                .zenhub_client
+                .clone()
+                .update_release_issues(release_id.clone(), add_issues, remove_issues)
+                .map(move |result| {
+                    Message::ToggledRelease(issue_number, release_id, added, result)
+                }),
+        );
+    }
+
+    /// Asks which issue on the board the selected pull request works on.
+    fn pick_issue_to_connect(&mut self) {
+        let pull_request = match self
+            .selected_local_issue()
+            .and_then(|issue_ref| self.issues.get(&issue_ref.number))
+        {
+            Some(FutureValue::Ready(issue)) if issue.pull_request.is_some() => issue.number,
+            Some(FutureValue::Ready(_)) => {
+                self.status = "Select a pull request to connect it to an issue".into();
+                return;
+            }
+            _ => return,
+        };
+        let issues: Vec<_> = self
+            .board
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| pipeline.pipeline.issues.iter())
+            .filter(|issue_ref| issue_ref.foreign_repo(self.properties.repo.id).is_none())
+            .filter_map(|issue_ref| match self.issues.get(&issue_ref.number) {
+                Some(FutureValue::Ready(issue)) if issue.pull_request.is_none() => Some(issue),
+                _ => None,
+            })
+            .collect();
+        if issues.is_empty() {
+            self.status = "No issues loaded to connect the pull request to".into();
+            return;
+        }
+        self.picker = Some(Picker::new(
+            format!("Connect #{} to", pull_request.0),
+            PickerAction::ConnectPullRequest(
+                pull_request,
+                issues.iter().map(|issue| issue.number).collect(),
+            ),
+            issues
+                .iter()
+                .map(|issue| format!("#{} {}", issue.number.0, issue.title))
+                .collect(),
+        ));
+    }
+
+    fn connect_pull_request(&mut self, pull_request: IssueNumber, issue_number: IssueNumber) {
+        let repo_id = self.properties.repo.id;
+        self.status = format!("Connecting #{} to #{}...", pull_request.0, issue_number.0);
+        self.spawn(
+            self.properties
+                .zenhub_client
+                .clone()
+                .connect_pull_request(repo_id, issue_number, repo_id, pull_request)
+                .map(move |result| {
+                    Message::ConnectedPullRequest(pull_request, issue_number, result)
+                }),
+        );
+    }
+
+    /// Shares the selected issue to the webhook in the settings, asking which
+    /// one if there are several.
+    fn pick_webhook(&mut self) {
+        let issue = match self
+            .selected_local_issue()
+            .and_then(|issue_ref| self.issues.get(&issue_ref.number))
+        {
+            Some(FutureValue::Ready(issue)) => issue.clone(),
+            _ => return,
+        };
+        let webhooks = &self.properties.settings.webhooks;
+        match webhooks.len() {
+            0 => self.status = "No webhooks in the settings, add one to `webhooks`".into(),
+            1 => {
+                let webhook = webhooks[0].clone();
+                self.share_issue(issue, webhook);
+            }
+            _ => {
+                self.picker = Some(Picker::new(
+                    format!("Share #{} to", issue.number.0),
+                    PickerAction::Share(issue, webhooks.clone()),
+                    webhooks
+                        .iter()
+                        .map(|webhook| webhook.name.clone())
+                        .collect(),
+                ));
+            }
+        }
+    }
+
+    fn share_issue(&mut self, issue: Issue, webhook: Webhook) {
+        let pipeline = self
+            .issue_location(issue.number)
+            .map(|(pipeline_index, _)| self.board.pipelines[pipeline_index].pipeline.name.clone())
+            .unwrap_or_default();
+        let text = share::render(
+            &webhook.template,
+            &self.properties.repo.full_name,
+            &issue,
+            &pipeline,
+        );
+        let issue_number = issue.number;
+        let Webhook {
+            name, url, kind, ..
+        } = webhook;
+        self.status = format!("Sharing #{} to {}...", issue_number.0, name);
+        self.spawn(
+            self.properties
+                .webhook_client
+                .clone()
+                .post_message(kind, url, text)
+                .map(move |result| Message::SharedIssue(issue_number, name, result)),
+        );
+    }
+
+    /// Runs the triage action bound to `key` on the selected issue.
+    fn triage_selected_issue(&mut self, key: char) {
+        let (issue_number, action) = match (
+            self.selected_local_issue(),
+            self.properties.settings.triage_action(key),
+        ) {
+            (Some(issue_ref), Some(action)) => (issue_ref.number, action.clone()),
+            _ => return,
+        };
+        let github_client = self.properties.github_client.clone();
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        let TriageAction {
+            labels,
+            comment,
+            close,
+            ..
+        } = action.clone();
+        self.status = format!("Triaging #{}...", issue_number.0);
+        self.spawn(
+            async move {
+                if !labels.is_empty() {
+                    github_client
+                        .clone()
+                        .add_labels(repo.clone(), issue_number, labels)
+                        .await?;
+                }
+                if let Some(comment) = comment {
+                    github_client
+                        .clone()
+                        .add_comment(repo.clone(), issue_number, comment)
+                        .await?;
+                }
+                if close {
+                    github_client.close_issue(repo, issue_number).await
+                } else {
+                    github_client.get_issue(repo, issue_number).await
+                }
+            }
+            .map(move |result| Message::TriagedIssue(action, result)),
+        );
+    }
+
+    /// Asks where to put a new issue in the selected pipeline, instead of
+    /// the `new_issue_position` from the settings.
+    fn ask_new_issue_position(&mut self) {
+        let mut positions = vec![NewIssuePosition::Top, NewIssuePosition::Bottom];
+        let mut choices = vec!["At the top".to_owned(), "At the bottom".to_owned()];
+        if let Some(issue_ref) = self.selected_issue() {
+            positions.push(NewIssuePosition::AfterSelected);
+            choices.push(format!("After #{}", issue_ref.number.0));
+        }
+        self.question = Some(Question::select(
+            "Create the issue",
+            choices,
+            QuestionAction::NewIssue(positions),
+        ));
+    }
+
+    /// Opens the editor with a template for a new issue in the selected
+    /// pipeline: its title goes on the first line, followed by its body.
+    fn edit_new_issue(&mut self, new_issue_position: NewIssuePosition) {
+        let pipeline_index = self.board.selected_pipeline;
+        let pipeline = match self.board.pipelines.get(pipeline_index) {
+            Some(pipeline) => &pipeline.pipeline,
+            None => return,
+        };
+        if pipeline.kind() == PipelineKind::Closed {
+            self.status = "Issues can't be created in the Closed pipeline".into();
+            return;
+        }
+        let pipeline_id = pipeline.id.clone();
+        let position = self.new_issue_position(pipeline_index, new_issue_position);
+        self.link.run_exclusive(move || {
+            Some(Message::EditedNewIssue(
+                pipeline_id,
+                position,
+                edit::edit(&format!("\n\n{}\n", NEW_ISSUE_HINT)),
+            ))
+        });
+    }
+
+    /// The Zenhub position of a new issue in a pipeline. Positions count the
+    /// cards hidden by filters too, so the selected card is looked up in the
+    /// whole pipeline.
+    fn new_issue_position(
+        &self,
+        pipeline_index: PipelineIndex,
+        new_issue_position: NewIssuePosition,
+    ) -> Position {
+        match new_issue_position {
+            NewIssuePosition::Top => Position::Top,
+            NewIssuePosition::Bottom => Position::Bottom,
+            NewIssuePosition::AfterSelected => {
+                let pipeline = &self.board.pipelines[pipeline_index];
+                self.visible_issues(pipeline)
+                    .get(pipeline.selected_issue)
+                    .and_then(|selected| {
+                        pipeline.pipeline.issues.iter().position(|issue_ref| {
+                            issue_ref.number == selected.number
+                                && issue_ref.repo_id == selected.repo_id
+                        })
+                    })
+                    .map_or(Position::Top, |index| Position::Index(index + 1))
+            }
+        }
+    }
+
+    /// Opens the edited issue on Github and moves it to its position in the
+    /// pipeline it was created from.
+    fn create_issue(&mut self, pipeline_id: String, position: Position, text: String) {
+        let mut lines = text.splitn(2, '\n');
+        let title = lines.next().unwrap_or_default().trim().to_owned();
+        let body = lines
+            .next()
+            .unwrap_or_default()
+            .replace(NEW_ISSUE_HINT, "")
+            .trim()
+            .to_owned();
+        if title.is_empty() {
+            self.status = "No issue was created, its title was empty".into();
+            return;
+        }
+
+        let pipeline = match self.board.pipeline_with_id(&pipeline_id) {
+            Some(pipeline_index) => &self.board.pipelines[pipeline_index].pipeline,
+            None => {
+                self.status = "No issue was created, its pipeline is no longer on the board".into();
+                return;
+            }
+        };
+        let github_client = self.properties.github_client.clone();
+        let zenhub_client = self.properties.zenhub_client.clone();
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        let repo_id = self.properties.repo.id;
+        self.status = format!("Creating issue in {}...", pipeline.name);
+        let move_to = pipeline_id.clone();
+        self.spawn(
+            async move {
+                let issue = github_client.create_issue(repo, title, body).await?;
+                zenhub_client
+                    .move_issue(repo_id, issue.number, move_to, position)
+                    .await
+                    .map(|()| issue)
+            }
+            .map(move |result| Message::CreatedIssue(pipeline_id, position, result)),
+        );
+    }
+
+    /// The marked issues, in the order they appear on the board.
+    fn marked_in_board_order(&self) -> Vector<IssueNumber> {
+        self.board
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| pipeline.pipeline.issues.iter())
+            .map(|issue_ref| issue_ref.number)
+            .filter(|issue_number| self.marked_issues.contains(issue_number))
+            .collect()
+    }
+
+    /// Opens the editor with a new epic for the marked issues: its title goes
+    /// on the first line, followed by a checklist of the issues as its body.
+    fn edit_epic_from_marked(&mut self) {
+        let children = self.marked_in_board_order();
+        if children.is_empty() {
+            self.status = "Mark issues with `v` to create an epic from them".into();
+            return;
+        }
+        let mut template = String::from("\n\n");
+        for issue_number in children.iter() {
+            match self.issues.get(issue_number) {
+                Some(FutureValue::Ready(issue)) => {
+                    template.push_str(&format!("- [ ] #{} {}\n", issue_number.0, issue.title))
+                }
+                _ => template.push_str(&format!("- [ ] #{}\n", issue_number.0)),
+            }
+        }
+        self.link
+            .run_exclusive(move || Some(Message::EditedEpic(edit::edit(&template))));
+    }
+
+    /// Opens the edited epic as a Github issue and makes the marked issues its
+    /// children on Zenhub.
+    fn create_epic(&mut self, text: String) {
+        let mut lines = text.splitn(2, '\n');
+        let title = lines.next().unwrap_or_default().trim().to_owned();
+        let body = lines.next().unwrap_or_default().trim().to_owned();
+        if title.is_empty() {
+            self.status = "No epic was created, its title was empty".into();
+            return;
+        }
+
+        let children = self.marked_in_board_order();
+        let github_client = self.properties.github_client.clone();
+        let zenhub_client = self.properties.zenhub_client.clone();
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        let repo_id = self.properties.repo.id;
+        let epic_issues = children
+            .iter()
+            .map(|issue_number| EpicIssue {
+                number: *issue_number,
+                repo_id,
+            })
+            .collect();
+        self.status = format!(
+            "Creating an epic for {}...",
+            humanize::count(children.len(), Noun::Issue)
+        );
+        self.spawn(
+            async move {
+                let epic = github_client.create_issue(repo, title, body).await?;
+                zenhub_client
+                    .convert_to_epic(repo_id, epic.number, epic_issues)
+                    .await
+                    .map(|()| epic)
+            }
+            .map(move |result| Message::CreatedEpic(children, result)),
+        );
+    }
+
+    /// Copies the visible issues of the selected pipeline to the clipboard as
+    /// a markdown checklist, e.g. for meeting notes.
+    fn copy_pipeline_markdown(&mut self) {
+        let pipeline = match self.board.selected_pipeline() {
+            Some(pipeline) => pipeline,
+            None => return,
+        };
+        let issue_refs = self.visible_issues(pipeline);
+        let mut markdown = format!("**{}**\n\n", pipeline.pipeline.name);
+        for issue_ref in issue_refs.iter() {
+            // Github links `owner/repo#123` references to other repos
+            let reference = match issue_ref.foreign_repo(self.properties.repo.id) {
+                Some(_) => match self.issue_repo(issue_ref) {
+                    Some(repo) => format!("{}#{}", repo.0, issue_ref.number.0),
+                    None => format!("#{}", issue_ref.number.0),
+                },
+                None => format!("#{}", issue_ref.number.0),
+            };
+            match self.issue(issue_ref) {
+                Some(FutureValue::Ready(issue)) => {
+                    markdown.push_str(&format!(
+                        "- [{}] {} {}",
+                        if issue.state == IssueState::Closed {
+                            "x"
+                        } else {
+                            " "
+                        },
+                        reference,
+                        issue.title
+                    ));
+                    if !issue.assignees.is_empty() {
+                        let assignees: Vec<_> = issue
+                            .assignees
+                            .iter()
+                            .map(|assignee| format!("@{}", assignee.login))
+                            .collect();
+                        markdown.push_str(&format!(" ({})", assignees.join(", ")));
+                    }
+                }
+                _ => markdown.push_str(&format!("- [ ] {}", reference)),
+            }
+            markdown.push('\n');
+        }
+
+        let num_issues = issue_refs.len();
+        let name = pipeline.pipeline.name.clone();
+        self.status = match clipboard::copy(&markdown) {
+            Ok(()) => format!(
+                "Copied {} in {} as markdown",
+                humanize::count(num_issues, Noun::Issue),
+                name
+            ),
+            Err(error) => {
+                log::error!("{:?}", error);
+                format!("Could not copy to the clipboard: {}", error)
+            }
+        };
+    }
+
+    /// Loads the issues of the pipeline which weren't yet, then drafts a
+    /// changelog of all of them.
+    fn draft_changelog(&mut self, pipeline_index: PipelineIndex, output: ChangelogOutput) {
+        let unloaded: Vec<_> = match self.board.pipelines.get(pipeline_index) {
+            Some(pipeline) => pipeline
+                .pipeline
+                .issues
+                .iter()
+                .filter(|issue_ref| issue_ref.foreign_repo(self.properties.repo.id).is_none())
+                .map(|issue_ref| issue_ref.number)
+                .filter(|issue_number| {
+                    !matches!(self.issues.get(issue_number), Some(FutureValue::Ready(_)))
+                })
+                .collect(),
+            None => return,
+        };
+        self.status = "Drafting the changelog...".into();
+        let github_client = self.properties.github_client.clone();
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        self.spawn(
+            future::join_all(unloaded.into_iter().map(move |issue_number| {
+                github_client
+                    .clone()
+                    .get_issue(repo.clone(), issue_number)
+                    .map(move |result| (issue_number, result))
+            }))
+            .map(move |loaded| Message::DraftChangelog(pipeline_index, output.clone(), loaded)),
+        );
+    }
+
+    fn write_changelog(&mut self, pipeline_index: PipelineIndex, output: ChangelogOutput) {
+        let pipeline = match self.board.pipelines.get(pipeline_index) {
+            Some(pipeline) => &pipeline.pipeline,
+            None => return,
+        };
+        let issues: Vec<_> = pipeline
+            .issues
+            .iter()
+            .filter(|issue_ref| issue_ref.foreign_repo(self.properties.repo.id).is_none())
+            .filter_map(|issue_ref| match self.issues.get(&issue_ref.number) {
+                Some(FutureValue::Ready(issue)) => Some(issue.clone()),
+                _ => None,
+            })
+            .collect();
+        let settings = &self.properties.settings.changelog;
+        let markdown = changelog::render(
+            &format!("{} ({})", pipeline.name, Local::now().format("%Y-%m-%d")),
+            &self.properties.repo.full_name,
+            &issues,
+            &settings.groups,
+        );
+        let num_issues = humanize::count(issues.len(), Noun::Issue);
+        self.status = match output {
+            ChangelogOutput::Release(tag_name) => {
+                let github_client = self.properties.github_client.clone();
+                let repo = Arc::new(self.properties.repo.full_name.clone());
+                self.spawn(
+                    github_client
+                        .create_release_draft(repo, tag_name.clone(), markdown)
+                        .map(Message::CreatedReleaseDraft),
+                );
+                format!("Drafting release {} of {}...", tag_name, num_issues)
+            }
+            ChangelogOutput::Clipboard => match clipboard::copy(&markdown) {
+                Ok(()) => format!("Copied a changelog of {}", num_issues),
+                Err(error) => {
+                    log::error!("{:?}", error);
+                    format!("Could not copy to the clipboard: {}", error)
+                }
+            },
+            ChangelogOutput::File => match fs::write(&settings.path, markdown) {
+                Ok(()) => format!(
+                    "Wrote a changelog of {} to `{}`",
+                    num_issues,
+                    settings.path.display()
+                ),
+                Err(error) => {
+                    log::error!("{:?}", error);
+                    format!("Could not write `{}`: {}", settings.path.display(), error)
+                }
+            },
+        };
+    }
+
+    /// Offers the repositories opened before, the most frecent first, to
+    /// switch the board to one of them.
+    fn pick_repo(&mut self) {
+        let current = &self.properties.repo.full_name;
+        let pinned = &self.properties.settings.pinned_repos;
+        let mut repos: Vec<_> = pinned.iter().map(String::as_str).collect();
+        repos.extend(
+            self.state
+                .recent_repos(Utc::now().timestamp())
+                .into_iter()
+                .filter(|full_name| !pinned.iter().any(|pinned| pinned == full_name)),
+        );
+        let repos: Vec<_> = repos
+            .into_iter()
+            .filter(|full_name| *full_name != current.0)
+            .map(|full_name| RepoFullName(full_name.to_owned()))
+            .collect();
+        if repos.is_empty() {
+            self.status = "No other repositories opened yet, run `zentui <owner/repo>`".into();
+            return;
+        }
+        let items = repos.iter().map(|full_name| full_name.0.clone()).collect();
+        self.picker = Some(Picker::new(
+            "Switch repository",
+            PickerAction::SwitchRepo(repos),
+            items,
+        ));
+    }
+
+    /// Starts fetching the boards of the pinned repos into their caches, so
+    /// switching to them shows their board at once. Skipped when few Github
+    /// requests are left, as the board in view needs them more.
+    fn preload_pinned_repos(&mut self) {
+        let max_concurrent = self.properties.settings.max_concurrent_preloads;
+        if max_concurrent == 0 {
+            return;
+        }
+        if let Some(rate_limit) = self.properties.github_client.rate_limit() {
+            if rate_limit.remaining < MIN_RATE_LIMIT_TO_PRELOAD {
+                log::info!(
+                    "Not preloading pinned repos, only {} Github requests left",
+                    rate_limit.remaining
+                );
+                return;
+            }
+        }
+        let current = &self.properties.repo.full_name;
+        let repos: Vec<_> = self
+            .properties
+            .settings
+            .pinned_repos
+            .iter()
+            .filter(|full_name| **full_name != current.0)
+            .map(|full_name| preload::PinnedRepo {
+                full_name: RepoFullName(full_name.clone()),
+                workspace: self
+                    .state
+                    .repo(full_name)
+                    .and_then(|repo| repo.workspace.clone())
+                    .map(WorkspaceId),
+            })
+            .collect();
+        if repos.is_empty() {
+            return;
+        }
+        self.spawn(
+            preload::preload_boards(
+                self.properties.github_client.clone(),
+                self.properties.zenhub_client.clone(),
+                repos,
+                max_concurrent,
+            )
+            .map(Message::PreloadedBoards),
+        );
+    }
+
+    /// Offers the workspaces which have the repo, to open the board of
+    /// another one.
+    fn pick_workspace(&mut self) {
+        let workspaces = match self.workspaces.as_ref() {
+            Some(workspaces) => workspaces.clone(),
+            None => {
+                self.status = "Loading workspaces...".into();
+                self.spawn(
+                    self.properties
+                        .zenhub_client
+                        .clone()
+                        .get_workspaces(self.properties.repo.id)
+                        .map(|result| Message::LoadedWorkspaces(result, WorkspaceIntent::Pick)),
+                );
+                return;
+            }
+        };
+        if workspaces.len() < 2 {
+            self.status = format!(
+                "{} is only in one workspace",
+                self.properties.repo.full_name.0
+            );
+            return;
+        }
+        let current = self.workspace.as_ref().map(|workspace| &workspace.id);
+        let items = workspaces
+            .iter()
+            .map(|workspace| {
+                format!(
+                    "{} {}",
+                    if Some(&workspace.id) == current {
+                        "✓"
+                    } else {
+                        " "
+                    },
+                    workspace.name
+                )
+            })
+            .collect();
+        self.picker = Some(Picker::new(
+            "Open the board of workspace",
+            PickerAction::SelectWorkspace(workspaces),
+            items,
+        ));
+    }
+
+    /// Opens the board of a workspace, which is remembered for the next time
+    /// the repo is opened.
+    fn open_workspace(&mut self, workspace: Workspace) {
+        self.remember_workspace(&workspace);
+        self.status = format!("Opening the board of {}", workspace.name);
+        self.workspace = Some(workspace);
+        self.load_board();
+    }
+
+    fn remember_workspace(&mut self, workspace: &Workspace) {
+        self.state
+            .repo_mut(&self.properties.repo.full_name.0)
+            .workspace = Some(workspace.id.0.clone());
+        self.save_state();
+    }
+
+    /// Asks which workspace's board to open when the repo is in several.
+    /// Zenhub would otherwise pick the oldest, which may not be the team's.
+    fn choose_workspace(&mut self, workspaces: Vector<Workspace>) {
+        if workspaces.len() < 2 {
+            self.load_board();
+            return;
+        }
+        let items = workspaces
+            .iter()
+            .map(|workspace| workspace.name.clone())
+            .collect();
+        self.picker = Some(Picker::new(
+            format!(
+                "{} is in {} workspaces, open the board of (Esc for the oldest)",
+                self.properties.repo.full_name.0,
+                workspaces.len()
+            ),
+            PickerAction::ChooseWorkspace(workspaces),
+            items,
+        ));
+    }
+
+    /// The request for the board of the current workspace, or of the oldest
+    /// one if none was picked.
+    fn board_request(&self) -> BoxFuture<'static, ClientResult<Board>> {
+        let zenhub_client = self.properties.zenhub_client.clone();
+        let repo_id = self.properties.repo.id;
+        match self.workspace.as_ref() {
+            Some(workspace) => zenhub_client
+                .get_board(workspace.id.clone(), repo_id)
+                .boxed(),
+            None => zenhub_client.get_oldest_board(repo_id).boxed(),
+        }
+    }
+
+    fn load_board(&mut self) {
+        self.spawn(self.board_request().map(Message::LoadedBoard));
+    }
+
+    /// The repository opened before the current one.
+    fn previous_repo(&self) -> Option<RepoFullName> {
+        let current = &self.properties.repo.full_name;
+        self.state
+            .last_repos()
+            .into_iter()
+            .find(|full_name| *full_name != current.0)
+            .map(|full_name| RepoFullName(full_name.to_owned()))
+    }
+
+    /// Exits the board, which is reopened on another repository.
+    fn switch_repo(&mut self, full_name: RepoFullName) {
+        *self
+            .properties
+            .next_repo
+            .lock()
+            .expect("next repo lock is not poisoned") = Some(full_name);
+        self.exit();
+    }
+
+    /// Applies the settings after the file changed, so that themes and keys
+    /// can be tweaked without restarting. Invalid settings are ignored.
+    fn reload_settings(&mut self) {
+        let path = match self.properties.settings_path.as_ref() {
+            Some(path) => path,
+            None => return,
+        };
+        match settings::try_read_settings(path) {
+            Ok(settings) => {
+                self.theme = Rc::new(Theme::new(
+                    &themes::from_settings(&settings, Some(path)),
+                    &settings.focus_emphasis,
+                    &settings.status_segments,
+                ));
+                self.properties.settings = settings;
+                self.clamp_selection();
+                self.status = "Reloaded the settings".into();
+                self.warn_shadowed_triage_keys();
+            }
+            Err(error) => {
+                log::error!("{:?}", error);
+                self.status = format!("{:#}", error);
+            }
+        }
+    }
+
+    /// Triage keys from the settings which a built in binding takes first,
+    /// so pressing them never triages. Only known on the board itself, as
+    /// overlays and prompts take every key.
+    fn shadowed_triage_keys(&self) -> Vec<char> {
+        self.properties
+            .settings
+            .triage
+            .iter()
+            .map(|action| action.key)
+            .filter(|&key| {
+                let binding = self.input_binding(&[Key::Char(key)]);
+                matches!(binding.transition, BindingTransition::Continue)
+                    || !matches!(binding.message, Some(Message::TriageSelectedIssue(_)))
+            })
+            .collect()
+    }
+
+    fn warn_shadowed_triage_keys(&mut self) {
+        if self.question.is_some()
+            || self.picker.is_some()
+            || self.overlay.is_some()
+            || self.show_jump_codes
+        {
+            return;
+        }
+        let shadowed = self.shadowed_triage_keys();
+        if !shadowed.is_empty() {
+            let keys: Vec<_> = shadowed.iter().map(char::to_string).collect();
+            log::warn!("Triage keys {} are taken by other bindings", keys.join(" "));
+            self.status = format!(
+                "Triage keys {} are taken by other bindings, pick others in the settings",
+                keys.join(" ")
+            );
+        }
+    }
+
+    /// Lists the base16 schemes in the themes directory to switch to one.
+    fn pick_scheme(&mut self) {
+        let themes_dir = match self
+            .properties
+            .settings_path
+            .as_deref()
+            .and_then(themes::themes_dir)
+        {
+            Some(themes_dir) => themes_dir,
+            None => return,
+        };
+        let names = themes::scheme_names(&themes_dir);
+        if names.is_empty() {
+            self.status = format!(
+                "Add base16 schemes (.yaml) to `{}` to switch between them",
+                themes_dir.display()
+            );
+            return;
+        }
+        self.picker = Some(Picker::new(
+            "Colour scheme",
+            PickerAction::Scheme(names.clone()),
+            names,
+        ));
+    }
+
+    /// Switches to a scheme until zentui exits; set `scheme` in the settings
+    /// to keep it.
+    fn select_scheme(&mut self, name: String) {
+        let themes_dir = self
+            .properties
+            .settings_path
+            .as_deref()
+            .and_then(themes::themes_dir);
+        match themes_dir.map(|themes_dir| themes::load_scheme(&themes_dir, &name)) {
+            Some(Ok(scheme)) => {
+                self.theme = Rc::new(Theme::new(
+                    &scheme.with_overrides(&self.properties.settings.theme),
+                    &self.properties.settings.focus_emphasis,
+                    &self.properties.settings.status_segments,
+                ));
+                self.properties.settings.scheme = Some(name.clone());
+                self.status = format!("Switched to the {} colour scheme", name);
+            }
+            Some(Err(error)) => {
+                log::error!("{:?}", error);
+                self.status = format!("{:#}", error);
+            }
+            None => {}
+        }
+    }
+
+    /// Turns focus-follow mode on or off. While on, the selected issue is
+    /// written to a file for a co-pilot tool to mirror, e.g. in a browser on
+    /// a second screen during a pairing session.
+    fn toggle_focus_follow(&mut self) {
+        if let Some(path) = self.focus_follow.take() {
+            self.followed = None;
+            if let Err(error) = follow::remove_selection(&path) {
+                log::error!("{:?}", error);
+            }
+            self.status = "Focus-follow is off".into();
+            return;
+        }
+        let path = match self.properties.settings.focus_follow_path.clone() {
+            Some(path) => path,
+            None => match follow::default_path() {
+                Ok(path) => path,
+                Err(error) => {
+                    self.status = format!("{}", error);
+                    return;
+                }
+            },
+        };
+        self.status = format!("Writing the selected issue to `{}`", path.display());
+        self.focus_follow = Some(path);
+        self.follow_selection(true);
+    }
+
+    /// Writes the selected issue to the focus-follow file, if it changed
+    /// since it was last written or when `force`d.
+    fn follow_selection(&mut self, force: bool) {
+        let path = match self.focus_follow {
+            Some(ref path) => path,
+            None => return,
+        };
+        let selection = self.selected_issue().and_then(|issue_ref| {
+            let repo = self.issue_repo(&issue_ref)?;
+            Some(Selection {
+                repo: repo.0.clone(),
+                number: issue_ref.number.0,
+                title: match self.issue(&issue_ref) {
+                    Some(FutureValue::Ready(issue)) => Some(issue.title.clone()),
+                    _ => None,
+                },
+                url: format!(
+                    "https://github.com/{}/issues/{}",
+                    repo.0, issue_ref.number.0
+                ),
+            })
+        });
+        if !force && selection == self.followed {
+            return;
+        }
+        if let Err(error) = follow::write_selection(path, selection.as_ref()) {
+            log::error!("{:?}", error);
+            self.status = format!("{}", error);
+        }
+        self.followed = selection;
+    }
+
+    /// The Github page of an issue, once the name of its repo is known.
+    fn issue_url(&self, issue_ref: &IssueRef) -> Option<String> {
+        self.issue_repo(issue_ref).map(|repo| {
+            format!(
+                "https://github.com/{}/issues/{}",
+                repo.0, issue_ref.number.0
+            )
+        })
+    }
+
+    /// Opens the selected issue on Github. Cards are drawn cell by cell, so
+    /// they can't carry OSC 8 hyperlinks to click on.
+    fn open_in_browser(&mut self) {
+        let url = match self
+            .selected_issue()
+            .and_then(|issue_ref| self.issue_url(&issue_ref))
+        {
+            Some(url) => url,
+            None => return,
+        };
+        if let Err(error) = terminal::open_url(&url) {
+            log::error!("{:?}", error);
+            self.status = format!("{}", error);
+        }
+    }
+
+    /// Downloads the images in the body of the selected issue to draw them in
+    /// the terminal. Terminals without graphics support only get the links,
+    /// listed in the detail pane.
+    fn preview_images(&mut self) {
+        let images = match self
+            .selected_issue()
+            .and_then(|issue_ref| self.issue(&issue_ref))
+        {
+            Some(FutureValue::Ready(issue)) => issue_detail::image_links(&issue.body),
+            _ => return,
+        };
+        if images.is_empty() {
+            self.status = "The issue has no images".into();
+            return;
+        }
+        let protocol = match self
+            .properties
+            .settings
+            .graphics_protocol
+            .or_else(GraphicsProtocol::detect)
+        {
+            Some(protocol) => protocol,
+            None => {
+                self.status = "The terminal can't show images, open the links in the detail pane \
+                               (i) instead"
+                    .into();
+                return;
+            }
+        };
+        let attachment_client = self.properties.attachment_client.clone();
+        self.status = format!("Downloading {} images...", images.len());
+        self.spawn(
+            future::join_all(images.into_iter().map(move |image| {
+                let caption = if image.alt.is_empty() {
+                    image.url.clone()
+                } else {
+                    image.alt.clone()
+                };
+                let url = image.url.clone();
+                attachment_client
+                    .clone()
+                    .get_bytes(image.url)
+                    .map(move |bytes| (caption, url, bytes))
+            }))
+            .map(move |images| Message::LoadedImages(protocol, images)),
+        );
+    }
+
+    /// Loads the milestones of the repo and their Zenhub start dates.
+    fn load_agenda(&mut self) {
+        let github_client = self.properties.github_client.clone();
+        let zenhub_client = self.properties.zenhub_client.clone();
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        let repo_id = self.properties.repo.id;
+        self.spawn(
+            async move {
+                let milestones = github_client.get_milestones(repo).await?;
+                future::join_all(milestones.into_iter().map(|milestone| {
+                    zenhub_client
+                        .clone()
+                        .get_milestone_start_date(repo_id, milestone.number)
+                        .map(move |start_date| {
+                            start_date.map(|start_date| AgendaMilestone {
+                                milestone,
+                                start_date,
+                            })
+                        })
+                }))
+                .await
+                .into_iter()
+                .collect::<ClientResult<Vec<_>>>()
+            }
+            .map(Message::LoadedAgenda),
+        );
+    }
+
+    /// Switches to one of the views zentui can start in.
+    fn open_view(&mut self, view: StartupView) {
+        match view {
+            StartupView::Board => {}
+            StartupView::MyWork => {
+                self.board.filter.assignee = Some(self.properties.user.login.clone());
+                self.status = "Showing issues assigned to you".into();
+            }
+            StartupView::Triage => match self.board.pipeline_of_kind(PipelineKind::NewIssues) {
+                Some(new_issues) => {
+                    self.board.pipelines[new_issues].hidden = false;
+                    self.board.selected_pipeline = new_issues;
+                    self.show_detail = true;
+                }
+                None => self.status = "The board has no New Issues pipeline".into(),
+            },
+            StartupView::Epics => {
+                self.board.filter.kind = Some(CardKind::Epics);
+                self.status = "Showing epics only".into();
+            }
+            StartupView::Notifications => {
+                self.notifications = FutureValue::Pending;
+                self.spawn(
+                    self.properties
+                        .github_client
+                        .clone()
+                        .get_notifications(Arc::new(self.properties.repo.full_name.clone()))
+                        .map(Message::LoadedNotifications),
+                );
+                self.overlay = Some(OverlayState {
+                    kind: OverlayKind::Notifications,
+                    selected: 0,
+                });
+            }
+        }
+    }
+
+    /// The local issues at the top of each pipeline, which are loaded with the
+    /// board.
+    fn top_issues(&self) -> Vec<IssueNumber> {
+        let board_repo = self.properties.repo.id;
+        self.board
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| pipeline.pipeline.issues.iter().take(NUM_PRELOADED_ISSUES))
+            .filter(|issue_ref| issue_ref.foreign_repo(board_repo).is_none())
+            .map(|issue_ref| issue_ref.number)
+            .collect()
+    }
+
+    /// Whether the board and the issues at its top have loaded, i.e. the
+    /// board is ready to be shown.
+    fn startup_loaded(&self) -> bool {
+        !self.board.pipelines.is_empty()
+            && self.top_issues().iter().all(|issue_number| {
+                !matches!(self.issues.get(issue_number), Some(FutureValue::Pending))
+            })
+    }
+
+    /// The progress of loading the board for the splash.
+    fn splash_properties(&self) -> splash::Properties {
+        let repo = &self.properties.repo;
+        let mut steps = vec![
+            splash::Step {
+                progress: splash::Progress::Done,
+                label: "Github".into(),
+                detail: format!("signed in as @{}", self.properties.user.login),
+            },
+            splash::Step {
+                progress: splash::Progress::Done,
+                label: "Repo".into(),
+                detail: match self.read_only_reason() {
+                    Some(reason) => format!("{} ({})", repo.full_name.0, reason),
+                    None => repo.full_name.0.clone(),
+                },
+            },
+        ];
+        let workspace = self.workspace.as_ref().map_or_else(
+            || "oldest workspace".into(),
+            |workspace| workspace.name.clone(),
+        );
+        steps.push(match (self.board.pipelines.is_empty(), &self.board_error) {
+            (false, _) => splash::Step {
+                progress: splash::Progress::Done,
+                label: "Board".into(),
+                detail: workspace,
+            },
+            (true, Some(error)) => splash::Step {
+                progress: splash::Progress::Failed,
+                label: "Board".into(),
+                detail: error.clone(),
+            },
+            (true, None) => splash::Step {
+                progress: splash::Progress::Pending,
+                label: "Board".into(),
+                detail: match self.picker {
+                    Some(Picker {
+                        action: PickerAction::ChooseWorkspace(_),
+                        ..
+                    }) => "choose a workspace below".into(),
+                    _ if self.workspace.is_none() && self.workspaces.is_none() => {
+                        "finding workspaces".into()
+                    }
+                    _ => workspace,
+                },
+            },
+        });
+        if !self.board.pipelines.is_empty() {
+            let top_issues = self.top_issues();
+            let num_loaded = top_issues
+                .iter()
+                .filter(|issue_number| {
+                    !matches!(self.issues.get(issue_number), Some(FutureValue::Pending))
+                })
+                .count();
+            steps.push(splash::Step {
+                progress: if num_loaded < top_issues.len() {
+                    splash::Progress::Pending
+                } else {
+                    splash::Progress::Done
+                },
+                label: "Issues".into(),
+                detail: format!("{}/{}", num_loaded, top_issues.len()),
+            });
+        }
+
+        let mut warnings = Vec::new();
+        if let Some(rate_limit) = self.properties.github_client.rate_limit() {
+            if rate_limit.remaining < rate_limit.limit / 10 {
+                warnings.push(match rate_limit.reset {
+                    Some(reset) => format!(
+                        "Only {} Github requests left until {}",
+                        rate_limit.remaining,
+                        reset.with_timezone(&Local).format("%H:%M")
+                    ),
+                    None => format!("Only {} Github requests left", rate_limit.remaining),
+                });
+            }
+        }
+        splash::Properties {
+            theme: self.theme.overlay.clone(),
+            title: format!("Loading {}", repo.full_name.0),
+            steps,
+            warnings,
+        }
+    }
+
+    /// Starts loading the local issues at the top of each pipeline. Issues
+    /// which were loaded before are reloaded in place.
+    fn load_top_issues(&mut self) {
+        self.load_issues(self.top_issues());
+    }
+
+    /// Starts loading issues of the board's repo. Cards keep showing an issue
+    /// which was loaded before while it's reloaded.
+    fn load_issues(&mut self, issue_numbers: Vec<IssueNumber>) {
+        for issue_number in issue_numbers.iter() {
+            if !self.issues.contains_key(issue_number) {
+                self.issues.insert(*issue_number, FutureValue::Pending);
+            }
+            self.stale_issues.remove(issue_number);
+        }
+        self.load_connected_pull_requests(&issue_numbers);
+        if !self.batch_issue_loads || issue_numbers.len() < 2 {
+            self.load_issues_one_by_one(issue_numbers);
+            return;
+        }
+
+        // A query per batch rather than a request per issue, sent at once so
+        // a large board loads in a single round trip
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        for batch in issue_numbers.chunks(ISSUES_PER_QUERY) {
+            let batch = batch.to_vec();
+            self.spawn(
+                self.properties
+                    .github_client
+                    .clone()
+                    .get_issues(repo.clone(), batch.clone())
+                    .map(move |issues| Message::LoadedIssues(batch, issues)),
+            );
+        }
+    }
+
+    /// Starts loading the pull requests connected to issues on Zenhub, which
+    /// are shown on their cards.
+    fn load_connected_pull_requests(&mut self, issue_numbers: &[IssueNumber]) {
+        if !self.properties.zenhub_client.loads_connections() {
+            if !mem::replace(&mut self.noted_missing_connections, true) {
+                self.status = "Only pull requests closing issues with a keyword are shown as \
+                               connected, Zenhub's connections need a GraphQL API key"
+                    .into();
+            }
+            return;
+        }
+        let repo_id = self.properties.repo.id;
+        for batch in issue_numbers.chunks(ISSUES_PER_QUERY) {
+            self.spawn(
+                self.properties
+                    .zenhub_client
+                    .clone()
+                    .get_connected_pull_requests(repo_id, batch.to_vec())
+                    .map(Message::LoadedConnectedPullRequests),
+            );
+        }
+    }
+
+    /// Stores a loaded issue, loading what else it needs, e.g. its reviewers
+    /// if it's a pull request, and syncing its pipeline with its labels.
+    fn insert_loaded_issue(&mut self, issue_number: IssueNumber, result: ClientResult<Issue>) {
+        let issue = match result {
+            Ok(issue) => {
+                if issue.pull_request.is_some() {
+                    let repo = Arc::new(self.properties.repo.full_name.clone());
+                    self.spawn(
+                        self.properties
+                            .github_client
+                            .clone()
+                            .get_pull_request(repo.clone(), issue_number)
+                            .map(move |pull_request| {
+                                Message::LoadedPullRequest(issue_number, pull_request)
+                            }),
+                    );
+                    self.spawn(
+                        self.properties
+                            .github_client
+                            .clone()
+                            .get_pull_request_status(repo, issue_number)
+                            .map(move |status| {
+                                Message::LoadedPullRequestStatus(issue_number, status)
+                            }),
+                    );
+                }
+                FutureValue::Ready(issue)
+            }
+            Err(error) => {
+                log::error!("{:?}", error);
+                FutureValue::Error(format!("{:?}", error))
+            }
+        };
+        self.insert_issue(issue_number, issue);
+        if !self.sync_pipeline(issue_number) {
+            self.sync_labels(issue_number);
+        }
+    }
+
+    /// Loads issues with a REST request each, which tells apart issues that
+    /// don't exist from those that can't be seen.
+    fn load_issues_one_by_one(&mut self, issue_numbers: Vec<IssueNumber>) {
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        for issue_number in issue_numbers {
+            self.spawn(
+                self.properties
+                    .github_client
+                    .clone()
+                    .get_issue(repo.clone(), issue_number)
+                    .map(move |issue| Message::LoadedIssue(issue_number, issue)),
+            );
+        }
+    }
+
+    /// Loads the issues on and around the cards in view of each pipeline, as
+    /// only the top of each pipeline is loaded with the board. Runs after
+    /// every update, so scrolling down a long pipeline loads the next page of
+    /// cards before it's shown.
+    fn load_nearby_issues(&mut self) {
+        let board_repo = self.properties.repo.id;
+        let page_size = self.page_size();
+        let mut local = Vec::new();
+        let mut foreign = Vec::new();
+        for pipeline in self
+            .board
+            .pipelines
+            .iter()
+            .filter(|pipeline| !pipeline.hidden)
+        {
+            let start = pipeline.selected_issue.saturating_sub(page_size);
+            let end = pipeline.selected_issue + 2 * page_size;
+            for issue_ref in self
+                .visible_issues(pipeline)
+                .iter()
+                .skip(start)
+                .take(end - start)
+            {
+                match issue_ref.foreign_repo(board_repo) {
+                    Some(repo_id) => {
+                        if !self
+                            .foreign_issues
+                            .contains_key(&(repo_id, issue_ref.number))
+                        {
+                            foreign.push((repo_id, issue_ref.number));
+                        }
+                    }
+                    None => {
+                        if !self.issues.contains_key(&issue_ref.number)
+                            || self.stale_issues.contains(&issue_ref.number)
+                        {
+                            local.push(issue_ref.number);
+                        }
+                    }
+                }
+            }
+        }
+        if !local.is_empty() {
+            self.load_issues(local);
+        }
+        if !foreign.is_empty() {
+            self.load_foreign(foreign);
+        }
+    }
+
+    /// Swaps in a reloaded board, keeping the selected card and reloading
+    /// the issues at the top of each pipeline, as well as the selected one.
+    fn refresh_board(&mut self, trigger: RefreshTrigger, board: Board) {
+        let selected = self.selected_issue().map(|issue_ref| issue_ref.number);
+        let previous: HashSet<IssueNumber> = self
+            .board
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| pipeline.pipeline.issues.iter())
+            .map(|issue_ref| issue_ref.number)
+            .collect();
+        let keep_for = 2 * self.board_poll_interval();
+        self.local_moves
+            .retain(|_, moved_at| moved_at.elapsed() < keep_for);
+        self.local_estimates
+            .retain(|_, estimated_at| estimated_at.elapsed() < keep_for);
+        let moved = self.local_moves.keys().copied().collect();
+        let estimated = self.local_estimates.keys().copied().collect();
+        let before = self.board.clone();
+        self.board
+            .refresh(self.properties.repo.id, board, &moved, &estimated);
+        self.cache_dirty = true;
+        self.record_board_changes(&before);
+        let num_new = self
+            .board
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| pipeline.pipeline.issues.iter())
+            .filter(|issue_ref| !previous.contains(&issue_ref.number))
+            .count();
+
+        self.load_top_issues();
+        self.load_foreign_issues();
+        if let Some(issue_number) = selected {
+            let on_top = self.board.pipelines.iter().any(|pipeline| {
+                pipeline
+                    .pipeline
+                    .issues
+                    .iter()
+                    .take(NUM_PRELOADED_ISSUES)
+                    .any(|issue_ref| issue_ref.refers_to(self.properties.repo.id, issue_number))
+            });
+            if !on_top && self.issues.contains_key(&issue_number) {
+                let repo = Arc::new(self.properties.repo.full_name.clone());
+                self.spawn(
+                    self.properties
+                        .github_client
+                        .clone()
+                        .get_issue(repo, issue_number)
+                        .map(move |issue| Message::LoadedIssue(issue_number, issue)),
+                );
+            }
+            self.focus_issue(issue_number);
+        }
+        self.clamp_selection();
+
+        if num_new > 0 {
+            self.status = format!(
+                "{} on the board",
+                humanize::count_with(num_new, "new", Noun::Issue)
+            );
+        } else if trigger == RefreshTrigger::Key {
+            self.status = "The board is up to date".into();
+        }
+    }
+
+    /// Starts loading the issues from other repos at the top of each
+    /// pipeline, looking up the names of their repos first.
+    fn load_foreign_issues(&mut self) {
+        let board_repo = self.properties.repo.id;
+        let unloaded: Vec<_> = self
+            .board
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| pipeline.pipeline.issues.iter().take(NUM_PRELOADED_ISSUES))
+            .filter_map(|issue_ref| {
+                issue_ref
+                    .foreign_repo(board_repo)
+                    .map(|repo_id| (repo_id, issue_ref.number))
+            })
+            .filter(|key| !self.foreign_issues.contains_key(key))
+            .collect();
+        self.load_foreign(unloaded);
+    }
+
+    /// Starts loading issues from other repos, or the names of their repos
+    /// if they aren't known yet.
+    fn load_foreign(&mut self, unloaded: Vec<(RepoId, IssueNumber)>) {
+        for (repo_id, issue_number) in unloaded {
+            match self.foreign_repos.get(&repo_id) {
+                Some(FutureValue::Ready(full_name)) => {
+                    let repo = Arc::new(full_name.clone());
+                    self.foreign_issues
+                        .insert((repo_id, issue_number), FutureValue::Pending);
+                    self.spawn(
+                        self.properties
+                            .github_client
+                            .clone()
+                            .get_issue(repo, issue_number)
+                            .map(move |issue| {
+                                Message::LoadedForeignIssue(repo_id, issue_number, issue)
+                            }),
+                    );
+                }
+                Some(_) => {}
+                None => {
+                    self.foreign_repos.insert(repo_id, FutureValue::Pending);
+                    self.spawn(
+                        self.properties
+                            .github_client
+                            .clone()
+                            .get_repo_by_id(repo_id)
+                            .map(move |repo| Message::LoadedForeignRepo(repo_id, repo)),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Starts loading the issues in the pipelines swept for stale issues, as
+    /// only the top of each pipeline is loaded with the board.
+    fn load_swept_issues(&mut self) {
+        let unloaded: Vec<_> = sweep::swept_pipelines(&self.board, &self.properties.settings.stale)
+            .into_iter()
+            .flat_map(|pipeline_index| self.board.pipelines[pipeline_index].pipeline.issues.iter())
+            .map(|issue_ref| issue_ref.number)
+            .filter(|issue_number| !self.issues.contains_key(issue_number))
+            .collect();
+        self.load_issues(unloaded);
+    }
+
+    /// Loads every issue on the board, for tools working across all of it.
+    fn load_all_issues(&mut self) {
+        let unloaded: Vec<_> = self
+            .board
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| pipeline.pipeline.issues.iter())
+            .filter(|issue_ref| issue_ref.foreign_repo(self.properties.repo.id).is_none())
+            .map(|issue_ref| issue_ref.number)
+            .filter(|issue_number| !self.issues.contains_key(issue_number))
+            .collect();
+        self.load_issues(unloaded);
+    }
+
+    /// The titles the find and replace would change, skipped ones included.
+    fn renames(&self) -> Vec<Rename> {
+        match self.title_replace {
+            Some(ref replace) => rename::renames(&self.board, &self.issues, replace),
+            None => Vec::new(),
+        }
+    }
+
+    fn confirm_renames(&mut self) {
+        let num_issues = self
+            .renames()
+            .iter()
+            .filter(|rename| !self.rename_skipped.contains(&rename.issue_number))
+            .count();
+        if num_issues == 0 {
+            self.status = "No titles to rename".into();
+            return;
+        }
+        self.question = Some(Question::confirm(
+            format!("Rename {}?", humanize::count(num_issues, Noun::Issue)),
+            QuestionAction::Rename,
+        ));
+    }
+
+    /// Renames the issues which weren't skipped, a batch at a time.
+    fn apply_renames(&mut self) {
+        let renames: VecDeque<_> = self
+            .renames()
+            .into_iter()
+            .filter(|rename| !self.rename_skipped.contains(&rename.issue_number))
+            .collect();
+        self.pending_renames.extend(renames);
+        self.overlay = None;
+        self.rename_next_batch();
+    }
+
+    fn rename_next_batch(&mut self) {
+        let batch_size = cmp::min(RENAME_BATCH_SIZE, self.pending_renames.len());
+        if batch_size == 0 {
+            return;
+        }
+        let batch: Vec<_> = self.pending_renames.drain(..batch_size).collect();
+        let github_client = self.properties.github_client.clone();
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        self.spawn(
+            future::join_all(batch.into_iter().map(move |rename| {
+                let issue_number = rename.issue_number;
+                github_client
+                    .clone()
+                    .rename_issue(repo.clone(), issue_number, rename.new_title)
+                    .map(move |result| (issue_number, result))
+            }))
+            .map(Message::RenamedBatch),
+        );
+    }
+
+    /// Runs a bulk action on the stale issues which weren't skipped.
+    fn sweep(&mut self, action: SweepAction) {
+        let stale = self.properties.settings.stale.clone();
+        let issue_numbers: Vec<_> =
+            sweep::stale_issues(&self.board, &self.issues, &stale, Utc::now())
+                .into_iter()
+                .map(|stale_issue| stale_issue.issue.number)
+                .filter(|issue_number| !self.sweep_skipped.contains(issue_number))
+                .collect();
+        if issue_numbers.is_empty() {
+            self.status = "No stale issues to sweep".into();
+            return;
+        }
+
+        if action == SweepAction::Icebox {
+            match self.board.pipeline_of_kind(PipelineKind::Icebox) {
+                Some(icebox) => {
+                    for issue_number in issue_numbers {
+                        self.move_issue(issue_number, icebox, Position::Top);
+                    }
+                }
+                None => self.status = "The board has no Icebox pipeline".into(),
+            }
+            return;
+        }
+
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        for issue_number in issue_numbers {
+            let github_client = self.properties.github_client.clone();
+            let repo = repo.clone();
+            let stale = stale.clone();
+            self.spawn(
+                async move {
+                    match action {
+                        SweepAction::Nudge => {
+                            github_client
+                                .clone()
+                                .add_comment(repo.clone(), issue_number, stale.comment)
+                                .await?;
+                        }
+                        SweepAction::Label => {
+                            github_client
+                                .clone()
+                                .add_labels(repo.clone(), issue_number, vec![stale.label])
+                                .await?;
+                        }
+                        SweepAction::Close => {
+                            return github_client.close_issue(repo, issue_number).await;
+                        }
+                        SweepAction::Icebox => {}
+                    }
+                    github_client.get_issue(repo, issue_number).await
+                }
+                .map(move |result| Message::SweptIssue(action, issue_number, result)),
+            );
+        }
+    }
+
+    /// The pipeline an issue is in and its card.
+    fn issue_location(&self, issue_number: IssueNumber) -> Option<(PipelineIndex, &IssueRef)> {
+        self.board
+            .pipelines
+            .iter()
+            .enumerate()
+            .find_map(|(pipeline_index, pipeline)| {
+                pipeline
+                    .pipeline
+                    .issues
+                    .iter()
+                    .find(|issue_ref| issue_ref.refers_to(self.properties.repo.id, issue_number))
+                    .map(|issue_ref| (pipeline_index, issue_ref))
+            })
+    }
+
+    /// Updates the estimate and pipeline labels of an issue to match the
+    /// board, as configured in the settings.
+    fn sync_labels(&mut self, issue_number: IssueNumber) {
+        if self.read_only_reason().is_some() {
+            return;
+        }
+        let issue = match self.issues.get(&issue_number) {
+            Some(FutureValue::Ready(issue)) => issue,
+            _ => return,
+        };
+        let location = self.issue_location(issue_number);
+        let changes = label_sync::label_changes(
+            &self.properties.settings,
+            issue,
+            location.map(|(pipeline_index, _)| &self.board.pipelines[pipeline_index].pipeline),
+            location.and_then(|(_, issue_ref)| issue_ref.estimate),
+        );
+        if changes.is_empty() {
+            return;
+        }
+
+        let github_client = self.properties.github_client.clone();
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        self.spawn(
+            async move {
+                let mut labels = None;
+                for label in changes.remove {
+                    labels = Some(
+                        github_client
+                            .clone()
+                            .remove_label(repo.clone(), issue_number, label)
+                            .await?,
+                    );
+                }
+                if !changes.add.is_empty() {
+                    labels = Some(
+                        github_client
+                            .add_labels(repo, issue_number, changes.add)
+                            .await?,
+                    );
+                }
+                Ok::<_, zentui_client::Error>(labels.unwrap_or_default())
+            }
+            .map(move |result| Message::SyncedLabels(issue_number, result)),
+        );
+    }
+
+    /// Moves an issue to the pipeline matching a label it was given outside
+    /// zentui. Returns whether the issue was moved.
+    fn sync_pipeline(&mut self, issue_number: IssueNumber) -> bool {
+        let issue = match self.issues.get(&issue_number) {
+            Some(FutureValue::Ready(issue)) => issue,
+            _ => return false,
+        };
+        let pipeline_index = match self.issue_location(issue_number) {
+            Some((pipeline_index, _)) => pipeline_index,
+            None => return false,
+        };
+        let to_pipeline = label_sync::labelled_pipeline(
+            &self.properties.settings.pipeline_labels,
+            issue,
+            &self.board.pipelines[pipeline_index].pipeline,
+        )
+        .and_then(|rule| {
+            self.board
+                .pipelines
+                .iter()
+                .position(|pipeline| rule.matches(&pipeline.pipeline.name))
+        });
+        match to_pipeline {
+            Some(to_pipeline) => {
+                self.move_issue(issue_number, to_pipeline, Position::Top);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn load_teams(&mut self, action: TeamAction) {
+        let org = self.properties.repo.full_name.owner().to_owned();
+        self.status = format!("Loading teams for {}...", org);
+        self.spawn(
+            self.properties
+                .github_client
+                .clone()
+                .get_org_teams(org)
+                .map(move |teams| Message::LoadedTeams(action, teams)),
+        );
+    }
+
+    fn pick_team(&mut self, action: TeamAction, team: Team) {
+        match action {
+            TeamAction::RequestReview(issue_number) => {
+                self.status = format!("Requesting review from {}...", team.name);
+                let repo = Arc::new(self.properties.repo.full_name.clone());
+                self.spawn(
+                    self.properties
+                        .github_client
+                        .clone()
+                        .request_team_review(repo, issue_number, team.clone())
+                        .map(move |result| {
+                            Message::RequestedTeamReview(issue_number, team, result)
+                        }),
+                );
+            }
+            TeamAction::Filter => self.load_team_members(team),
+        }
+    }
+
+    /// Starts loading the members of a team, to filter the board by it.
+    fn load_team_members(&mut self, team: Team) {
+        let org = self.properties.repo.full_name.owner().to_owned();
+        self.status = format!("Loading members of {}...", team.name);
+        self.spawn(
+            self.properties
+                .github_client
+                .clone()
+                .get_team_members(org, team.clone())
+                .map(move |members| Message::LoadedTeamMembers(team, members)),
+        );
+    }
+
+    fn move_issue(
+        &mut self,
+        issue_number: IssueNumber,
+        to_pipeline: PipelineIndex,
+        position: Position,
+    ) {
+        let index = match position {
+            Position::Top => 0,
+            Position::Bottom => usize::MAX,
+            Position::Index(index) => index,
+        };
+        let origin =
+            match self
+                .board
+                .move_issue(self.properties.repo.id, issue_number, to_pipeline, index)
+            {
+                Some(origin) => origin,
+                None => return,
+            };
+        self.cache_dirty = true;
+        self.local_moves.insert(issue_number, Instant::now());
+        let pipeline = &self.board.pipelines[to_pipeline].pipeline;
+        let pipeline_id = pipeline.id.clone();
+        self.status = format!("Moving #{} to {}...", issue_number.0, pipeline.name);
+        self.spawn(
+            self.properties
+                .zenhub_client
+                .clone()
+                .move_issue(self.properties.repo.id, issue_number, pipeline_id, position)
+                .map(move |result| Message::MovedIssue(issue_number, origin, result)),
+        );
+    }
+
+    /// Assigns the current user to an issue they moved into a pipeline set up
+    /// with `auto_assign`, or asks first. Moves made to follow a label, or by
+    /// teammates, don't assign anyone.
+    fn auto_assign(&mut self, issue_number: IssueNumber, to_pipeline: PipelineIndex) {
+        if self.read_only_reason().is_some() {
+            return;
+        }
+        let pipeline_name = &self.board.pipelines[to_pipeline].pipeline.name;
+        let rule = match self
+            .properties
+            .settings
+            .auto_assign
+            .iter()
+            .find(|rule| rule.matches(pipeline_name))
+        {
+            Some(rule) => rule,
+            None => return,
+        };
+        let login = &self.properties.user.login;
+        match self.issues.get(&issue_number) {
+            Some(FutureValue::Ready(issue))
+                if !issue
+                    .assignees
+                    .iter()
+                    .any(|assignee| assignee.login == *login) => {}
+            _ => return,
+        }
+        if rule.ask {
+            self.question = Some(Question::confirm(
+                format!("Assign #{} to yourself?", issue_number.0),
+                QuestionAction::AssignToMe(issue_number),
+            ));
+        } else {
+            self.assign_to_me(issue_number);
+        }
+    }
+
+    fn assign_to_me(&mut self, issue_number: IssueNumber) {
+        let repo = Arc::new(self.properties.repo.full_name.clone());
+        let login = self.properties.user.login.clone();
+        self.spawn(
+            self.properties
+                .github_client
+                .clone()
+                .add_assignees(repo, issue_number, vec![login])
+                .map(move |result| Message::AssignedIssue(issue_number, result)),
+        );
+    }
+
+    /// Moves the cards which were moved on Zenhub since the board was last
+    /// loaded, e.g. by teammates. Issues recently moved from zentui are left
+    /// alone, as the polled board may predate their move.
+    fn apply_remote_moves(&mut self, polled: Board) {
+        let keep_for = 2 * self.board_poll_interval();
+        self.local_moves
+            .retain(|_, moved_at| moved_at.elapsed() < keep_for);
+        let repo_id = self.properties.repo.id;
+        let before = self.board.clone();
+        for pipeline in polled.pipelines {
+            let to_pipeline = match self
+                .board
+                .pipelines
+                .iter()
+                .position(|view| view.pipeline.id == pipeline.id)
+            {
+                Some(to_pipeline) => to_pipeline,
+                None => continue,
+            };
+            let pipeline = PipelineView::from(pipeline).pipeline;
+            for (index, issue_ref) in pipeline.issues.iter().enumerate() {
+                let issue_number = issue_ref.number;
+                // Remote moves are tracked by number, foreign issues are
+                // picked up by the next full refresh instead
+                if issue_ref.foreign_repo(repo_id).is_some()
+                    || self.local_moves.contains_key(&issue_number)
+                {
+                    continue;
+                }
+                let from_pipeline = self.board.pipelines.iter().position(|view| {
+                    view.pipeline
+                        .issues
+                        .iter()
+                        .any(|issue_ref| issue_ref.refers_to(repo_id, issue_number))
+                });
+                match from_pipeline {
+                    Some(from_pipeline) if from_pipeline != to_pipeline => {
+                        self.board
+                            .move_issue(repo_id, issue_number, to_pipeline, index);
+                        self.cache_dirty = true;
+                        self.remote_moves.push(RemoteMove {
+                            issue_number,
+                            from_pipeline,
+                            to_pipeline,
+                            at: Instant::now(),
+                        });
+                        self.status = format!(
+                            "#{} was moved to {}",
+                            issue_number.0, self.board.pipelines[to_pipeline].pipeline.name
+                        );
+                        self.spawn(
+                            self.properties
+                                .zenhub_client
+                                .clone()
+                                .get_issue_events(repo_id, issue_number)
+                                .map(move |events| {
+                                    Message::LoadedIssueEvents(issue_number, events)
+                                }),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.record_board_changes(&before);
+        self.clamp_selection();
+    }
+
+    /// Adds what changed from the board `before` a reload to the list of
+    /// changes, forgetting the oldest ones past `MAX_BOARD_CHANGES`.
+    fn record_board_changes(&mut self, before: &BoardView) {
+        self.board_changes
+            .extend(board_diff::changes(before, &self.board, Local::now()));
+        let num_forgotten = self.board_changes.len().saturating_sub(MAX_BOARD_CHANGES);
+        self.board_changes.drain(..num_forgotten);
+    }
+
+    /// Moves the selected card, sending its new position to Zenhub such that
+    /// the manual ordering of the pipeline is preserved. When moving to
+    /// another pipeline, the card is placed above the selected card there.
+    fn move_selected_issue(&mut self, card_move: CardMove) {
+        let issue_number = match self.selected_local_issue() {
+            Some(issue_ref) => issue_ref.number,
+            None => return,
+        };
+        let board_repo = self.properties.repo.id;
+        let from_pipeline = self.board.selected_pipeline;
+        // The target card may be from another repo, so it's identified by
+        // its repo as well as its number
+        let (to_pipeline, target) = match card_move {
+            CardMove::Within(direction) => {
+                let pipeline = &self.board.pipelines[from_pipeline];
+                if let Some(sort) = pipeline.sort {
+                    self.status = format!(
+                        "{} is sorted by {}, s s to reorder it again",
+                        pipeline.pipeline.name,
+                        sort.name()
+                    );
+                    return;
+                }
+                if pipeline.pipeline.kind() == PipelineKind::Icebox {
+                    self.status = "The Icebox is sorted by reactions and can't be reordered".into();
+                    return;
+                }
+                let visible = self.visible_issues(pipeline);
+                let index = match visible
+                    .iter()
+                    .position(|issue_ref| issue_ref.refers_to(board_repo, issue_number))
+                {
+                    Some(index) => index,
+                    None => return,
+                };
+                let neighbour = match direction {
+                    Direction::Previous => index.checked_sub(1),
+                    Direction::Next => Some(index + 1),
+                };
+                match neighbour.and_then(|neighbour| visible.get(neighbour)) {
+                    Some(neighbour) => (from_pipeline, Some((neighbour.number, neighbour.repo_id))),
+                    None => return,
+                }
+            }
+            CardMove::Across(direction) => {
+                let to_pipeline = match self.board.adjacent_pipeline(from_pipeline, direction) {
+                    Some(to_pipeline) => to_pipeline,
+                    None => return,
+                };
+                let pipeline = &self.board.pipelines[to_pipeline];
+                let target = self
+                    .visible_issues(pipeline)
+                    .get(pipeline.selected_issue)
+                    .map(|issue_ref| (issue_ref.number, issue_ref.repo_id));
+                (to_pipeline, target)
+            }
+        };
+
+        // Zenhub positions count all the issues in a pipeline, including the
+        // ones hidden by filters. Taking the place of the target card puts the
+        // moved card above it, or below it when moving down the same pipeline.
+        let position = target
+            .and_then(|target| {
+                self.board.pipelines[to_pipeline]
+                    .pipeline
+                    .issues
+                    .iter()
+                    .position(|issue_ref| (issue_ref.number, issue_ref.repo_id) == target)
+            })
+            .map_or(Position::Bottom, Position::Index);
+        if to_pipeline != from_pipeline
+            && self.confirm_wip_limit(issue_number, to_pipeline, position)
+        {
+            return;
+        }
+        self.move_issue(issue_number, to_pipeline, position);
+        if to_pipeline != from_pipeline {
+            self.auto_assign(issue_number, to_pipeline);
+        }
+        self.focus_issue(issue_number);
+    }
+
+    fn wip_limit(&self, pipeline_name: &str) -> Option<&WipLimit> {
+        self.properties
+            .settings
+            .wip_limits
+            .iter()
+            .find(|wip_limit| wip_limit.matches(pipeline_name))
+    }
+
+    /// Asks before moving an issue into a pipeline which is already at its
+    /// WIP limit, when the limit is set to `confirm`. Returns whether the
+    /// move waits for the answer.
+    fn confirm_wip_limit(
+        &mut self,
+        issue_number: IssueNumber,
+        to_pipeline: PipelineIndex,
+        position: Position,
+    ) -> bool {
+        let pipeline = &self.board.pipelines[to_pipeline].pipeline;
+        let limit = match self.wip_limit(&pipeline.name) {
+            Some(wip_limit) if wip_limit.confirm && pipeline.issues.len() >= wip_limit.limit => {
+                wip_limit.limit
+            }
+            _ => return false,
+        };
+        self.question = Some(Question::confirm(
+            format!(
+                "{} is at its WIP limit of {}, move #{} anyway?",
+                pipeline.name, limit, issue_number.0
+            ),
+            QuestionAction::MoveOverWipLimit(issue_number, to_pipeline, position),
+        ));
+        true
+    }
+
+    /// Asks what to filter the board by, starting from the current filter.
+    fn ask_filter(&mut self, field: FilterField) {
+        let filter = &self.board.filter;
+        self.question = Some(match field {
+            FilterField::Label => Question::text(
+                "Only show issues labelled (empty for any)",
+                filter.label.clone().unwrap_or_default(),
+                QuestionAction::Filter(field),
+            ),
+            FilterField::Assignee => Question::text(
+                "Only show issues assigned to (@me for you, empty for anyone)",
+                filter
+                    .assignee
+                    .as_ref()
+                    .map_or_else(String::new, |login| format!("@{}", login)),
+                QuestionAction::Filter(field),
+            ),
+            FilterField::State => Question::select(
+                "Only show issues which are",
+                FILTER_STATES
+                    .iter()
+                    .map(|state| state.map_or("open or closed", state_name).to_owned())
+                    .collect(),
+                QuestionAction::Filter(field),
+            ),
+        });
+    }
+
+    /// The status, followed by the active filters so it's clear why cards
+    /// are missing.
+    /// The segments of the status row, in the order of the settings. Empty
+    /// ones, like the filters of an unfiltered board, are left out.
+    fn prompt_segments(&self) -> Vec<prompt::Segment> {
+        let filter_segment = self
+            .theme
+            .status_segments
+            .iter()
+            .any(|&(kind, _)| kind == StatusSegmentKind::Filter);
+        self.theme
+            .status_segments
+            .iter()
+            .filter_map(|&(kind, style)| match kind {
+                StatusSegmentKind::Sync => Some(prompt::Segment::Sync(style)),
+                StatusSegmentKind::Message => Some(prompt::Segment::Message(
+                    self.prompt_message(!filter_segment),
+                    style,
+                )),
+                StatusSegmentKind::Mode => {
+                    Some(prompt::Segment::Text(self.mode_name().into(), style))
+                }
+                StatusSegmentKind::Repo => Some(prompt::Segment::Text(
+                    self.properties.repo.full_name.0.clone(),
+                    style,
+                )),
+                StatusSegmentKind::Filter => {
+                    let filters = self.board.filter.describe();
+                    if filters.is_empty() {
+                        None
+                    } else {
+                        Some(prompt::Segment::Text(filters.join(" "), style))
+                    }
+                }
+                StatusSegmentKind::RateLimit => {
+                    self.properties
+                        .github_client
+                        .rate_limit()
+                        .map(|rate_limit| {
+                            prompt::Segment::Text(
+                                format!("{}/{} requests", rate_limit.remaining, rate_limit.limit),
+                                style,
+                            )
+                        })
+                }
+            })
+            .collect()
+    }
+
+    /// What the keys act on, for the mode segment of the status row.
+    fn mode_name(&self) -> &'static str {
+        if self.picker.is_some() {
+            "PICK"
+        } else if self.overlay.is_some() {
+            "VIEW"
+        } else if self.search.is_some() {
+            "SEARCH"
+        } else if !self.marked_issues.is_empty() {
+            "MARKED"
+        } else {
+            "BOARD"
+        }
+    }
+
+    /// The status message, followed by the filters unless they have their
+    /// own segment.
+    fn prompt_message(&self, with_filters: bool) -> String {
+        let filters = if with_filters {
+            self.board.filter.describe()
+        } else {
+            Vec::new()
+        };
+        let message = if filters.is_empty() {
+            self.status.clone()
+        } else if self.status.is_empty() {
+            format!("[{}] f c to clear", filters.join(" "))
+        } else {
+            format!("{}  [{}]", self.status, filters.join(" "))
+        };
+        // Write actions are refused while the repo can't be changed
+        match self.read_only_reason() {
+            Some(reason) if message.is_empty() => format!("({})", reason),
+            Some(reason) => format!("({}) {}", reason, message),
+            None => message,
+        }
+    }
+
+    /// The cards matching the search, in board order.
+    fn search_matches(&self) -> Vec<IssueNumber> {
+        let query = match self.search {
+            Some(ref query) => query,
+            None => return Vec::new(),
+        };
+        self.board
+            .pipelines
+            .iter()
+            .flat_map(|pipeline| self.visible_issues(pipeline))
+            .filter(|issue_ref| {
+                let title = match self.issue(issue_ref) {
+                    Some(FutureValue::Ready(issue)) => issue.title.as_str(),
+                    _ => "",
+                };
+                prompt::search_matches(query, issue_ref.number, title)
+            })
+            .map(|issue_ref| issue_ref.number)
+            .collect()
+    }
+
+    /// Selects the next or previous card matching the search, wrapping
+    /// around the board. Without a direction, the selection stays put if it
+    /// matches and moves to the first match otherwise, as the query is typed.
+    fn jump_to_match(&mut self, direction: Option<Direction>) {
+        let matches = self.search_matches();
+        if matches.is_empty() {
+            return;
+        }
+        let current = self.selected_issue().and_then(|issue_ref| {
+            matches
+                .iter()
+                .position(|&number| number == issue_ref.number)
+        });
+        let index = match (current, direction) {
+            (Some(current), None) => current,
+            (None, None) | (None, Some(Direction::Next)) => 0,
+            (None, Some(Direction::Previous)) => matches.len() - 1,
+            (Some(current), Some(Direction::Next)) => (current + 1) % matches.len(),
+            (Some(current), Some(Direction::Previous)) => {
+                (current + matches.len() - 1) % matches.len()
+            }
+        };
+        self.focus_issue(matches[index]);
+    }
+
+    /// Asks which pipeline to move the selected issue to.
+    fn ask_move_issue(&mut self) {
+        let issue_ref = match self.selected_local_issue() {
+            Some(issue_ref) => issue_ref,
+            None => return,
+        };
+        let (pipelines, names): (Vec<_>, Vec<_>) = self
+            .board
+            .pipelines
+            .iter()
+            .enumerate()
+            .filter(|&(pipeline_index, _)| pipeline_index != self.board.selected_pipeline)
+            .map(|(pipeline_index, pipeline)| (pipeline_index, pipeline.pipeline.name.clone()))
+            .unzip();
+        self.question = Some(Question::select(
+            format!("Move #{} to", issue_ref.number.0),
+            names,
+            QuestionAction::MoveIssue(issue_ref.number, pipelines),
+        ));
+    }
+
+    /// Sweeping acts on every stale issue at once, so it's confirmed first
+    /// with the number of issues it will change.
+    fn confirm_sweep(&mut self, action: SweepAction) {
+        let stale = &self.properties.settings.stale;
+        let num_issues = sweep::stale_issues(&self.board, &self.issues, stale, Utc::now())
+            .iter()
+            .filter(|stale_issue| !self.sweep_skipped.contains(&stale_issue.issue.number))
+            .count();
+        if num_issues == 0 {
+            self.status = "No stale issues to sweep".into();
+            return;
+        }
+        self.question = Some(Question::confirm(
+            format!(
+                "{} {}?",
+                action.imperative(),
+                humanize::count_with(num_issues, "stale", Noun::Issue)
+            ),
+            QuestionAction::Sweep(action),
+        ));
+    }
+
+    fn answer_question(&mut self, action: QuestionAction, answer: Answer) {
+        match (action, answer) {
+            (QuestionAction::GoToIssue, Answer::Text(text)) => {
+                match text.trim().trim_start_matches('#').parse() {
+                    Ok(number) => {
+                        if !self.focus_issue(IssueNumber(number)) {
+                            self.status = format!("#{} is not on the board", number);
+                        }
+                    }
+                    Err(_) => self.status = format!("`{}` is not an issue number", text.trim()),
+                }
+            }
+            (QuestionAction::MoveIssue(issue_number, pipelines), Answer::Selected(index)) => {
+                let to_pipeline = pipelines[index];
+                if !self.confirm_wip_limit(issue_number, to_pipeline, Position::Top) {
+                    self.move_issue(issue_number, to_pipeline, Position::Top);
+                    self.auto_assign(issue_number, to_pipeline);
+                }
+            }
+            (
+                QuestionAction::MoveOverWipLimit(issue_number, to_pipeline, position),
+                Answer::Confirmed,
+            ) => {
+                self.move_issue(issue_number, to_pipeline, position);
+                self.auto_assign(issue_number, to_pipeline);
+                self.focus_issue(issue_number);
+            }
+            (QuestionAction::NewIssue(positions), Answer::Selected(index)) => {
+                self.edit_new_issue(positions[index])
+            }
+            (QuestionAction::Snooze(issue_number), Answer::Text(text)) => {
+                self.snooze(issue_number, &text)
+            }
+            (QuestionAction::AssignToMe(issue_number), Answer::Confirmed) => {
+                self.assign_to_me(issue_number)
+            }
+            (QuestionAction::Sweep(action), Answer::Confirmed) => self.sweep(action),
+            (QuestionAction::RememberWorkspace(workspace), Answer::Confirmed) => {
+                self.remember_workspace(&workspace);
+                self.status = format!(
+                    "Opening the board of {} from now on, C-x w to switch",
+                    workspace.name
+                );
+            }
+            (QuestionAction::FindTitles, Answer::Text(pattern)) => match Regex::new(&pattern) {
+                Ok(_) => {
+                    self.question = Some(Question::text(
+                        format!("Replace /{}/ with ($1 for the first group)", pattern),
+                        self.title_replace
+                            .as_ref()
+                            .map(|replace| replace.replacement.clone())
+                            .unwrap_or_default(),
+                        QuestionAction::ReplaceTitles(pattern),
+                    ))
+                }
+                Err(error) => self.status = format!("Invalid pattern: {}", error),
+            },
+            (QuestionAction::ReplaceTitles(pattern), Answer::Text(replacement)) => {
+                if let Ok(pattern) = Regex::new(&pattern) {
+                    self.title_replace = Some(TitleReplace {
+                        pattern,
+                        replacement,
+                    });
+                    self.load_all_issues();
+                    self.rename_skipped.clear();
+                    self.overlay = Some(OverlayState {
+                        kind: OverlayKind::Rename,
+                        selected: 0,
+                    });
+                }
+            }
+            (QuestionAction::Rename, Answer::Confirmed) => self.apply_renames(),
+            (QuestionAction::Changelog(pipeline_index), Answer::Selected(0)) => {
+                self.draft_changelog(pipeline_index, ChangelogOutput::Clipboard)
+            }
+            (QuestionAction::Changelog(pipeline_index), Answer::Selected(1)) => {
+                self.draft_changelog(pipeline_index, ChangelogOutput::File)
+            }
+            (QuestionAction::Changelog(_), Answer::Selected(_))
+                if self.read_only_reason().is_some() =>
+            {
+                self.status = format!(
+                    "Can't draft a release, {} is {}",
+                    self.properties.repo.full_name.0,
+                    self.read_only_reason().unwrap_or_default()
+                );
+            }
+            (QuestionAction::Changelog(pipeline_index), Answer::Selected(_)) => {
+                self.question = Some(Question::text(
+                    "Tag of the release",
+                    "",
+                    QuestionAction::ReleaseTag(pipeline_index),
+                ));
+            }
+            (QuestionAction::ReleaseTag(pipeline_index), Answer::Text(tag_name)) => {
+                let tag_name = tag_name.trim();
+                if !tag_name.is_empty() {
+                    self.draft_changelog(pipeline_index, ChangelogOutput::Release(tag_name.into()));
+                }
+            }
+            (QuestionAction::Filter(FilterField::Label), Answer::Text(label)) => {
+                self.board.filter.label =
+                    Some(label.trim().to_owned()).filter(|label| !label.is_empty());
+                self.clamp_selection();
+            }
+            (QuestionAction::Filter(FilterField::Assignee), Answer::Text(login)) => {
+                let login = login.trim().trim_start_matches('@');
+                self.board.filter.assignee = match login {
+                    "" => None,
+                    "me" => Some(self.properties.user.login.clone()),
+                    login => Some(login.to_owned()),
+                };
+                self.clamp_selection();
+            }
+            (QuestionAction::Filter(FilterField::State), Answer::Selected(index)) => {
+                self.board.filter.state = FILTER_STATES[index];
+                self.clamp_selection();
+            }
+            (QuestionAction::Search, Answer::Text(query)) => {
+                self.search = Some(query).filter(|query| !query.trim().is_empty());
+                let num_matches = self.search_matches().len();
+                self.status = match num_matches {
+                    _ if self.search.is_none() => String::new(),
+                    0 => "No cards match the search".into(),
+                    1 => "1 card matches, n / p to jump to it".into(),
+                    _ => format!(
+                        "{} match, n / p to jump between them",
+                        humanize::count(num_matches, Noun::Card)
+                    ),
+                };
+            }
+            (action, answer) => log::warn!("Unexpected answer {:?} to {:?}", answer, action),
+        }
+    }
+
+    fn accept_picker(&mut self) {
+        let picker = match self.picker.take() {
+            Some(picker) => picker,
+            None => return,
+        };
+        let selected = match picker.selected_item() {
+            Some(selected) => selected,
+            None => {
+                // The board still has to be opened
+                if let PickerAction::ChooseWorkspace(_) = picker.action {
+                    self.load_board();
+                }
+                return;
+            }
+        };
+        match picker.action {
+            PickerAction::Team(action, teams) => self.pick_team(action, teams[selected].clone()),
+            PickerAction::Share(issue, webhooks) => {
+                self.share_issue(issue, webhooks[selected].clone())
+            }
+            PickerAction::ConnectPullRequest(pull_request, issue_numbers) => {
+                self.connect_pull_request(pull_request, issue_numbers[selected])
+            }
+            PickerAction::ToggleLabel(issue_number, labels) => {
+                self.toggle_label(issue_number, labels[selected].clone())
+            }
+            PickerAction::ToggleRelease(issue_number, releases) => {
+                self.toggle_release(issue_number, releases[selected].clone())
+            }
+            PickerAction::SelectWorkspace(workspaces) => {
+                self.open_workspace(workspaces[selected].clone())
+            }
+            PickerAction::ChooseWorkspace(workspaces) => {
+                let workspace = workspaces[selected].clone();
+                self.status = format!("Opening the board of {}", workspace.name);
+                self.question = Some(Question::confirm(
+                    format!(
+                        "Always open the board of {} for {}?",
+                        workspace.name, self.properties.repo.full_name.0
+                    ),
+                    QuestionAction::RememberWorkspace(workspace.clone()),
+                ));
+                self.workspace = Some(workspace);
+                self.load_board();
+            }
+            PickerAction::SwitchRepo(repos) => self.switch_repo(repos[selected].clone()),
+            PickerAction::Scheme(names) => self.select_scheme(names[selected].clone()),
+        }
+    }
+}
+
+impl App {
+    fn new(properties: Properties, frame: Rect, link: AppLink) -> Self {
+        {
+            let link = link.clone();
+            let zenhub_client = properties.zenhub_client.clone();
+            let workspace = properties.workspace.clone().or_else(|| {
+                properties
+                    .state
+                    .repo(&properties.repo.full_name.0)
+                    .and_then(|repo| repo.workspace.clone())
+            });
+            match workspace {
+                Some(workspace) => properties.async_runtime.spawn(
+                    zenhub_client
+                        .get_workspaces(properties.repo.id)
+                        .map(move |workspaces| {
+                            link.send(Message::LoadedWorkspaces(
+                                workspaces,
+                                WorkspaceIntent::Open(workspace),
+                            ));
+                        }),
+                ),
+                None => properties.async_runtime.spawn(
+                    zenhub_client
+                        .get_workspaces(properties.repo.id)
+                        .map(move |workspaces| {
+                            link.send(Message::LoadedWorkspaces(
+                                workspaces,
+                                WorkspaceIntent::Choose,
+                            ));
+                        }),
+                ),
+            };
+        }
+
+        let settings_watcher = Arc::new(());
+        if let Some(path) = properties.settings_path.clone() {
+            let link = link.clone();
+            let app_alive = Arc::downgrade(&settings_watcher);
+            thread::spawn(move || {
+                settings::watch(path, move || {
+                    let alive = app_alive.upgrade().is_some();
+                    if alive {
+                        link.send(Message::SettingsChanged);
+                    }
+                    alive
+                })
+            });
+        }
+
+        // Show the board as it was last time while the live one loads
+        let (board, issues, status) =
+            match properties.cache_path.as_ref().and_then(cache::read_cache) {
+                Some(cache) => (
+                    cache.board.into(),
+                    cache
+                        .issues
+                        .into_iter()
+                        .map(|issue| (issue.number, FutureValue::Ready(issue)))
+                        .collect(),
+                    match cache.saved_at {
+                        Some(saved_at) => format!(
+                            "Showing the board as of {} ago while it loads",
+                            issue_card::format_age(Utc::now() - saved_at)
+                        ),
+                        None => "Showing the cached board while it loads".into(),
+                    },
+                ),
+                None => (BoardView::default(), HashMap::new(), String::new()),
+            };
+        let stale_issues = issues.keys().copied().collect();
+        let splash = board.pipelines.is_empty();
+
+        let mut app = Self {
+            state: properties.state.clone(),
+            stats: properties.stats.clone(),
+            link,
+            theme: Rc::new(Theme::new(
+                &themes::from_settings(&properties.settings, properties.settings_path.as_deref()),
+                &properties.settings.focus_emphasis,
+                &properties.settings.status_segments,
+            )),
+            frame,
+            board,
+            issues,
+            stale_issues,
+            foreign_issues: HashMap::new(),
+            foreign_repos: HashMap::new(),
+            comments: HashMap::new(),
+            pull_requests: HashMap::new(),
+            pull_request_statuses: HashMap::new(),
+            connected_pull_requests: HashMap::new(),
+            noted_missing_connections: false,
+            repo_labels: None,
+            releases: None,
+            release_issues: HashMap::new(),
+            dependencies: Vector::new(),
+            preloaded_pinned_repos: false,
+            remote_moves: Vec::new(),
+            board_changes: Vec::new(),
+            local_moves: HashMap::new(),
+            local_estimates: HashMap::new(),
+            quit_pressed_at: None,
+            polling_board: false,
+            last_board_poll: Instant::now(),
+            refreshing: false,
+            last_cache_write: Instant::now(),
+            cache_dirty: false,
+            cache_writer: None,
+            focus_follow: None,
+            followed: None,
+            epics: HashMap::new(),
+            collapsed_epics: HashSet::new(),
+            marked_issues: HashSet::new(),
+            resurfaced: HashSet::new(),
+            saved_board: properties
+                .state
+                .repo(&properties.repo.full_name.0)
+                .and_then(|repo| repo.board.clone()),
+            bulk_estimate: None,
+            title_replace: None,
+            rename_skipped: HashSet::new(),
+            pending_renames: VecDeque::new(),
+            sweep_skipped: HashSet::new(),
+            agenda: FutureValue::Pending,
+            notifications: FutureValue::Pending,
+            startup_view: Some(properties.startup_view),
+            workspace: None,
+            workspaces: None,
+            picker: None,
+            question: None,
+            search: None,
+            overlay: None,
+            show_detail: false,
+            show_stats_header: false,
+            density: properties.settings.density,
+            fold_epic_children: properties.settings.fold_epic_children,
+            batch_issue_loads: true,
+            show_jump_codes: false,
+            _settings_watcher: settings_watcher,
+            log_level: Level::INFO,
+            log_generation: 0,
+            num_log_lines: 0,
+            status,
+            splash,
+            board_error: None,
+            num_pending_tasks: 1,
+            needs_redraw: false,
+            last_redraw: Instant::now(),
+            properties,
+        };
+        app.restore_board();
+        app.warn_shadowed_triage_keys();
+        app
+    }
+}
+
+impl Component for App {
+    type Message = Message;
+    type Properties = Properties;
+
+    fn create(properties: Self::Properties, frame: Rect, link: ComponentLink<Self>) -> Self {
+        Self::new(properties, frame, AppLink::Zi(link))
+    }
+
+    fn resize(&mut self, frame: Rect) -> ShouldRender {
+        self.frame = frame;
+        ShouldRender::Yes
+    }
+
+    fn update(&mut self, message: Self::Message) -> ShouldRender {
+        let span = tracing::debug_span!("message", name = message.name());
+        let _enter = span.enter();
+        self.update_crash_summary(message.name());
+        match message {
+            message if message.writes_to_github() && self.read_only_reason().is_some() => {
+                self.status = format!(
+                    "{} is {}",
+                    self.properties.repo.full_name.0,
+                    self.read_only_reason().unwrap_or_default()
+                );
+            }
+            Message::Redraw => {}
+            Message::SettingsChanged => self.reload_settings(),
+            Message::NextPipeline => self.board.select_next_pipeline(),
+            Message::PreviousPipeline => self.board.select_previous_pipeline(),
+            Message::SelectIssue(issue_index) => {
+                let num_issues = self
+                    .board
+                    .selected_pipeline()
+                    .map(|pipeline| self.num_cards(pipeline))
+                    .unwrap_or(0);
+                if let Some(pipeline) = self.board.selected_pipeline_mut() {
+                    pipeline.select_issue(issue_index, num_issues);
+                }
+                if let Some(issue_ref) = self.selected_issue() {
+                    self.resurfaced.remove(&issue_ref.number);
+                }
+            }
+            Message::PageIssues(direction) => {
+                let page_size = self.page_size();
+                let num_issues = self
+                    .board
+                    .selected_pipeline()
+                    .map(|pipeline| self.num_cards(pipeline))
+                    .unwrap_or(0);
+                if let Some(pipeline) = self.board.selected_pipeline_mut() {
+                    // Moving by a whole page keeps the selection at the same
+                    // place within the page
+                    let issue_index = match direction {
+                        Direction::Previous => pipeline.selected_issue.saturating_sub(page_size),
+                        Direction::Next => pipeline.selected_issue + page_size,
+                    };
+                    pipeline.select_issue(issue_index, num_issues);
+                }
+            }
+            Message::LoadedBoard(new_board) => {
+                let new_board = match new_board {
+                    Ok(new_board) => new_board,
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.num_pending_tasks -= 1;
+                        self.status = format!("Could not load the board: {}", error);
+                        self.board_error = Some(error.to_string());
+                        return ShouldRender::Yes;
+                    }
+                };
+                self.num_pending_tasks -= 1;
+                self.board_error = None;
+                if self.board.pipelines.is_empty() {
+                    self.board = new_board.into();
+                    self.restore_board();
+                } else {
+                    // The cached board is shown, keep the selection
+                    self.board.refresh(
+                        self.properties.repo.id,
+                        new_board,
+                        &HashSet::new(),
+                        &HashSet::new(),
+                    );
+                }
+                self.cache_dirty = true;
+                self.load_top_issues();
+
+                let epic_numbers: Vec<_> = self
+                    .board
+                    .pipelines
+                    .iter()
+                    .flat_map(|pipeline| pipeline.pipeline.issues.iter())
+                    .filter(|issue_ref| issue_ref.is_epic)
+                    .map(|issue_ref| issue_ref.number)
+                    .collect();
+                let repo_id = self.properties.repo.id;
+                for issue_number in epic_numbers {
+                    self.spawn(
+                        self.properties
+                            .zenhub_client
+                            .clone()
+                            .get_epic(repo_id, issue_number)
+                            .map(move |epic| Message::LoadedEpic(issue_number, epic)),
+                    );
+                }
+                self.load_foreign_issues();
+                self.load_releases();
+                self.load_dependencies();
+                if let Some(view) = self.startup_view.take() {
+                    self.open_view(view);
+                }
+                if !mem::replace(&mut self.preloaded_pinned_repos, true) {
+                    self.preload_pinned_repos();
+                }
+            }
+            Message::WriteCache => {
+                self.last_cache_write = Instant::now();
+                self.write_cache();
+            }
+            Message::PollBoard => {
+                self.polling_board = true;
+                self.spawn(self.board_request().map(Message::PolledBoard));
+            }
+            Message::Refresh(trigger) => {
+                self.refreshing = true;
+                if trigger == RefreshTrigger::Key {
+                    self.status = "Refreshing the board...".into();
+                    // Reloading on demand must not be answered from memory
+                    self.properties
+                        .zenhub_client
+                        .forget_boards(self.properties.repo.id);
+                }
+                self.spawn(
+                    self.board_request()
+                        .map(move |result| Message::Refreshed(trigger, result)),
+                );
+            }
+            Message::Refreshed(trigger, result) => {
+                self.num_pending_tasks -= 1;
+                self.refreshing = false;
+                match result {
+                    Ok(board) => self.refresh_board(trigger, board),
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        if trigger == RefreshTrigger::Key {
+                            self.status = format!("Could not refresh the board: {}", error);
+                        }
+                    }
+                }
+            }
+            Message::PolledBoard(result) => {
+                self.num_pending_tasks -= 1;
+                self.polling_board = false;
+                self.last_board_poll = Instant::now();
+                match result {
+                    Ok(board) => {
+                        // Highlight the cards moved elsewhere before taking
+                        // the rest of the reloaded board
+                        self.apply_remote_moves(board.clone());
+                        if self.properties.settings.auto_refresh_secs.is_some() {
+                            self.refresh_board(RefreshTrigger::Timer, board);
+                        }
+                    }
+                    Err(error) => log::warn!("Could not poll the board: {}", error),
+                }
+            }
+            Message::LoadedIssueEvents(issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(events) => {
+                        if let Some(event) = events
+                            .iter()
+                            .find(|event| event.kind == IssueEventKind::TransferIssue)
+                        {
+                            let name = |pipeline: &Option<PipelineName>| {
+                                pipeline.as_ref().map_or_else(
+                                    || "?".to_owned(),
+                                    |pipeline| pipeline.name.clone(),
+                                )
+                            };
+                            self.status = format!(
+                                "#{} was moved from {} to {} at {}",
+                                issue_number.0,
+                                name(&event.from_pipeline),
+                                name(&event.to_pipeline),
+                                event.created_at.with_timezone(&Local).format("%H:%M")
+                            );
+                        }
+                    }
+                    Err(error) => log::warn!(
+                        "Could not load the events of #{}: {}",
+                        issue_number.0,
+                        error
+                    ),
+                }
+            }
+            Message::ExpireMoveHighlights => {
+                self.remote_moves
+                    .retain(|remote_move| remote_move.at.elapsed() < MOVE_HIGHLIGHT_DURATION);
+            }
+            Message::LoadedEpic(issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(epic) => {
+                        // Children from other repos are never on this board
+                        let repo_id = self.properties.repo.id;
+                        let children = epic
+                            .issues
+                            .iter()
+                            .filter(|child| child.repo_id == repo_id)
+                            .map(|child| child.number)
+                            .collect();
+                        self.epics.insert(issue_number, children);
+                        self.clamp_selection();
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not load epic #{}: {}", issue_number.0, error);
+                    }
+                }
+            }
+            Message::LoadedForeignRepo(repo_id, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(repo) => {
+                        self.foreign_repos
+                            .insert(repo_id, FutureValue::Ready(repo.full_name));
+                        self.load_foreign_issues();
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.foreign_repos.insert(
+                            repo_id,
+                            FutureValue::Error(format!("Could not load its repo: {}", error)),
+                        );
+                    }
+                }
+            }
+            Message::LoadedForeignIssue(repo_id, issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                let issue = match result {
+                    Ok(issue) => FutureValue::Ready(issue),
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        FutureValue::Error(format!("{:?}", error))
+                    }
+                };
+                self.foreign_issues.insert((repo_id, issue_number), issue);
+                self.needs_redraw = true;
+                return ShouldRender::No;
+            }
+            Message::LoadedIssue(issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                self.insert_loaded_issue(issue_number, result);
+
+                // Issues arrive in bursts when a board loads, coalesce them
+                // into a single redraw per frame (see `tick`).
+                self.needs_redraw = true;
+                return ShouldRender::No;
+            }
+            Message::LoadedIssues(issue_numbers, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(issues) => {
+                        let mut missing: HashSet<_> = issue_numbers.into_iter().collect();
+                        for issue in issues {
+                            missing.remove(&issue.number);
+                            self.insert_loaded_issue(issue.number, Ok(issue));
+                        }
+                        self.load_issues_one_by_one(missing.into_iter().collect());
+                    }
+                    Err(error) => {
+                        log::warn!(
+                            "Could not load issues in a batch, loading them one by one: {:?}",
+                            error
+                        );
+                        self.batch_issue_loads = false;
+                        self.load_issues_one_by_one(issue_numbers);
+                    }
+                }
+                self.needs_redraw = true;
+                return ShouldRender::No;
+            }
+            Message::LoadedPullRequest(issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(pull_request) => {
+                        self.pull_requests.insert(issue_number, pull_request);
+                    }
+                    Err(error) => log::error!("{:?}", error),
+                }
+                self.needs_redraw = true;
+                return ShouldRender::No;
+            }
+            Message::LoadedPullRequestStatus(issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(status) => {
+                        self.pull_request_statuses.insert(issue_number, status);
+                    }
+                    Err(error) => log::error!("{:?}", error),
+                }
+                self.needs_redraw = true;
+                return ShouldRender::No;
+            }
+            Message::LoadComments(issue_number) => {
+                self.comments.insert(issue_number, FutureValue::Pending);
+                let repo = Arc::new(self.properties.repo.full_name.clone());
+                self.spawn(
+                    self.properties
+                        .github_client
+                        .clone()
+                        .get_comments(repo, issue_number)
+                        .map(move |comments| Message::LoadedComments(issue_number, comments)),
+                );
+            }
+            Message::LoadedComments(issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                let comments = match result {
+                    Ok(comments) => FutureValue::Ready(comments),
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        FutureValue::Error(error.to_string())
+                    }
+                };
+                self.comments.insert(issue_number, comments);
+            }
+            Message::PickIssueToConnect => self.pick_issue_to_connect(),
+            Message::PickLabel => self.pick_label(),
+            Message::LoadedReleases(result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(releases) => {
+                        // Issues from other repos are never on this board
+                        let repo_id = self.properties.repo.id;
+                        self.release_issues = releases
+                            .iter()
+                            .map(|(release, issues)| {
+                                (
+                                    release.id.clone(),
+                                    issues
+                                        .iter()
+                                        .filter(|issue| issue.repo_id == repo_id)
+                                        .map(|issue| issue.number)
+                                        .collect(),
+                                )
+                            })
+                            .collect();
+                        self.releases =
+                            Some(releases.into_iter().map(|(release, _)| release).collect());
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not load releases: {}", error);
+                    }
+                }
+            }
+            Message::PreloadedBoards(results) => {
+                self.num_pending_tasks -= 1;
+                for (full_name, result) in results {
+                    match result {
+                        Ok(()) => log::debug!("Preloaded the board of {}", full_name.0),
+                        Err(error) => log::warn!(
+                            "Could not preload the board of {}: {:?}",
+                            full_name.0,
+                            error
+                        ),
+                    }
+                }
+            }
+            Message::LoadedDependencies(result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(dependencies) => self.dependencies = dependencies,
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not load dependencies: {}", error);
+                    }
+                }
+            }
+            Message::PickRelease => self.pick_release(),
+            Message::ToggledRelease(issue_number, release_id, added, result) => {
+                self.num_pending_tasks -= 1;
+                if let Err(error) = result {
+                    log::error!("{:?}", error);
+                    self.status = format!(
+                        "Could not change the release of #{}: {}",
+                        issue_number.0, error
+                    );
+                    let issues = self.release_issues.entry(release_id).or_default();
+                    if added {
+                        issues.remove(&issue_number);
+                    } else {
+                        issues.insert(issue_number);
+                    }
+                }
+            }
+            Message::LoadedLabels(result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(labels) => {
+                        self.status.clear();
+                        self.repo_labels = Some(labels);
+                        self.pick_label();
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not load labels: {}", error);
+                    }
+                }
+            }
+            Message::ToggledLabel(previous, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(issue) => {
+                        self.insert_issue(issue.number, FutureValue::Ready(issue));
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!(
+                            "Could not change labels of #{}: {}",
+                            previous.number.0, error
+                        );
+                        self.issues
+                            .insert(previous.number, FutureValue::Ready(previous));
+                    }
+                }
+            }
+            Message::LoadedConnectedPullRequests(result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(connections) => self.connected_pull_requests.extend(connections),
+                    Err(error) => log::error!("{:?}", error),
+                }
+                self.needs_redraw = true;
+                return ShouldRender::No;
+            }
+            Message::ConnectedPullRequest(pull_request, issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(()) => {
+                        self.status =
+                            format!("Connected #{} to #{}", pull_request.0, issue_number.0);
+                        let pull_requests = self
+                            .connected_pull_requests
+                            .entry(issue_number)
+                            .or_default();
+                        if !pull_requests.contains(&pull_request) {
+                            pull_requests.push_back(pull_request);
+                        }
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!(
+                            "Could not connect #{} to #{}: {}",
+                            pull_request.0, issue_number.0, error
+                        );
+                    }
+                }
+            }
+            Message::NewIssue => self.edit_new_issue(self.properties.settings.new_issue_position),
+            Message::AskNewIssuePosition => self.ask_new_issue_position(),
+            Message::EditedNewIssue(pipeline_id, position, result) => match result {
+                Ok(text) => self.create_issue(pipeline_id, position, text),
+                Err(error) => {
+                    log::error!("{:?}", error);
+                    self.status = format!("Could not edit the new issue: {}", error);
+                }
+            },
+            Message::CreatedIssue(pipeline_id, position, result) => {
+                self.num_pending_tasks -= 1;
+                match (result, self.board.pipeline_with_id(&pipeline_id)) {
+                    (Ok(issue), None) => {
+                        // The pipeline left the board, the next reload shows
+                        // the issue wherever it is
+                        self.status = format!("Created #{}", issue.number.0);
+                        self.insert_issue(issue.number, FutureValue::Ready(issue));
+                    }
+                    (Ok(issue), Some(pipeline_index)) => {
+                        let issue_number = issue.number;
+                        let pipeline = &mut self.board.pipelines[pipeline_index];
+                        self.status =
+                            format!("Created #{} in {}", issue_number.0, pipeline.pipeline.name);
+                        let issues = &mut pipeline.pipeline.issues;
+                        let index = match position {
+                            Position::Top => 0,
+                            Position::Bottom => issues.len(),
+                            Position::Index(index) => cmp::min(index, issues.len()),
+                        };
+                        issues.insert(
+                            index,
+                            IssueRef {
+                                number: issue_number,
+                                estimate: None,
+                                position: Some(index),
+                                is_epic: false,
+                                repo_id: None,
+                            },
+                        );
+                        self.local_moves.insert(issue_number, Instant::now());
+                        self.insert_issue(issue_number, FutureValue::Ready(issue));
+                        self.focus_issue(issue_number);
+                    }
+                    (Err(error), _) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not create the issue: {}", error);
+                    }
+                }
+            }
+            Message::ToggleMark(issue_number) => {
+                if self.marked_issues.remove(&issue_number).is_none() {
+                    self.marked_issues.insert(issue_number);
+                }
+            }
+            Message::ClearMarks => self.marked_issues.clear(),
+            Message::CreateEpicFromMarked => self.edit_epic_from_marked(),
+            Message::EditedEpic(result) => match result {
+                Ok(text) => self.create_epic(text),
+                Err(error) => {
+                    log::error!("{:?}", error);
+                    self.status = format!("Could not edit the epic: {}", error);
+                }
+            },
+            Message::CreatedEpic(children, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(epic) => {
+                        self.status = format!(
+                            "Created epic #{} with {}",
+                            epic.number.0,
+                            humanize::count(children.len(), Noun::Issue)
+                        );
+                        if let Some(new_issues) =
+                            self.board.pipeline_of_kind(PipelineKind::NewIssues)
+                        {
+                            self.board.pipelines[new_issues]
+                                .pipeline
+                                .issues
+                                .push_front(IssueRef {
+                                    number: epic.number,
+                                    estimate: None,
+                                    position: None,
+                                    is_epic: true,
+                                    repo_id: None,
+                                });
+                        }
+                        self.epics.insert(epic.number, children);
+                        self.insert_issue(epic.number, FutureValue::Ready(epic));
+                        self.marked_issues.clear();
+                        self.clamp_selection();
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not create the epic: {}", error);
+                    }
+                }
+            }
+            Message::EditIssue(issue_number, result) => {
+                let issue = match result {
+                    Ok(issue) => FutureValue::Ready(issue),
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        FutureValue::Error(format!("{:?}", error))
+                    }
+                };
+                self.insert_issue(issue_number, issue);
+            }
+            Message::OpenedProject(issue_number, result) => match result {
+                Ok(path) => {
+                    self.status = format!("Opened #{} in `{}`", issue_number.0, path.display());
+                }
+                Err(error) => {
+                    log::error!("{:?}", error);
+                    self.status = format!("Could not open #{}: {}", issue_number.0, error);
+                }
+            },
+            Message::SharedIssue(issue_number, webhook_name, result) => {
+                self.num_pending_tasks -= 1;
+                self.status = match result {
+                    Ok(()) => format!("Shared #{} to {}", issue_number.0, webhook_name),
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        format!(
+                            "Could not share #{} to {}: {}",
+                            issue_number.0, webhook_name, error
+                        )
+                    }
+                };
+            }
+            Message::TriageSelectedIssue(key) => self.triage_selected_issue(key),
+            Message::TriagedIssue(action, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(issue) => {
+                        if action.comment.is_some() {
+                            self.record_activity(Activity::PostedComment);
+                            self.comments.remove(&issue.number);
+                        }
+                        if action.close {
+                            self.record_activity(Activity::ClosedIssue);
+                        }
+                        self.status = format!("Triaged #{}", issue.number.0);
+                        self.insert_issue(issue.number, FutureValue::Ready(issue));
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not triage issue: {}", error);
+                    }
+                }
+            }
+            Message::ShowSweep => {
+                self.load_swept_issues();
+                self.sweep_skipped.clear();
+                self.overlay = Some(OverlayState {
+                    kind: OverlayKind::Sweep,
+                    selected: 0,
+                });
+            }
+            Message::SweptIssue(action, issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(issue) => {
+                        match action {
+                            SweepAction::Nudge => {
+                                self.record_activity(Activity::PostedComment);
+                                self.comments.remove(&issue_number);
+                            }
+                            SweepAction::Close => {
+                                self.record_activity(Activity::ClosedIssue);
+                                if let Some(closed) =
+                                    self.board.pipeline_of_kind(PipelineKind::Closed)
+                                {
+                                    self.board.move_issue(
+                                        self.properties.repo.id,
+                                        issue_number,
+                                        closed,
+                                        0,
+                                    );
+                                    self.cache_dirty = true;
+                                    self.local_moves.insert(issue_number, Instant::now());
+                                }
+                            }
+                            SweepAction::Label | SweepAction::Icebox => {}
+                        }
+                        self.status = format!("{} #{}", action.past_tense(), issue_number.0);
+                        self.insert_issue(issue_number, FutureValue::Ready(issue));
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not sweep #{}: {}", issue_number.0, error);
+                    }
+                }
+            }
+            Message::OpenProject => self.open_project(),
+            Message::WriteCommitTemplate => self.write_commit_template(),
+            Message::PickWebhook => self.pick_webhook(),
+            Message::OpenInBrowser => self.open_in_browser(),
+            Message::CopyPipelineMarkdown => self.copy_pipeline_markdown(),
+            Message::PreviewImages => self.preview_images(),
+            Message::LoadedImages(protocol, images) => {
+                self.num_pending_tasks -= 1;
+                let mut loaded = Vec::new();
+                for (caption, url, result) in images {
+                    match result {
+                        Ok(bytes) => loaded.push((caption, url, bytes)),
+                        Err(error) => {
+                            log::error!("{:?}", error);
+                            self.status = format!("Could not download {}: {}", caption, error);
+                        }
+                    }
+                }
+                if !loaded.is_empty() {
+                    self.link.run_exclusive(move || {
+                        Some(Message::PreviewedImages(terminal::show_images(
+                            protocol, &loaded,
+                        )))
+                    });
+                }
+            }
+            Message::PreviewedImages(result) => {
+                if let Err(error) = result {
+                    log::error!("{:?}", error);
+                    self.status = format!("Could not show images: {}", error);
+                }
+            }
+            Message::ShowNotifications => self.open_view(StartupView::Notifications),
+            Message::Exit => self.exit(),
+            Message::PressedQuit => {
+                let confirmed = self.properties.settings.quit_with_q == QuitKey::Once
+                    || self.quit_pressed_at.map_or(false, |pressed_at| {
+                        pressed_at.elapsed() < QUIT_CONFIRM_INTERVAL
+                    });
+                if confirmed {
+                    self.exit();
+                } else {
+                    self.quit_pressed_at = Some(Instant::now());
+                    self.status = "Press q again to quit".into();
+                }
+            }
+            Message::LoadedNotifications(result) => {
+                self.num_pending_tasks -= 1;
+                self.notifications = match result {
+                    Ok(notifications) => FutureValue::Ready(notifications),
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        FutureValue::Error(format!("Could not load notifications: {}", error))
+                    }
+                };
+            }
+            Message::ToggleFocusFollow => self.toggle_focus_follow(),
+            Message::ToggleMyWork => {
+                if self.board.filter.assignee.take().is_some() {
+                    self.status.clear();
+                } else {
+                    self.open_view(StartupView::MyWork);
+                }
+                self.clamp_selection();
+            }
+            Message::ToggleEpicsOnly => {
+                if self.board.filter.kind == Some(CardKind::Epics) {
+                    self.board.filter.kind = None;
+                    self.status.clear();
+                } else {
+                    self.open_view(StartupView::Epics);
+                }
+                self.clamp_selection();
+            }
+            Message::SortPipeline(sort) => {
+                let selected = self.selected_issue().map(|issue_ref| issue_ref.number);
+                let selected_pipeline = self.board.selected_pipeline;
+                let pipeline = &mut self.board.pipelines[selected_pipeline];
+                pipeline.sort = sort;
+                self.status = match sort {
+                    Some(sort) => format!(
+                        "Sorted {} by {}, s s for the order on Zenhub",
+                        pipeline.pipeline.name,
+                        sort.name()
+                    ),
+                    None => format!("{} is in its order on Zenhub", pipeline.pipeline.name),
+                };
+                if let Some(issue_number) = selected {
+                    self.focus_issue(issue_number);
+                }
+            }
+            Message::CycleKindFilter => {
+                self.board.filter.kind = CardKind::next(self.board.filter.kind);
+                self.status = match self.board.filter.kind {
+                    Some(CardKind::Issues) => "Showing issues only".into(),
+                    Some(CardKind::PullRequests) => "Showing pull requests only".into(),
+                    Some(CardKind::Epics) => "Showing epics only".into(),
+                    None => "Showing every kind of card".into(),
+                };
+                self.clamp_selection();
+            }
+            Message::ToggleFoldEpicChildren => {
+                self.fold_epic_children = !self.fold_epic_children;
+                self.status = if self.fold_epic_children {
+                    "Folding children into their epics in other pipelines".into()
+                } else {
+                    "Showing children of epics in their own pipelines".into()
+                };
+                self.clamp_selection();
+            }
+            Message::ShowAgenda => {
+                self.load_agenda();
+                self.overlay = Some(OverlayState {
+                    kind: OverlayKind::Agenda,
+                    selected: 0,
+                });
+            }
+            Message::LoadedAgenda(result) => {
+                self.num_pending_tasks -= 1;
+                self.agenda = match result {
+                    Ok(milestones) => FutureValue::Ready(milestones),
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        FutureValue::Error(format!("Could not load milestones: {}", error))
+                    }
+                };
+            }
+            Message::HidePipeline(pipeline_index) => {
+                if !self.board.hide_pipeline(pipeline_index) {
+                    self.status = "The last pipeline shown can't be hidden".into();
+                }
+            }
+            Message::CollapsePipeline(pipeline_index) => {
+                self.board.toggle_collapsed(pipeline_index)
+            }
+            Message::ShowAllPipelines => self.board.show_all_pipelines(),
+            Message::LoadTeams(action) => self.load_teams(action),
+            Message::LoadedTeams(action, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(teams) => {
+                        self.status.clear();
+                        let title = match action {
+                            TeamAction::RequestReview(issue_number) => {
+                                format!("Request review on #{} from", issue_number.0)
+                            }
+                            TeamAction::Filter => "Filter by team".into(),
+                        };
+                        self.picker = Some(Picker::new(
+                            title,
+                            PickerAction::Team(action, teams.clone()),
+                            teams.iter().map(|team| team.name.clone()).collect(),
+                        ));
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("{}", error);
+                    }
+                }
+            }
+            Message::LoadedTeamMembers(team, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(members) => {
+                        let selected = self.selected_issue().map(|issue_ref| issue_ref.number);
+                        self.status = format!("Showing issues assigned to {}", team.name);
+                        self.board.filter.team = Some(TeamFilter {
+                            team,
+                            members: members.into_iter().map(|member| member.login).collect(),
+                        });
+                        self.board
+                            .pipelines
+                            .iter_mut()
+                            .for_each(|pipeline| pipeline.selected_issue = 0);
+                        // Keep the selected card if the team is assigned to it
+                        if let Some(issue_number) = selected {
+                            self.focus_issue(issue_number);
+                        }
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("{}", error);
+                    }
+                }
+            }
+            Message::RequestedTeamReview(issue_number, team, result) => {
+                self.num_pending_tasks -= 1;
+                self.status = match result {
+                    Ok(()) => format!("Requested review on #{} from {}", issue_number.0, team.name),
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        format!("{}", error)
+                    }
+                };
+            }
+            Message::MoveSelectedIssue(card_move) => self.move_selected_issue(card_move),
+            Message::PromoteIssue(issue_number) => {
+                match self.board.pipeline_of_kind(PipelineKind::NewIssues) {
+                    Some(new_issues) => self.move_issue(issue_number, new_issues, Position::Top),
+                    None => self.status = "The board has no New Issues pipeline".into(),
+                }
+            }
+            Message::MovedIssue(issue_number, (from_pipeline, from_position), result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(()) => {
+                        self.status = format!("Moved #{}", issue_number.0);
+                        self.record_activity(Activity::MovedIssue);
+                        let closed = self.board.pipelines.iter().any(|pipeline| {
+                            pipeline.pipeline.kind() == PipelineKind::Closed
+                                && pipeline.pipeline.issues.iter().any(|issue_ref| {
+                                    issue_ref.refers_to(self.properties.repo.id, issue_number)
+                                })
+                        });
+                        if closed {
+                            self.record_activity(Activity::ClosedIssue);
+                        }
+                        self.sync_labels(issue_number);
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not move #{}: {}", issue_number.0, error);
+                        self.board.move_issue(
+                            self.properties.repo.id,
+                            issue_number,
+                            from_pipeline,
+                            from_position,
+                        );
+                        self.cache_dirty = true;
+                    }
+                }
+            }
+            Message::AssignedIssue(issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(issue) => {
+                        self.status = format!("Assigned #{} to you", issue_number.0);
+                        self.insert_issue(issue_number, FutureValue::Ready(issue));
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not assign #{}: {}", issue_number.0, error);
+                    }
+                }
+            }
+            Message::SyncedLabels(issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(labels) => {
+                        if let Some(FutureValue::Ready(issue)) = self.issues.get_mut(&issue_number)
+                        {
+                            issue.labels = labels;
+                            self.cache_dirty = true;
+                        }
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status =
+                            format!("Could not update labels of #{}: {}", issue_number.0, error);
+                    }
+                }
+            }
+            Message::ClearTeamFilter => {
+                self.board.filter.team = None;
+                self.status.clear();
+            }
+            Message::ToggleBots(pipeline_index) => {
+                if let Some(pipeline) = self.board.pipelines.get_mut(pipeline_index) {
+                    pipeline.bots_expanded = !pipeline.bots_expanded;
+                }
+                if let Some(pipeline) = self.board.pipelines.get(pipeline_index) {
+                    let num_cards = self.num_cards(pipeline);
+                    let pipeline = &mut self.board.pipelines[pipeline_index];
+                    pipeline.select_issue(pipeline.selected_issue, num_cards);
+                }
+            }
+            Message::ToggleEpic(issue_number) => {
+                if self.collapsed_epics.remove(&issue_number).is_none() {
+                    self.collapsed_epics.insert(issue_number);
+                }
+                self.clamp_selection();
+            }
+            Message::SetBookmark(slot) => {
+                if let Some(issue_ref) = self.selected_local_issue() {
+                    self.state
+                        .repo_mut(&self.properties.repo.full_name.0)
+                        .bookmarks
+                        .insert(slot, issue_ref.number);
+                    self.save_state();
+                    self.status = format!("Bookmarked #{} as '{}", issue_ref.number.0, slot);
+                }
+            }
+            Message::AskSnooze => self.ask_snooze(),
+            Message::ExpireSnoozes => self.wake_snoozed(false),
+            Message::WakeAllSnoozed => self.wake_snoozed(true),
+            Message::JumpToBookmark(slot) => {
+                let issue_number = self
+                    .state
+                    .repo(&self.properties.repo.full_name.0)
+                    .and_then(|repo_state| repo_state.bookmarks.get(&slot).copied());
+                self.status = match issue_number {
+                    Some(issue_number) => {
+                        if self.focus_issue(issue_number) {
+                            String::new()
+                        } else {
+                            format!("Bookmarked issue #{} is not on the board", issue_number.0)
+                        }
+                    }
+                    None => format!("No bookmark '{}", slot),
+                };
+            }
+            Message::ToggleOverlay(kind) => {
+                self.overlay = match self.overlay {
+                    Some(overlay) if overlay.kind == kind => {
+                        // The changes were seen, only list newer ones next time
+                        if kind == OverlayKind::Changes {
+                            self.board_changes.clear();
+                        }
+                        None
+                    }
+                    _ => Some(OverlayState { kind, selected: 0 }),
+                }
+            }
+            Message::OverlayNext => {
+                if let Some(overlay) = self.overlay {
+                    let num_lines = self.overlay_content(overlay.kind).1.len();
+                    self.overlay = Some(OverlayState {
+                        selected: cmp::min(overlay.selected + 1, num_lines.saturating_sub(1)),
+                        ..overlay
+                    });
+                }
+            }
+            Message::OverlayAccept => self.accept_overlay(),
+            Message::ShowLogs => {
+                if self.properties.logs.is_some() {
+                    self.overlay = Some(OverlayState {
+                        kind: OverlayKind::Logs,
+                        selected: 0,
+                    });
+                    self.follow_logs(true);
+                } else {
+                    self.status = "Logging is off, restart zentui with --log to see logs".into();
+                }
+            }
+            Message::LogsChanged => {
+                self.log_generation = self
+                    .properties
+                    .logs
+                    .as_ref()
+                    .map_or(0, |logs| logs.generation());
+                self.follow_logs(false);
+            }
+            Message::SetLogLevel(level) => {
+                self.log_level = level;
+                self.follow_logs(true);
+            }
+            Message::OverlayPrevious => {
+                if let Some(overlay) = self.overlay.as_mut() {
+                    overlay.selected = overlay.selected.saturating_sub(1);
+                }
+            }
+            Message::ToggleDetailPane => self.show_detail = !self.show_detail,
+            Message::ToggleStatsHeader => self.show_stats_header = !self.show_stats_header,
+            Message::CycleDensity => {
+                self.density = self.density.next();
+                self.status = format!("Showing {} cards", self.density.name());
+            }
+            Message::ToggleJumpCodes => self.show_jump_codes = !self.show_jump_codes,
+            Message::JumpToCode(code) => {
+                self.show_jump_codes = false;
+                match self
+                    .jump_codes()
+                    .into_iter()
+                    .find(|(_, jump_code)| *jump_code == code)
+                {
+                    Some((issue_number, _)) => {
+                        self.focus_issue(issue_number);
+                    }
+                    None => self.status = format!("No card with jump code `{}`", code),
+                }
+            }
+            Message::PickRepo => self.pick_repo(),
+            Message::PickScheme => self.pick_scheme(),
+            Message::SwitchRepo(full_name) => self.switch_repo(full_name),
+            Message::PickWorkspace => self.pick_workspace(),
+            Message::LoadedWorkspaces(result, intent) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(workspaces) => {
+                        self.workspaces = Some(workspaces.clone());
+                        match intent {
+                            WorkspaceIntent::Open(query) => {
+                                match workspaces.into_iter().find(|workspace| {
+                                    workspace.id.0 == query
+                                        || workspace.name.eq_ignore_ascii_case(&query)
+                                }) {
+                                    Some(workspace) => self.open_workspace(workspace),
+                                    None => {
+                                        self.status = format!(
+                                            "{} is not in a workspace `{}`, opening the oldest board",
+                                            self.properties.repo.full_name.0, query
+                                        );
+                                        self.load_board();
+                                    }
+                                }
+                            }
+                            WorkspaceIntent::Pick => self.pick_workspace(),
+                            WorkspaceIntent::Choose => self.choose_workspace(workspaces),
+                        }
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not load workspaces: {}", error);
+                        if let WorkspaceIntent::Open(_) | WorkspaceIntent::Choose = intent {
+                            self.load_board();
+                        }
+                    }
+                }
+            }
+            Message::SetEstimate(issue_number, points) => self.set_estimate(issue_number, points),
+            Message::EstimateMarked(points) => self.estimate_marked(points),
+            Message::AskFindTitles => {
+                self.question = Some(Question::text(
+                    "Find titles matching (regex)",
+                    self.title_replace
+                        .as_ref()
+                        .map(|replace| replace.pattern.as_str().to_owned())
+                        .unwrap_or_default(),
+                    QuestionAction::FindTitles,
+                ))
+            }
+            Message::ConfirmRenames => self.confirm_renames(),
+            Message::AskChangelog => {
+                if let Some(pipeline) = self.board.selected_pipeline() {
+                    self.question = Some(Question::select(
+                        format!("Draft a changelog of {}", pipeline.pipeline.name),
+                        vec![
+                            "Copy it to the clipboard".into(),
+                            format!(
+                                "Write it to `{}`",
+                                self.properties.settings.changelog.path.display()
+                            ),
+                            "Create a Github release draft".into(),
+                        ],
+                        QuestionAction::Changelog(self.board.selected_pipeline),
+                    ));
+                }
+            }
+            Message::DraftChangelog(pipeline_index, output, loaded) => {
+                self.num_pending_tasks -= 1;
+                for (issue_number, result) in loaded {
+                    match result {
+                        Ok(issue) => {
+                            self.insert_issue(issue_number, FutureValue::Ready(issue));
+                        }
+                        Err(error) => log::error!("{:?}", error),
+                    }
+                }
+                self.write_changelog(pipeline_index, output);
+            }
+            Message::CreatedReleaseDraft(result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(release) => {
+                        self.status =
+                            format!("Drafted release {}: {}", release.tag_name, release.html_url);
+                        if let Err(error) = terminal::open_url(&release.html_url) {
+                            log::error!("{:?}", error);
+                        }
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!("Could not draft the release: {}", error);
+                    }
+                }
+            }
+            Message::RenamedBatch(results) => {
+                self.num_pending_tasks -= 1;
+                for (issue_number, result) in results {
+                    match result {
+                        Ok(issue) => {
+                            self.status = format!("Renamed #{}", issue_number.0);
+                            self.insert_issue(issue_number, FutureValue::Ready(issue));
+                        }
+                        Err(error) => {
+                            log::error!("{:?}", error);
+                            self.status =
+                                format!("Could not rename #{}: {}", issue_number.0, error);
+                        }
+                    }
+                }
+                self.rename_next_batch();
+            }
+            Message::EstimatedBatch(results) => {
+                self.num_pending_tasks -= 1;
+                for (issue_number, previous, result) in results {
+                    match result {
+                        Ok(()) => self.sync_labels(issue_number),
+                        Err(error) => {
+                            log::error!("{:?}", error);
+                            self.replace_estimate(issue_number, previous);
+                            if let Some(ref mut bulk) = self.bulk_estimate {
+                                bulk.failed.push(issue_number);
+                            }
+                        }
+                    }
+                    if let Some(ref mut bulk) = self.bulk_estimate {
+                        bulk.num_done += 1;
+                    }
+                }
+                self.estimate_next_batch();
+            }
+            Message::EstimatedIssue(issue_number, previous, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(()) => {
+                        // Read back what Zenhub stored, the card shows the
+                        // requested estimate until then
+                        self.spawn(
+                            self.properties
+                                .zenhub_client
+                                .clone()
+                                .get_issue_data(self.properties.repo.id, issue_number)
+                                .map(move |result| Message::LoadedIssueData(issue_number, result)),
+                        );
+                        self.sync_labels(issue_number);
+                    }
+                    Err(error) => {
+                        log::error!("{:?}", error);
+                        self.status = format!(
+                            "Could not change the estimate of #{}: {}",
+                            issue_number.0, error
+                        );
+                        self.replace_estimate(issue_number, previous);
+                    }
+                }
+            }
+            Message::LoadedIssueData(issue_number, result) => {
+                self.num_pending_tasks -= 1;
+                match result {
+                    Ok(issue_data) => {
+                        self.replace_estimate(issue_number, issue_data.estimate);
+                    }
+                    Err(error) => log::error!("{:?}", error),
+                }
+            }
+            Message::ResizeDetailPane(delta) => {
+                let width = (self.detail_pane_width() as isize + delta).max(0) as usize;
+                self.state.detail_pane_width = Some(self.clamp_detail_pane_width(width));
+                self.save_state();
+            }
+            Message::ResetDetailPane => {
+                self.state.detail_pane_width = None;
+                self.save_state();
+            }
+            Message::PickerInsertChar(character) => {
+                if let Some(picker) = self.picker.as_mut() {
+                    picker.insert_char(character);
+                }
+            }
+            Message::PickerDeleteChar => {
+                if let Some(picker) = self.picker.as_mut() {
+                    picker.delete_char();
+                }
+            }
+            Message::PickerNext => {
+                if let Some(picker) = self.picker.as_mut() {
+                    picker.select_next();
+                }
+            }
+            Message::PickerPrevious => {
+                if let Some(picker) = self.picker.as_mut() {
+                    picker.select_previous();
+                }
+            }
+            Message::PickerAccept => self.accept_picker(),
+            Message::PickerCancel => {
+                // Without a workspace chosen, fall back to the oldest board
+                if let Some(PickerAction::ChooseWorkspace(_)) =
+                    self.picker.take().map(|picker| picker.action)
+                {
+                    self.load_board();
+                }
+            }
+            Message::AskGoToIssue => {
+                self.question = Some(Question::text(
+                    "Go to issue #",
+                    "",
+                    QuestionAction::GoToIssue,
+                ))
+            }
+            Message::AskMoveIssue => self.ask_move_issue(),
+            Message::ConfirmSweep(action) => self.confirm_sweep(action),
+            Message::QuestionKey(key) => {
+                if let Some(mut question) = self.question.take() {
+                    match question.handle_key(key) {
+                        Outcome::Pending => {
+                            if question.action == QuestionAction::Search {
+                                self.search = question.typed().map(str::to_owned);
+                                self.jump_to_match(None);
+                            }
+                            self.question = Some(question);
+                        }
+                        Outcome::Cancelled if question.action == QuestionAction::Search => {
+                            self.search = None;
+                        }
+                        Outcome::Cancelled => {}
+                        Outcome::Answered(answer) => self.answer_question(question.action, answer),
+                    }
+                }
+            }
+            Message::AskSearch => {
+                self.question = Some(Question::text(
+                    "Search",
+                    self.search.clone().unwrap_or_default(),
+                    QuestionAction::Search,
+                ))
+            }
+            Message::JumpToMatch(direction) => self.jump_to_match(Some(direction)),
+            Message::AskFilter(field) => self.ask_filter(field),
+            Message::ClearFilters => {
+                self.board.filter = BoardFilter::default();
+                self.status = "Cleared the filters".into();
+                self.clamp_selection();
+            }
+            Message::ClearSearch => {
+                self.search = None;
+                self.status.clear();
+            }
+        }
+        self.load_nearby_issues();
+        self.follow_selection(false);
+        if self.splash {
+            self.splash = !self.startup_loaded();
+        }
+        self.needs_redraw = false;
+        self.last_redraw = Instant::now();
+        ShouldRender::Yes
+    }
+
+    fn tick(&self) -> Option<Self::Message> {
+        let logs_changed = match (self.overlay, self.properties.logs.as_ref()) {
+            (Some(overlay), Some(logs)) if overlay.kind == OverlayKind::Logs => {
+                logs.generation() != self.log_generation
+            }
+            _ => false,
+        };
+        if logs_changed {
+            Some(Message::LogsChanged)
+        } else if let Some(issue_number) = self.unloaded_detail_comments() {
+            Some(Message::LoadComments(issue_number))
+        } else if self
+            .remote_moves
+            .iter()
+            .any(|remote_move| remote_move.at.elapsed() >= MOVE_HIGHLIGHT_DURATION)
+        {
+            Some(Message::ExpireMoveHighlights)
+        } else if self
+            .snoozes()
+            .iter()
+            .any(|snooze| snooze.until <= Utc::now().timestamp())
+        {
+            Some(Message::ExpireSnoozes)
+        } else if !self.polling_board
+            && !self.board.pipelines.is_empty()
+            && self.last_board_poll.elapsed()
+                >= self.background_interval(self.board_poll_interval())
+        {
+            Some(Message::PollBoard)
+        } else if self.cache_dirty
+            && !self.board.pipelines.is_empty()
+            && self.last_cache_write.elapsed() >= CACHE_WRITE_INTERVAL
+        {
+            Some(Message::WriteCache)
+        } else if self.needs_redraw && self.last_redraw.elapsed() >= MIN_REDRAW_INTERVAL {
+            Some(Message::Redraw)
+        } else {
+            None
+        }
     }
 
     fn view(&self) -> Layout {
+        if self.frame.size.width < MIN_TERMINAL_WIDTH
+            || self.frame.size.height < MIN_TERMINAL_HEIGHT
+        {
+            return self.too_small_view();
+        }
+
         let separator = |pipeline_index| {
             iter::once(layout::fixed(
                 1,
@@ -291,46 +6720,259 @@ impl Component for App {
             ))
         };
 
-        layout::column([
-            layout::auto(layout::row_reverse_iter(
-                self.board
-                    .pipelines
-                    .iter()
-                    .enumerate()
-                    .rev()
-                    .filter(|(_, pipeline)| !pipeline.hidden)
-                    .flat_map(|(pipeline_index, pipeline)| {
-                        let focused = pipeline_index == self.board.selected_pipeline;
-                        separator(pipeline_index + 1).chain(iter::once(layout::auto(
-                            layout::component_with_key::<pipeline::Pipeline>(
+        let relationships = self.relationships();
+        let jump_codes = if self.show_jump_codes {
+            self.jump_codes()
+        } else {
+            HashMap::new()
+        };
+        let pull_requests =
+            connections::connected_pull_requests(&self.issues, &self.connected_pull_requests);
+        let releases = self.issue_releases();
+        let search_matches: HashSet<_> = self.search_matches().into_iter().collect();
+        let card_footer = self.properties.settings.card_footer.clone().map(Rc::new);
+        let sla_deadlines = sla::deadlines(&self.properties.settings.sla, &self.issues);
+        let blocked = self.blocked_issues();
+        let stale: HashSet<_> = sweep::stale_issues(
+            &self.board,
+            &self.issues,
+            &self.properties.settings.stale,
+            Utc::now(),
+        )
+        .into_iter()
+        .map(|stale_issue| stale_issue.issue.number)
+        .collect();
+        let folded_children = self.folded_epic_children().into_iter().fold(
+            HashMap::new(),
+            |mut folded_children, (_, epic)| {
+                *folded_children.entry(epic).or_insert(0) += 1;
+                folded_children
+            },
+        );
+        let in_view = self.board.pipelines_in_view(self.num_columns());
+        let board = layout::auto(layout::row_reverse_iter(
+            self.board
+                .pipelines
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(pipeline_index, _)| in_view.contains(pipeline_index))
+                .flat_map(|(pipeline_index, pipeline)| {
+                    let focused = pipeline_index == self.board.selected_pipeline;
+                    if pipeline.collapsed {
+                        let theme = if focused {
+                            &self.theme.pipeline_focused
+                        } else {
+                            &self.theme.pipeline_unfocused
+                        };
+                        return separator(pipeline_index + 1).chain(iter::once(layout::fixed(
+                            COLLAPSED_PIPELINE_WIDTH,
+                            layout::component_with_key::<Text>(
                                 1000 * pipeline_index,
-                                pipeline::Properties {
-                                    theme: if focused {
-                                        self.theme.pipeline_focused.clone()
-                                    } else {
-                                        self.theme.pipeline_unfocused.clone()
-                                    },
-                                    pipeline_view: pipeline.clone(),
-                                    issues: self.issues.clone(),
-                                    focused,
-                                    on_selected_change: self.link.callback(Message::SelectIssue),
-                                },
+                                TextProperties::new()
+                                    .content(collapsed_label(pipeline))
+                                    .style(theme.title)
+                                    .align(TextAlign::Centre),
                             ),
-                        )))
-                    })
-                    .skip(1),
+                        )));
+                    }
+                    let (issue_refs, tree) = self.arranged_issues(pipeline);
+                    separator(pipeline_index + 1).chain(iter::once(layout::auto(
+                        layout::component_with_key::<pipeline::Pipeline>(
+                            1000 * pipeline_index,
+                            pipeline::Properties {
+                                theme: if focused {
+                                    self.theme.pipeline_focused.clone()
+                                } else {
+                                    self.theme.pipeline_unfocused.clone()
+                                },
+                                pipeline_view: pipeline.clone(),
+                                issue_refs,
+                                tree,
+                                bot_fold: self.bot_fold(pipeline),
+                                card_height: self.properties.settings.card_height,
+                                density: self.density,
+                                max_title_lines: self.properties.settings.max_title_lines,
+                                wip_limit: self
+                                    .wip_limit(&pipeline.pipeline.name)
+                                    .map(|wip_limit| wip_limit.limit),
+                                more_left: in_view.first() == Some(&pipeline_index)
+                                    && self
+                                        .board
+                                        .adjacent_pipeline(pipeline_index, Direction::Previous)
+                                        .is_some(),
+                                more_right: in_view.last() == Some(&pipeline_index)
+                                    && self
+                                        .board
+                                        .adjacent_pipeline(pipeline_index, Direction::Next)
+                                        .is_some(),
+                                card_footer: card_footer.clone(),
+                                issues: self.issues.clone(),
+                                repo_id: self.properties.repo.id,
+                                foreign_issues: self.foreign_issues.clone(),
+                                foreign_repos: self.foreign_repos.clone(),
+                                relationships: relationships.clone(),
+                                pull_requests: pull_requests.clone(),
+                                pull_request_statuses: self.pull_request_statuses.clone(),
+                                releases: releases.clone(),
+                                sla_deadlines: sla_deadlines.clone(),
+                                blocked: blocked.clone(),
+                                stale: stale.clone(),
+                                folded_children: folded_children.clone(),
+                                marked: self.marked_issues.clone(),
+                                jump_codes: jump_codes.clone(),
+                                jump_page_size: if self.show_jump_codes {
+                                    Some(self.page_size())
+                                } else {
+                                    None
+                                },
+                                search_matches: search_matches.clone(),
+                                moved: self
+                                    .remote_moves
+                                    .iter()
+                                    .filter(|remote_move| remote_move.to_pipeline == pipeline_index)
+                                    .map(|remote_move| remote_move.issue_number)
+                                    .chain(self.resurfaced.iter().copied())
+                                    .collect(),
+                                highlighted: self.remote_moves.iter().any(|remote_move| {
+                                    remote_move.from_pipeline == pipeline_index
+                                        || remote_move.to_pipeline == pipeline_index
+                                }),
+                                focused: focused && self.picker.is_none(),
+                                on_selected_change: self.link.callback(Message::SelectIssue),
+                            },
+                        ),
+                    )))
+                })
+                .skip(1),
+        ));
+
+        let board = match self.overlay {
+            None if self.splash => layout::auto(layout::component_with_key::<Splash>(
+                8,
+                self.splash_properties(),
             )),
+            Some(overlay) => {
+                let (title, lines) = self.overlay_content(overlay.kind);
+                layout::auto(layout::component_with_key::<Overlay>(
+                    5,
+                    overlay::Properties {
+                        theme: self.theme.overlay.clone(),
+                        title: title.into(),
+                        lines: Rc::new(lines),
+                        selected: overlay.selected,
+                    },
+                ))
+            }
+            None => board,
+        };
+
+        let board = if self.show_detail && self.overlay.is_none() {
+            let selected_issue = self.selected_issue();
+            layout::auto(layout::row([
+                board,
+                layout::fixed(
+                    1,
+                    layout::component_with_key::<Text>(
+                        3,
+                        TextProperties::new().style(self.theme.divider),
+                    ),
+                ),
+                layout::fixed(
+                    self.detail_pane_width(),
+                    layout::component_with_key::<IssueDetail>(
+                        4,
+                        issue_detail::Properties {
+                            theme: self.theme.detail.clone(),
+                            issue_number: selected_issue.as_ref().map(|issue_ref| issue_ref.number),
+                            url: selected_issue
+                                .as_ref()
+                                .and_then(|issue_ref| self.issue_url(issue_ref)),
+                            issue: selected_issue
+                                .as_ref()
+                                .and_then(|issue_ref| self.issue(issue_ref).cloned())
+                                .unwrap_or(FutureValue::Pending),
+                            comments: match selected_issue {
+                                Some(ref issue_ref)
+                                    if issue_ref
+                                        .foreign_repo(self.properties.repo.id)
+                                        .is_some() =>
+                                {
+                                    FutureValue::Error(format!(
+                                        "they are only loaded for issues in {}",
+                                        self.properties.repo.full_name.0
+                                    ))
+                                }
+                                Some(ref issue_ref) => self
+                                    .comments
+                                    .get(&issue_ref.number)
+                                    .cloned()
+                                    .unwrap_or(FutureValue::Pending),
+                                None => FutureValue::Pending,
+                            },
+                        },
+                    ),
+                ),
+            ]))
+        } else {
+            board
+        };
+
+        let stats_header = if self.show_stats_header {
+            let stats = BoardStats::new(
+                &self.board,
+                &self.issues,
+                &self.pull_requests,
+                &self.properties.user,
+            );
+            Some(layout::fixed(
+                board_stats::HEADER_HEIGHT,
+                layout::component_with_key::<Text>(
+                    6,
+                    TextProperties::new()
+                        .content(stats.lines())
+                        .style(self.theme.stats_header),
+                ),
+            ))
+        } else {
+            None
+        };
+
+        let picker = self.picker.as_ref().map(|picker| {
             layout::fixed(
-                1,
-                layout::component_with_key::<prompt::Prompt>(
+                cmp::min(picker.num_matches() + 1, picker::MAX_HEIGHT),
+                layout::component_with_key::<PickerView>(
+                    2,
+                    picker.properties(self.theme.picker.clone()),
+                ),
+            )
+        });
+
+        let prompt = layout::fixed(
+            1,
+            match self.question.as_ref() {
+                Some(question) => layout::component_with_key::<QuestionView>(
+                    7,
+                    question.properties(self.theme.prompt.clone()),
+                ),
+                None => layout::component_with_key::<prompt::Prompt>(
                     1,
                     prompt::PromptProperties {
                         theme: self.theme.prompt.clone(),
                         pending: self.num_pending_tasks > 0,
+                        segments: self.prompt_segments(),
                     },
                 ),
-            ),
-        ])
+            },
+        );
+
+        layout::column_iter(
+            stats_header
+                .into_iter()
+                .chain(iter::once(board))
+                .chain(picker.into_iter())
+                .chain(iter::once(prompt)),
+        )
     }
 
     fn has_focus(&self) -> bool {
@@ -338,18 +6980,103 @@ impl Component for App {
     }
 
     fn input_binding(&self, pressed: &[Key]) -> BindingMatch<Self::Message> {
+        // A question in the prompt row takes every key until it's answered
+        if self.question.is_some() {
+            return BindingMatch {
+                transition: BindingTransition::Clear,
+                message: pressed.last().cloned().map(Message::QuestionKey),
+            };
+        }
+
+        if self.picker.is_some() {
+            return BindingMatch {
+                transition: BindingTransition::Clear,
+                message: match pressed {
+                    &[Key::Esc] | &[Key::Ctrl('g')] => Some(Message::PickerCancel),
+                    &[Key::Char('\n')] => Some(Message::PickerAccept),
+                    &[Key::Down] | &[Key::Ctrl('n')] => Some(Message::PickerNext),
+                    &[Key::Up] | &[Key::Ctrl('p')] => Some(Message::PickerPrevious),
+                    &[Key::Backspace] => Some(Message::PickerDeleteChar),
+                    &[Key::Char(character)] => Some(Message::PickerInsertChar(character)),
+                    _ => None,
+                },
+            };
+        }
+
+        if let Some(overlay) = self.overlay {
+            return BindingMatch {
+                transition: BindingTransition::Clear,
+                message: match pressed {
+                    &[Key::Esc] | &[Key::Ctrl('g')] => Some(Message::ToggleOverlay(overlay.kind)),
+                    &[Key::Down] | &[Key::Ctrl('n')] | &[Key::Char('j')] => {
+                        Some(Message::OverlayNext)
+                    }
+                    &[Key::Up] | &[Key::Ctrl('p')] | &[Key::Char('k')] => {
+                        Some(Message::OverlayPrevious)
+                    }
+                    &[Key::Char('\n')] => Some(Message::OverlayAccept),
+                    &[Key::Char(digit @ '1'..='5')] if overlay.kind == OverlayKind::Logs => {
+                        Some(Message::SetLogLevel(match digit {
+                            '1' => Level::ERROR,
+                            '2' => Level::WARN,
+                            '3' => Level::INFO,
+                            '4' => Level::DEBUG,
+                            _ => Level::TRACE,
+                        }))
+                    }
+                    &[Key::Char(key)] if overlay.kind == OverlayKind::Sweep => match key {
+                        'c' => Some(Message::ConfirmSweep(SweepAction::Nudge)),
+                        's' => Some(Message::ConfirmSweep(SweepAction::Label)),
+                        'I' => Some(Message::ConfirmSweep(SweepAction::Icebox)),
+                        'x' => Some(Message::ConfirmSweep(SweepAction::Close)),
+                        _ => None,
+                    },
+                    &[Key::Char('a')] if overlay.kind == OverlayKind::Rename => {
+                        Some(Message::ConfirmRenames)
+                    }
+                    &[Key::Char('A')] => Some(Message::ToggleOverlay(OverlayKind::Analytics)),
+                    &[Key::Char('W')] => Some(Message::ToggleOverlay(OverlayKind::Workload)),
+                    &[Key::Char('L')] => Some(Message::ToggleOverlay(OverlayKind::Labels)),
+                    &[Key::Char('z')] => Some(Message::ToggleOverlay(OverlayKind::Title)),
+                    _ => None,
+                },
+            };
+        }
+
+        if self.show_jump_codes {
+            let mut transition = BindingTransition::Clear;
+            let message = match pressed {
+                // Not `f` again, it's one of the code letters
+                &[Key::Esc] | &[Key::Ctrl('g')] => Some(Message::ToggleJumpCodes),
+                &[Key::Char(first)] if JUMP_CODE_ALPHABET.contains(&first) => {
+                    transition = BindingTransition::Continue;
+                    None
+                }
+                &[Key::Char(first), Key::Char(second)] => {
+                    Some(Message::JumpToCode(format!("{}{}", first, second)))
+                }
+                _ => None,
+            };
+            return BindingMatch {
+                transition,
+                message,
+            };
+        }
+
         let mut transition = BindingTransition::Clear;
         let message = match pressed {
             &[Key::Ctrl('f')] | &[Key::Right] | &[Key::Char('l')] => Some(Message::NextPipeline),
             &[Key::Ctrl('b')] | &[Key::Left] | &[Key::Char('h')] => Some(Message::PreviousPipeline),
+            &[Key::Ctrl('h')] => Some(Message::HidePipeline(self.board.selected_pipeline)),
+            &[Key::Char('x')] => Some(Message::CollapsePipeline(self.board.selected_pipeline)),
+            &[Key::Ctrl('x'), Key::Ctrl('h')] => Some(Message::ShowAllPipelines),
+            &[Key::Char('\n')] if self.is_bot_fold_selected() => {
+                Some(Message::ToggleBots(self.board.selected_pipeline))
+            }
             &[Key::Char('\n')] => {
                 if let Some(FutureValue::Ready(issue)) = self
-                    .board
-                    .selected_pipeline()
-                    .and_then(|pipeline| {
-                        self.issues
-                            .get(&pipeline.pipeline.issues[pipeline.selected_issue].number)
-                    })
+                    .selected_local_issue()
+                    .and_then(|issue_ref| self.issues.get(&issue_ref.number))
                     .cloned()
                 {
                     self.link.run_exclusive(move || {
@@ -365,16 +7092,179 @@ impl Component for App {
                 }
                 None
             }
-            &[Key::Ctrl('h')] => Some(Message::HidePipeline(self.board.selected_pipeline)),
-            &[Key::Ctrl('x'), Key::Ctrl('h')] => Some(Message::ShowAllPipelines),
-            &[Key::Ctrl('x'), Key::Ctrl('c')] => {
-                self.link.exit();
+            &[Key::Char('o')] => Some(Message::OpenProject),
+            &[Key::Char('c')] => Some(Message::WriteCommitTemplate),
+            &[Key::Char('C')] => Some(Message::PickIssueToConnect),
+            &[Key::Char('n')] if self.search.is_some() => {
+                Some(Message::JumpToMatch(Direction::Next))
+            }
+            &[Key::Char('p')] if self.search.is_some() => {
+                Some(Message::JumpToMatch(Direction::Previous))
+            }
+            &[Key::Char('/')] => Some(Message::AskSearch),
+            &[Key::PageDown] => Some(Message::PageIssues(Direction::Next)),
+            &[Key::PageUp] => Some(Message::PageIssues(Direction::Previous)),
+            &[Key::Char('n')] => Some(Message::NewIssue),
+            &[Key::Char('#')] => Some(Message::PickLabel),
+            &[Key::Char('R')] => Some(Message::PickRelease),
+            &[Key::Char('u')] => Some(Message::ToggleMyWork),
+            &[Key::Char('E')] => Some(Message::ToggleEpicsOnly),
+            &[Key::Char('N')] => Some(Message::ShowNotifications),
+            &[Key::Char(':')] => Some(Message::AskGoToIssue),
+            &[Key::Char('M')] => self.selected_local_issue().map(|_| Message::AskMoveIssue),
+            &[Key::Char('S')] => Some(Message::PickWebhook),
+            &[Key::Char('p')] => Some(Message::PreviewImages),
+            &[Key::Char('O')] => Some(Message::OpenInBrowser),
+            &[Key::Char('y')] => Some(Message::CopyPipelineMarkdown),
+            &[Key::Char('Y')] => Some(Message::AskChangelog),
+            &[Key::Char('t')] => match self
+                .selected_local_issue()
+                .and_then(|issue_ref| self.issues.get(&issue_ref.number))
+            {
+                Some(FutureValue::Ready(issue)) if issue.pull_request.is_some() => {
+                    Some(Message::LoadTeams(TeamAction::RequestReview(issue.number)))
+                }
+                _ => None,
+            },
+            &[Key::Char('P')] => self
+                .board
+                .selected_pipeline()
+                .filter(|pipeline| pipeline.pipeline.kind() == PipelineKind::Icebox)
+                .and_then(|_| self.selected_local_issue())
+                .map(|issue_ref| Message::PromoteIssue(issue_ref.number)),
+            &[Key::Char('T')] if self.board.filter.team.is_some() => Some(Message::ClearTeamFilter),
+            &[Key::Char('T')] => Some(Message::LoadTeams(TeamAction::Filter)),
+            &[Key::Char('m'), Key::Char(slot)] if slot.is_ascii_lowercase() => {
+                Some(Message::SetBookmark(slot))
+            }
+            &[Key::Char('\''), Key::Char(slot)] if slot.is_ascii_lowercase() => {
+                Some(Message::JumpToBookmark(slot))
+            }
+            &[Key::Char('m')] | &[Key::Char('\'')] => {
+                transition = BindingTransition::Continue;
+                None
+            }
+            &[Key::Char('<')] => Some(Message::MoveSelectedIssue(CardMove::Across(
+                Direction::Previous,
+            ))),
+            &[Key::Char('>')] => Some(Message::MoveSelectedIssue(CardMove::Across(
+                Direction::Next,
+            ))),
+            &[Key::Char('K')] => Some(Message::MoveSelectedIssue(CardMove::Within(
+                Direction::Previous,
+            ))),
+            &[Key::Char('J')] => Some(Message::MoveSelectedIssue(CardMove::Within(
+                Direction::Next,
+            ))),
+            &[Key::Char('b')] => Some(Message::ToggleBots(self.board.selected_pipeline)),
+            &[Key::Char('e'), Key::Char('e')] => self
+                .selected_local_issue()
+                .filter(|issue_ref| self.epics.contains_key(&issue_ref.number))
+                .map(|issue_ref| Message::ToggleEpic(issue_ref.number)),
+            // `e 0` clears the estimate, of all the marked issues if there are any
+            &[Key::Char('e'), Key::Char(digit)]
+                if digit.is_ascii_digit() && !self.marked_issues.is_empty() =>
+            {
+                let points = digit.to_digit(10).filter(|&points| points > 0);
+                Some(Message::EstimateMarked(points.map(f64::from)))
+            }
+            &[Key::Char('e'), Key::Char(digit)] if digit.is_ascii_digit() => {
+                self.selected_local_issue().map(|issue_ref| {
+                    let points = digit.to_digit(10).filter(|&points| points > 0);
+                    Message::SetEstimate(issue_ref.number, points.map(f64::from))
+                })
+            }
+            &[Key::Char('e'), Key::Char('f')] => Some(Message::ToggleFoldEpicChildren),
+            &[Key::Char('e')] => {
+                transition = BindingTransition::Continue;
+                None
+            }
+            &[Key::Char('g'), Key::Char('d')] => Some(Message::CycleDensity),
+            &[Key::Char('g'), Key::Char('f')] => Some(Message::ToggleFocusFollow),
+            &[Key::Char('g'), Key::Char('t')] => Some(Message::CycleKindFilter),
+            &[Key::Char('s'), Key::Char('u')] => {
+                Some(Message::SortPipeline(Some(IssueSort::Updated)))
+            }
+            &[Key::Char('s'), Key::Char('e')] => {
+                Some(Message::SortPipeline(Some(IssueSort::Estimate)))
+            }
+            &[Key::Char('s'), Key::Char('n')] => {
+                Some(Message::SortPipeline(Some(IssueSort::Number)))
+            }
+            &[Key::Char('s'), Key::Char('t')] => {
+                Some(Message::SortPipeline(Some(IssueSort::Title)))
+            }
+            &[Key::Char('s'), Key::Char('s')] => Some(Message::SortPipeline(None)),
+            &[Key::Char('s')] => {
+                transition = BindingTransition::Continue;
                 None
             }
+            &[Key::Char('g'), Key::Char('r')] if !self.refreshing => {
+                Some(Message::Refresh(RefreshTrigger::Key))
+            }
+            &[Key::Char('g')] => {
+                transition = BindingTransition::Continue;
+                None
+            }
+            &[Key::Char('+')] => self.step_selected_estimate(true),
+            &[Key::Char('-')] => self.step_selected_estimate(false),
+            &[Key::Char('v')] => self
+                .selected_local_issue()
+                .map(|issue_ref| Message::ToggleMark(issue_ref.number)),
+            &[Key::Esc] if self.search.is_some() => Some(Message::ClearSearch),
+            &[Key::Esc] if !self.marked_issues.is_empty() => Some(Message::ClearMarks),
+            &[Key::Char('i')] => Some(Message::ToggleDetailPane),
+            &[Key::Char('H')] => Some(Message::ToggleStatsHeader),
+            &[Key::Char('f'), Key::Char('f')] => Some(Message::ToggleJumpCodes),
+            &[Key::Char('f'), Key::Char('l')] => Some(Message::AskFilter(FilterField::Label)),
+            &[Key::Char('f'), Key::Char('a')] => Some(Message::AskFilter(FilterField::Assignee)),
+            &[Key::Char('f'), Key::Char('s')] => Some(Message::AskFilter(FilterField::State)),
+            &[Key::Char('f'), Key::Char('c')] => Some(Message::ClearFilters),
+            &[Key::Char('f')] => {
+                transition = BindingTransition::Continue;
+                None
+            }
+            &[Key::Char('A')] => Some(Message::ToggleOverlay(OverlayKind::Analytics)),
+            &[Key::Char('W')] => Some(Message::ToggleOverlay(OverlayKind::Workload)),
+            &[Key::Char('L')] => Some(Message::ToggleOverlay(OverlayKind::Labels)),
+            &[Key::Char('z')] => Some(Message::ToggleOverlay(OverlayKind::Title)),
+            &[Key::Char('Z')] => Some(Message::AskSnooze),
+            &[Key::Char('D')] => Some(Message::ToggleOverlay(OverlayKind::Dependencies)),
+            &[Key::Char('U')] => Some(Message::ToggleOverlay(OverlayKind::Changes)),
+            &[Key::Ctrl('w'), Key::Char('>')] if self.show_detail => {
+                Some(Message::ResizeDetailPane(DETAIL_PANE_RESIZE_STEP))
+            }
+            &[Key::Ctrl('w'), Key::Char('<')] if self.show_detail => {
+                Some(Message::ResizeDetailPane(-DETAIL_PANE_RESIZE_STEP))
+            }
+            &[Key::Ctrl('w'), Key::Char('=')] => Some(Message::ResetDetailPane),
+            &[Key::Ctrl('w')] => {
+                transition = BindingTransition::Continue;
+                None
+            }
+            &[Key::Ctrl('x'), Key::Char('l')] => Some(Message::ShowLogs),
+            &[Key::Ctrl('x'), Key::Char('n')] => Some(Message::AskNewIssuePosition),
+            &[Key::Ctrl('x'), Key::Char('z')] => Some(Message::WakeAllSnoozed),
+            &[Key::Ctrl('x'), Key::Char('s')] => Some(Message::ShowSweep),
+            &[Key::Ctrl('x'), Key::Char('%')] => Some(Message::AskFindTitles),
+            &[Key::Ctrl('x'), Key::Char('c')] => Some(Message::ShowAgenda),
+            &[Key::Ctrl('x'), Key::Char('e')] => Some(Message::CreateEpicFromMarked),
+            &[Key::Ctrl('x'), Key::Char('r')] => Some(Message::PickRepo),
+            &[Key::Ctrl('x'), Key::Char('t')] => Some(Message::PickScheme),
+            &[Key::Ctrl('x'), Key::Char('w')] => Some(Message::PickWorkspace),
+            &[Key::Ctrl('x'), Key::Ctrl('r')] => self.previous_repo().map(Message::SwitchRepo),
+            &[Key::Char('q')] if self.properties.settings.quit_with_q != QuitKey::Off => {
+                Some(Message::PressedQuit)
+            }
+            &[Key::Ctrl('x'), Key::Ctrl('c')] => Some(Message::Exit),
             &[Key::Ctrl('x')] => {
                 transition = BindingTransition::Continue;
                 None
             }
+            // Triage keys come from the settings, built in bindings win
+            &[Key::Char(key)] if self.properties.settings.triage_action(key).is_some() => {
+                Some(Message::TriageSelectedIssue(key))
+            }
             _ => None,
         };
         BindingMatch {
@@ -384,6 +7274,53 @@ impl Component for App {
     }
 }
 
+/// The name of a collapsed pipeline written downwards, a letter per row,
+/// followed by its number of issues.
+fn collapsed_label(pipeline: &PipelineView) -> String {
+    let mut label: String = pipeline
+        .pipeline
+        .name
+        .chars()
+        .flat_map(|character| iter::once(character).chain(iter::once('\n')))
+        .collect();
+    label.push_str(&format!("\n{}", pipeline.pipeline.issues.len()));
+    label
+}
+
+const MIN_TERMINAL_WIDTH: usize = 80;
+const COLLAPSED_PIPELINE_WIDTH: usize = 4;
+const MIN_TERMINAL_HEIGHT: usize = 24;
+const MIN_REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+/// The choices of `f s`, in order.
+const FILTER_STATES: [Option<IssueState>; 3] =
+    [Some(IssueState::Open), Some(IssueState::Closed), None];
+/// Issues are loaded from the top of each pipeline when the board loads.
+const NUM_PRELOADED_ISSUES: usize = 7;
+/// How many estimates of marked issues are changed at once.
+const ESTIMATE_BATCH_SIZE: usize = 5;
+/// How many titles are changed at once by a find and replace.
+const RENAME_BATCH_SIZE: usize = 5;
+/// Fewer Github requests left than this and pinned repos aren't preloaded.
+const MIN_RATE_LIMIT_TO_PRELOAD: u64 = 1000;
+/// Where the full title shown with `z` wraps.
+const TITLE_OVERLAY_WIDTH: usize = 72;
+/// Home row keys first, so the most common codes are the quickest to type.
+const JUMP_CODE_ALPHABET: [char; 26] = [
+    'a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l', 'q', 'w', 'e', 'r', 't', 'y', 'u', 'i', 'o', 'p',
+    'z', 'x', 'c', 'v', 'b', 'n', 'm',
+];
+const NEW_ISSUE_HINT: &str =
+    "<!-- The first line is the title, the rest is the body. Leave it empty to cancel. -->";
+const BOARD_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const CACHE_WRITE_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_BOARD_CHANGES: usize = 200;
+const QUIT_CONFIRM_INTERVAL: Duration = Duration::from_secs(2);
+const MOVE_HIGHLIGHT_DURATION: Duration = Duration::from_secs(2);
+const DEFAULT_DETAIL_PANE_WIDTH: usize = 60;
+const MIN_DETAIL_PANE_WIDTH: usize = 20;
+const MIN_BOARD_WIDTH: usize = 20;
+const DETAIL_PANE_RESIZE_STEP: isize = 4;
+
 /// Represents a base16 theme.
 ///
 /// Colours base00 to base07 are typically variations of a shade and run from
@@ -393,6 +7330,7 @@ impl Component for App {
 /// In order to create a dark theme, colours base00 to base07 should span from
 /// dark to light. For a light theme, these colours should span from light to
 /// dark.
+#[derive(Clone, Copy)]
 pub struct Base16Theme {
     pub base00: Colour, // Default Background
     pub base01: Colour, // Lighter Background (Used for status bars)
@@ -412,6 +7350,106 @@ pub struct Base16Theme {
     pub base0f: Colour, // Deprecated, Opening/Closing Embedded Language Tags, e.g. <?php ?>
 }
 
+impl Base16Theme {
+    /// A colour of the scheme by name, e.g. `base0d`, or one given as a hex
+    /// string.
+    pub fn colour(&self, value: &str) -> Option<Colour> {
+        match value.to_lowercase().as_str() {
+            "base00" => Some(self.base00),
+            "base01" => Some(self.base01),
+            "base02" => Some(self.base02),
+            "base03" => Some(self.base03),
+            "base04" => Some(self.base04),
+            "base05" => Some(self.base05),
+            "base06" => Some(self.base06),
+            "base07" => Some(self.base07),
+            "base08" => Some(self.base08),
+            "base09" => Some(self.base09),
+            "base0a" => Some(self.base0a),
+            "base0b" => Some(self.base0b),
+            "base0c" => Some(self.base0c),
+            "base0d" => Some(self.base0d),
+            "base0e" => Some(self.base0e),
+            "base0f" => Some(self.base0f),
+            _ => parse_hex_colour(value),
+        }
+    }
+
+    /// Replaces colours with the ones given as hex strings by name, e.g.
+    /// `base0d = "#00bcd4"`. Invalid colours are logged and skipped.
+    pub fn with_overrides(mut self, colours: &BTreeMap<String, String>) -> Self {
+        for (name, hex) in colours.iter() {
+            let colour = match parse_hex_colour(hex) {
+                Some(colour) => colour,
+                None => {
+                    log::warn!("Invalid colour `{}` for `{}` in the theme", hex, name);
+                    continue;
+                }
+            };
+            match name.to_lowercase().as_str() {
+                "base00" => self.base00 = colour,
+                "base01" => self.base01 = colour,
+                "base02" => self.base02 = colour,
+                "base03" => self.base03 = colour,
+                "base04" => self.base04 = colour,
+                "base05" => self.base05 = colour,
+                "base06" => self.base06 = colour,
+                "base07" => self.base07 = colour,
+                "base08" => self.base08 = colour,
+                "base09" => self.base09 = colour,
+                "base0a" => self.base0a = colour,
+                "base0b" => self.base0b = colour,
+                "base0c" => self.base0c = colour,
+                "base0d" => self.base0d = colour,
+                "base0e" => self.base0e = colour,
+                "base0f" => self.base0f = colour,
+                _ => log::warn!("Unknown theme colour `{}`", name),
+            }
+        }
+        self
+    }
+}
+
+/// The style of a status row segment: the scheme's colours for its kind,
+/// unless the settings choose others.
+fn status_segment_style(theme: &Base16Theme, segment: &StatusSegment) -> Style {
+    let (background, foreground, bold) = match segment.segment {
+        StatusSegmentKind::Sync => (theme.base0e, theme.base00, true),
+        StatusSegmentKind::Message => (theme.base00, theme.base04, true),
+        StatusSegmentKind::Mode => (theme.base0d, theme.base00, true),
+        StatusSegmentKind::Repo => (theme.base02, theme.base05, false),
+        StatusSegmentKind::Filter => (theme.base0a, theme.base00, true),
+        StatusSegmentKind::RateLimit => (theme.base02, theme.base04, false),
+    };
+    let colour = |value: &Option<String>, default: Colour| match value {
+        Some(value) => theme.colour(value).unwrap_or_else(|| {
+            log::warn!("Invalid colour `{}` for a status segment", value);
+            default
+        }),
+        None => default,
+    };
+    let background = colour(&segment.bg, background);
+    let foreground = colour(&segment.fg, foreground);
+    if segment.bold.unwrap_or(bold) {
+        Style::bold(background, foreground)
+    } else {
+        Style::normal(background, foreground)
+    }
+}
+
+fn parse_hex_colour(hex: &str) -> Option<Colour> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let colour = u32::from_str_radix(hex, 16).ok()?;
+    Some(Colour::rgb(
+        (colour >> 16) as u8,
+        (colour >> 8) as u8,
+        colour as u8,
+    ))
+}
+
 pub const ICY: Base16Theme = Base16Theme {
     base00: Colour::rgb(2, 16, 18),
     base01: Colour::rgb(3, 22, 25),
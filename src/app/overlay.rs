@@ -0,0 +1,140 @@
+use std::{cmp, rc::Rc};
+use unicode_width::UnicodeWidthStr;
+use zi::{Canvas, Component, ComponentLink, Layout, Rect, ShouldRender, Style};
+
+use super::Base16Theme;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub title: Style,
+    pub heading: Style,
+    pub text: Style,
+    pub dim: Style,
+    pub bar: Style,
+    pub selected: Style,
+}
+
+impl From<&Base16Theme> for Theme {
+    fn from(theme: &Base16Theme) -> Self {
+        Self {
+            title: Style::bold(theme.base0d, theme.base00),
+            heading: Style::bold(theme.base00, theme.base0d),
+            text: Style::normal(theme.base00, theme.base05),
+            dim: Style::normal(theme.base00, theme.base03),
+            bar: Style::normal(theme.base00, theme.base0b),
+            selected: Style::bold(theme.base02, theme.base08),
+        }
+    }
+}
+
+/// A line in an overlay, made of differently styled segments.
+pub type Line = Vec<(String, Style)>;
+
+#[derive(Clone, PartialEq)]
+pub struct Properties {
+    pub theme: Rc<Theme>,
+    pub title: String,
+    pub lines: Rc<Vec<Line>>,
+    pub selected: usize,
+}
+
+/// A full screen panel listing lines of text, used for reports and lists
+/// which are displayed instead of the board.
+pub struct Overlay {
+    properties: Properties,
+    frame: Rect,
+}
+
+impl Component for Overlay {
+    type Message = ();
+    type Properties = Properties;
+
+    fn create(properties: Self::Properties, frame: Rect, _link: ComponentLink<Self>) -> Self {
+        Self { properties, frame }
+    }
+
+    fn change(&mut self, properties: Self::Properties) -> ShouldRender {
+        if self.properties != properties {
+            self.properties = properties;
+            ShouldRender::Yes
+        } else {
+            ShouldRender::No
+        }
+    }
+
+    fn resize(&mut self, frame: Rect) -> ShouldRender {
+        self.frame = frame;
+        ShouldRender::Yes
+    }
+
+    fn view(&self) -> Layout {
+        let Self {
+            properties:
+                Properties {
+                    ref theme,
+                    ref title,
+                    ref lines,
+                    selected,
+                },
+            frame,
+        } = *self;
+
+        let mut canvas = Canvas::new(frame.size);
+        canvas.clear(theme.text);
+        canvas.draw_str(0, 0, theme.title, &" ".repeat(frame.size.width));
+        canvas.draw_str(0, 0, theme.title, &format!(" {}", title));
+
+        let num_rows = frame.size.height.saturating_sub(2);
+        let offset = (selected + 1).saturating_sub(num_rows);
+        for (row, (index, line)) in lines
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(num_rows)
+            .enumerate()
+        {
+            let mut x = 1;
+            if index == selected {
+                canvas.draw_str(0, row + 1, theme.selected, &" ".repeat(frame.size.width));
+            }
+            for (text, style) in line.iter() {
+                let style = if index == selected {
+                    theme.selected
+                } else {
+                    *style
+                };
+                canvas.draw_str(x, row + 1, style, text);
+                x += UnicodeWidthStr::width(text.as_str());
+            }
+        }
+
+        canvas.draw_str(
+            0,
+            frame.size.height.saturating_sub(1),
+            theme.dim,
+            &format!(
+                " {}/{} · ↑↓ scroll · Esc close",
+                cmp::min(selected + 1, lines.len()),
+                lines.len()
+            ),
+        );
+
+        canvas.into()
+    }
+}
+
+/// Renders `value` as a horizontal bar using eighth block characters, scaled
+/// such that `max` fills `width` columns.
+pub fn bar(value: f64, max: f64, width: usize) -> String {
+    if max <= 0.0 || value <= 0.0 {
+        return String::new();
+    }
+    let eighths = ((value / max).min(1.0) * (width * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 > 0 {
+        bar.push(PARTIAL_BLOCKS[eighths % 8 - 1]);
+    }
+    bar
+}
+
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
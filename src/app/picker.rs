@@ -0,0 +1,228 @@
+use std::{cmp, rc::Rc};
+use unicode_width::UnicodeWidthStr;
+use zi::{Canvas, Component, ComponentLink, Layout, Rect, ShouldRender, Style};
+
+use super::Base16Theme;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub title: Style,
+    pub query: Style,
+    pub item: Style,
+    pub selected: Style,
+}
+
+impl From<&Base16Theme> for Theme {
+    fn from(theme: &Base16Theme) -> Self {
+        Self {
+            title: Style::bold(theme.base01, theme.base0d),
+            query: Style::normal(theme.base01, theme.base09),
+            item: Style::normal(theme.base01, theme.base05),
+            selected: Style::bold(theme.base02, theme.base08),
+        }
+    }
+}
+
+/// The state of an interactive picker: a list of items narrowed down by a
+/// fuzzy query typed by the user. The `action` records what to do with the
+/// item once one is picked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Picker<ActionT> {
+    pub title: String,
+    pub action: ActionT,
+    items: Vec<String>,
+    query: String,
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl<ActionT> Picker<ActionT> {
+    pub fn new(title: impl Into<String>, action: ActionT, items: Vec<String>) -> Self {
+        Self {
+            title: title.into(),
+            action,
+            matches: (0..items.len()).collect(),
+            items,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn insert_char(&mut self, character: char) {
+        self.query.push(character);
+        self.update_matches();
+    }
+
+    pub fn delete_char(&mut self) {
+        self.query.pop();
+        self.update_matches();
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = cmp::min(self.selected + 1, self.matches.len().saturating_sub(1));
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// The index in the original list of items of the selected match.
+    pub fn selected_item(&self) -> Option<usize> {
+        self.matches.get(self.selected).copied()
+    }
+
+    pub fn num_matches(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn properties(&self, theme: Rc<Theme>) -> Properties {
+        Properties {
+            theme,
+            title: self.title.clone(),
+            query: self.query.clone(),
+            items: self
+                .matches
+                .iter()
+                .map(|&index| self.items[index].clone())
+                .collect(),
+            selected: self.selected,
+        }
+    }
+
+    fn update_matches(&mut self) {
+        let query = &self.query;
+        let mut scored: Vec<_> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| fuzzy_score(query, item).map(|score| (score, index)))
+            .collect();
+        scored.sort_by_key(|&(score, index)| (cmp::Reverse(score), index));
+        self.matches = scored.into_iter().map(|(_, index)| index).collect();
+        self.selected = 0;
+    }
+}
+
+/// Scores how well `query` matches `candidate`. All characters in the query
+/// must appear in order in the candidate (case insensitive); consecutive
+/// matches and matches at the start of words score higher.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous_matched = false;
+    let mut previous_char: Option<char> = None;
+    for candidate_char in candidate.chars() {
+        let query_char = match query_chars.peek() {
+            Some(&query_char) => query_char,
+            None => break,
+        };
+        if candidate_char
+            .to_lowercase()
+            .eq(std::iter::once(query_char))
+        {
+            query_chars.next();
+            score += 1;
+            if previous_matched {
+                score += 5;
+            }
+            if previous_char.map_or(true, |character| !character.is_alphanumeric()) {
+                score += 3;
+            }
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous_char = Some(candidate_char);
+    }
+
+    if query_chars.peek().is_some() {
+        None
+    } else {
+        Some(score * 100 - candidate.len() as i64)
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Properties {
+    pub theme: Rc<Theme>,
+    pub title: String,
+    pub query: String,
+    pub items: Vec<String>,
+    pub selected: usize,
+}
+
+pub struct PickerView {
+    properties: Properties,
+    frame: Rect,
+}
+
+impl Component for PickerView {
+    type Message = ();
+    type Properties = Properties;
+
+    fn create(properties: Self::Properties, frame: Rect, _link: ComponentLink<Self>) -> Self {
+        Self { properties, frame }
+    }
+
+    fn change(&mut self, properties: Self::Properties) -> ShouldRender {
+        if self.properties != properties {
+            self.properties = properties;
+            ShouldRender::Yes
+        } else {
+            ShouldRender::No
+        }
+    }
+
+    fn resize(&mut self, frame: Rect) -> ShouldRender {
+        self.frame = frame;
+        ShouldRender::Yes
+    }
+
+    fn view(&self) -> Layout {
+        let Self {
+            properties:
+                Properties {
+                    ref theme,
+                    ref title,
+                    ref query,
+                    ref items,
+                    selected,
+                },
+            frame,
+        } = *self;
+
+        let mut canvas = Canvas::new(frame.size);
+        canvas.clear(theme.item);
+
+        let prompt = format!(" {}: ", title);
+        canvas.draw_str(0, 0, theme.title, &prompt);
+        canvas.draw_str(
+            UnicodeWidthStr::width(prompt.as_str()),
+            0,
+            theme.query,
+            query,
+        );
+
+        let num_rows = frame.size.height.saturating_sub(1);
+        let offset = (selected + 1).saturating_sub(num_rows);
+        for (row, (index, item)) in items
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(num_rows)
+            .enumerate()
+        {
+            let style = if index == selected {
+                canvas.draw_str(0, row + 1, theme.selected, &" ".repeat(frame.size.width));
+                theme.selected
+            } else {
+                theme.item
+            };
+            canvas.draw_str(0, row + 1, style, &format!("  {}", item));
+        }
+
+        canvas.into()
+    }
+}
+
+pub const MAX_HEIGHT: usize = 12;
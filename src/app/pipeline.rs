@@ -1,23 +1,34 @@
-use im::hashmap::HashMap;
-use std::rc::Rc;
+use chrono::{DateTime, Utc};
+use im::{hashmap::HashMap, hashset::HashSet, Vector};
+use std::{cmp, rc::Rc};
 use zi::{
     components::{
+        border::{Border, BorderProperties},
         select::{Select, SelectProperties},
-        text::{Text, TextAlign, TextProperties},
+        text::{Text, TextAlign, TextProperties, TextWrap},
     },
     layout, Callback, Component, ComponentLink, Layout, Rect, ShouldRender, Style,
 };
 
 use super::{
-    issue_card::{self, IssueCard},
+    epic_tree::TreeNode,
+    humanize::{self, Noun},
+    issue_card::{self, IssueCard, Relationship},
     Base16Theme, FutureValue, PipelineView,
 };
-use crate::github::{Issue, IssueNumber};
+use crate::{
+    github::{Issue, IssueNumber, PullRequestStatus, RepoFullName, RepoId},
+    settings::Density,
+    zenhub::IssueRef,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Theme {
     pub title: Style,
     pub subtitle: Style,
+    /// The subtitle of a pipeline over its WIP limit.
+    pub over_limit: Style,
+    pub highlighted: Style,
     pub issue: Rc<issue_card::Theme>,
 }
 
@@ -26,16 +37,74 @@ impl From<&Base16Theme> for Theme {
         Self {
             title: Style::bold(theme.base0f, theme.base0d),
             subtitle: Style::normal(theme.base0f, theme.base04),
+            over_limit: Style::bold(theme.base0f, theme.base08),
+            highlighted: Style::bold(theme.base0b, theme.base00),
             issue: Rc::new(theme.into()),
         }
     }
 }
 
+/// Issues opened by bots which are folded into a single card.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BotFold {
+    pub num_issues: usize,
+    pub accounts: Vec<String>,
+}
+
 #[derive(Clone, PartialEq)]
 pub struct Properties {
     pub theme: Rc<Theme>,
     pub pipeline_view: PipelineView,
+    pub issue_refs: Vector<IssueRef>,
+    pub tree: HashMap<IssueNumber, TreeNode>,
+    pub bot_fold: Option<BotFold>,
+    pub card_height: usize,
+    pub density: Density,
+    /// The most lines a card's title takes, `0` for no limit.
+    pub max_title_lines: usize,
+    /// The most issues the pipeline should hold, from the settings.
+    pub wip_limit: Option<usize>,
+    /// Whether pipelines were scrolled out of view on either side of this
+    /// one, marked next to its name.
+    pub more_left: bool,
+    pub more_right: bool,
+    /// The template of the cards' last line, from the settings.
+    pub card_footer: Option<Rc<String>>,
     pub issues: HashMap<IssueNumber, FutureValue<Issue>>,
+    /// The board's repo, the issues of other repos are looked up in
+    /// `foreign_issues` instead.
+    pub repo_id: RepoId,
+    pub foreign_issues: HashMap<(RepoId, IssueNumber), FutureValue<Issue>>,
+    pub foreign_repos: HashMap<RepoId, FutureValue<RepoFullName>>,
+    pub relationships: HashMap<IssueNumber, Relationship>,
+    pub pull_requests: HashMap<IssueNumber, Vector<IssueNumber>>,
+    /// The review and check status of the pull requests on the board.
+    pub pull_request_statuses: HashMap<IssueNumber, PullRequestStatus>,
+    /// The titles of the open releases each issue is part of.
+    pub releases: HashMap<IssueNumber, Vector<String>>,
+    /// When the issues with an SLA label are due.
+    pub sla_deadlines: HashMap<IssueNumber, DateTime<Utc>>,
+    /// The issues waiting on another issue.
+    pub blocked: HashSet<IssueNumber>,
+    /// The issues the stale sweep would find.
+    pub stale: HashSet<IssueNumber>,
+    /// How many children of each epic are folded into its card, from other
+    /// pipelines.
+    pub folded_children: HashMap<IssueNumber, usize>,
+    /// Issues a teammate just moved into this pipeline.
+    pub moved: HashSet<IssueNumber>,
+    /// Issues in the multi-selection.
+    pub marked: HashSet<IssueNumber>,
+    /// Issues matching the search typed in the prompt row.
+    pub search_matches: HashSet<IssueNumber>,
+    /// The codes to type to jump to cards, when they are shown.
+    pub jump_codes: HashMap<IssueNumber, String>,
+    /// While jump codes are shown, the page of this many cards holding the
+    /// selected one is shown rather than wherever the pipeline was scrolled
+    /// to, as those are the cards given codes.
+    pub jump_page_size: Option<usize>,
+    /// Whether an issue was just moved into or out of this pipeline.
+    pub highlighted: bool,
     pub focused: bool,
     pub on_selected_change: Callback<usize>,
 }
@@ -43,6 +112,41 @@ pub struct Properties {
 pub struct Pipeline {
     properties: Properties,
     link: ComponentLink<Self>,
+    frame: Rect,
+}
+
+impl Pipeline {
+    fn card_height(&self) -> usize {
+        card_height(
+            self.frame.size.height,
+            self.properties.card_height,
+            self.properties.density,
+        )
+    }
+}
+
+/// Cards shrink when the terminal is short, so that at least one full card
+/// fits below the pipeline's header. Minimal cards are always a single line
+/// and detailed ones grow to fit the start of the description.
+fn card_height(pipeline_height: usize, preferred: usize, density: Density) -> usize {
+    let (preferred, min) = match density {
+        Density::Minimal => (MINIMAL_CARD_HEIGHT, MINIMAL_CARD_HEIGHT),
+        Density::Normal => (preferred, MIN_CARD_HEIGHT),
+        Density::Detailed => (preferred + issue_card::BODY_EXCERPT_HEIGHT, MIN_CARD_HEIGHT),
+    };
+    cmp::max(
+        cmp::min(preferred, pipeline_height.saturating_sub(HEADER_HEIGHT)),
+        min,
+    )
+}
+
+/// How many cards fit in a pipeline at once, i.e. how far PgUp and PgDn move.
+pub fn page_size(pipeline_height: usize, preferred_card_height: usize, density: Density) -> usize {
+    cmp::max(
+        pipeline_height.saturating_sub(HEADER_HEIGHT)
+            / card_height(pipeline_height, preferred_card_height, density),
+        1,
+    )
 }
 
 pub enum Message {}
@@ -51,8 +155,12 @@ impl Component for Pipeline {
     type Message = Message;
     type Properties = Properties;
 
-    fn create(properties: Self::Properties, _frame: Rect, link: ComponentLink<Self>) -> Self {
-        Self { properties, link }
+    fn create(properties: Self::Properties, frame: Rect, link: ComponentLink<Self>) -> Self {
+        Self {
+            properties,
+            link,
+            frame,
+        }
     }
 
     fn change(&mut self, properties: Self::Properties) -> ShouldRender {
@@ -64,6 +172,11 @@ impl Component for Pipeline {
         }
     }
 
+    fn resize(&mut self, frame: Rect) -> ShouldRender {
+        self.frame = frame;
+        ShouldRender::Yes
+    }
+
     fn update(&mut self, _message: Self::Message) -> ShouldRender {
         ShouldRender::Yes
     }
@@ -73,32 +186,110 @@ impl Component for Pipeline {
             properties:
                 Properties {
                     ref pipeline_view,
+                    ref issue_refs,
+                    ref tree,
+                    ref bot_fold,
                     ref theme,
+                    ref card_footer,
                     ref issues,
+                    ref foreign_issues,
+                    ref foreign_repos,
+                    ref relationships,
+                    ref pull_requests,
+                    ref pull_request_statuses,
+                    ref releases,
+                    ref sla_deadlines,
+                    ref blocked,
+                    ref stale,
+                    ref folded_children,
+                    ref moved,
+                    ref marked,
+                    ref search_matches,
+                    ref jump_codes,
                     ref on_selected_change,
+                    repo_id,
+                    jump_page_size,
+                    density,
+                    max_title_lines,
+                    wip_limit,
+                    more_left,
+                    more_right,
+                    highlighted,
                     focused,
                     ..
                 },
             ..
         } = *self;
 
-        let pipeline_issues = pipeline_view.pipeline.issues.clone();
+        let pipeline_issues = issue_refs.clone();
         let issues = issues.clone();
+        let foreign_issues = foreign_issues.clone();
+        let foreign_repos = foreign_repos.clone();
+        let relationships = relationships.clone();
+        let pull_requests = pull_requests.clone();
+        let pull_request_statuses = pull_request_statuses.clone();
+        let releases = releases.clone();
+        let sla_deadlines = sla_deadlines.clone();
+        let blocked = blocked.clone();
+        let stale = stale.clone();
+        let folded_children = folded_children.clone();
+        let moved = moved.clone();
+        let marked = marked.clone();
+        let search_matches = search_matches.clone();
+        let jump_codes = jump_codes.clone();
+        let tree = tree.clone();
+        let bot_fold = bot_fold.clone();
         let theme = theme.clone();
+        let card_footer = card_footer.clone();
         let selected_issue = pipeline_view.selected_issue;
-        let subtitle = if pipeline_issues.is_empty() {
+        let card_height = self.card_height();
+        let num_total = pipeline_view.pipeline.issues.len();
+        let mut subtitle = if pipeline_issues.is_empty() && num_total == 0 {
             "(empty)".into()
+        } else if pipeline_issues.len() < num_total {
+            format!(
+                "({})",
+                humanize::count_of(pipeline_issues.len(), num_total, Noun::Issue)
+            )
         } else {
-            format!("({} issues)", pipeline_issues.len())
+            format!("({})", humanize::count(pipeline_issues.len(), Noun::Issue))
         };
+        if let Some(sort) = pipeline_view.sort {
+            subtitle.push_str(&format!(" by {}", sort.name()));
+        }
+        let over_limit = wip_limit.map_or(false, |wip_limit| num_total > wip_limit);
+        if let Some(wip_limit) = wip_limit {
+            subtitle.push_str(&format!(" {}/{} WIP", num_total, wip_limit));
+        }
+        let num_cards = pipeline_issues.len() + bot_fold.as_ref().map_or(0, |_| 1);
+        let first_card = jump_page_size.map_or(0, |page_size| {
+            cmp::min(selected_issue, num_cards.saturating_sub(1)) / page_size * page_size
+        });
+        let page_size = page_size(self.frame.size.height, self.properties.card_height, density);
+        if num_cards > page_size {
+            subtitle.push_str(&format!(
+                " page {}/{}",
+                selected_issue / page_size + 1,
+                (num_cards + page_size - 1) / page_size
+            ));
+        }
         layout::column([
             layout::fixed(
                 1,
                 layout::component_with_key::<Text>(
                     0,
                     TextProperties::new()
-                        .content(pipeline_view.pipeline.name.clone())
-                        .style(theme.title)
+                        .content(format!(
+                            "{}{}{}",
+                            if more_left { "‹ " } else { "" },
+                            pipeline_view.pipeline.name,
+                            if more_right { " ›" } else { "" }
+                        ))
+                        .style(if highlighted {
+                            theme.highlighted
+                        } else {
+                            theme.title
+                        })
                         .align(TextAlign::Centre),
                 ),
             ),
@@ -108,7 +299,11 @@ impl Component for Pipeline {
                     1,
                     TextProperties::new()
                         .content(subtitle)
-                        .style(theme.subtitle)
+                        .style(if over_limit {
+                            theme.over_limit
+                        } else {
+                            theme.subtitle
+                        })
                         .align(TextAlign::Centre),
                 ),
             ),
@@ -117,30 +312,172 @@ impl Component for Pipeline {
                 SelectProperties {
                     background: theme.title,
                     direction: layout::FlexDirection::Column,
-                    focused,
-                    num_items: pipeline_issues.len(),
+                    // Keys are for typing codes while they're shown
+                    focused: focused && jump_page_size.is_none(),
+                    num_items: cmp::min(
+                        num_cards.saturating_sub(first_card),
+                        jump_page_size.unwrap_or(num_cards),
+                    ),
                     item_at: (move |index: usize| {
+                        let index = first_card + index;
+                        if index >= pipeline_issues.len() {
+                            return layout::fixed(
+                                card_height,
+                                bot_fold_card(
+                                    &theme.issue,
+                                    bot_fold
+                                        .as_ref()
+                                        .expect("index past issues is the bot fold"),
+                                    focused && index == selected_issue,
+                                ),
+                            );
+                        }
                         let issue_number = pipeline_issues[index].number;
-                        let issue = issues.get(&issue_number).cloned();
-                        layout::fixed(
-                            10,
-                            layout::component_with_key::<IssueCard>(
-                                10000 + pipeline_issues[index].number.0,
-                                issue_card::Properties {
-                                    theme: theme.issue.clone(),
-                                    issue_number,
-                                    issue: issue.unwrap_or(FutureValue::Pending),
-                                    focused: focused && index == selected_issue,
+                        let foreign_repo = pipeline_issues[index].foreign_repo(repo_id);
+                        let tree_node = match foreign_repo {
+                            Some(_) => None,
+                            None => tree.get(&issue_number).copied(),
+                        };
+                        let card = match foreign_repo {
+                            // Everything else zentui tracks is keyed by the
+                            // board repo's issue numbers
+                            Some(foreign_repo) => issue_card::Properties {
+                                theme: theme.issue.clone(),
+                                issue_number,
+                                repo: match foreign_repos.get(&foreign_repo) {
+                                    Some(FutureValue::Ready(full_name)) => Some(full_name.clone()),
+                                    _ => None,
+                                },
+                                issue: match foreign_repos.get(&foreign_repo) {
+                                    Some(FutureValue::Error(message)) => {
+                                        FutureValue::Error(message.clone())
+                                    }
+                                    _ => foreign_issues
+                                        .get(&(foreign_repo, issue_number))
+                                        .cloned()
+                                        .unwrap_or(FutureValue::Pending),
                                 },
-                            ),
-                        )
+                                estimate: pipeline_issues[index]
+                                    .estimate
+                                    .map(|estimate| estimate.value),
+                                relationship: None,
+                                tree_node,
+                                pull_requests: Vector::new(),
+                                pull_request_status: None,
+                                releases: Vector::new(),
+                                sla_deadline: None,
+                                blocked: false,
+                                stale: false,
+                                num_folded_children: 0,
+                                moved: false,
+                                marked: false,
+                                matched: false,
+                                jump_code: None,
+                                footer: card_footer.clone(),
+                                density,
+                                max_title_lines,
+                                focused: focused && index == selected_issue,
+                            },
+                            None => issue_card::Properties {
+                                theme: theme.issue.clone(),
+                                issue_number,
+                                repo: None,
+                                issue: issues
+                                    .get(&issue_number)
+                                    .cloned()
+                                    .unwrap_or(FutureValue::Pending),
+                                estimate: pipeline_issues[index]
+                                    .estimate
+                                    .map(|estimate| estimate.value),
+                                relationship: relationships.get(&issue_number).copied(),
+                                tree_node,
+                                pull_requests: pull_requests
+                                    .get(&issue_number)
+                                    .cloned()
+                                    .unwrap_or_default(),
+                                pull_request_status: pull_request_statuses
+                                    .get(&issue_number)
+                                    .copied(),
+                                releases: releases.get(&issue_number).cloned().unwrap_or_default(),
+                                sla_deadline: sla_deadlines.get(&issue_number).copied(),
+                                blocked: blocked.contains(&issue_number),
+                                stale: stale.contains(&issue_number),
+                                num_folded_children: folded_children
+                                    .get(&issue_number)
+                                    .copied()
+                                    .unwrap_or(0),
+                                moved: moved.contains(&issue_number),
+                                marked: marked.contains(&issue_number),
+                                matched: search_matches.contains(&issue_number),
+                                jump_code: jump_codes.get(&issue_number).cloned(),
+                                footer: card_footer.clone(),
+                                density,
+                                max_title_lines,
+                                focused: focused && index == selected_issue,
+                            },
+                        };
+                        let card =
+                            layout::component_with_key::<IssueCard>(10000 + issue_number.0, card);
+                        let indent = tree_node
+                            .map_or(0, |node| cmp::min(node.depth, MAX_TREE_DEPTH) * TREE_INDENT);
+                        if indent == 0 {
+                            layout::fixed(card_height, card)
+                        } else {
+                            layout::fixed(
+                                card_height,
+                                layout::row([
+                                    layout::fixed(
+                                        indent,
+                                        layout::component::<Text>(
+                                            TextProperties::new().style(theme.title),
+                                        ),
+                                    ),
+                                    layout::auto(card),
+                                ]),
+                            )
+                        }
                     })
                     .into(),
-                    item_size: 10,
-                    selected: selected_issue,
+                    item_size: card_height,
+                    selected: selected_issue - first_card,
                     on_change: Some(on_selected_change.clone()),
                 },
             )),
         ])
     }
 }
+
+fn bot_fold_card(theme: &issue_card::Theme, bot_fold: &BotFold, focused: bool) -> Layout {
+    layout::component_with_key_str::<Border>(
+        "bot-fold",
+        BorderProperties::new(layout::component::<Text>(
+            TextProperties::new()
+                .content(format!(
+                    "{} by {} (press b to expand)",
+                    humanize::count(bot_fold.num_issues, Noun::Issue),
+                    bot_fold.accounts.join(", ")
+                ))
+                .style(theme.number)
+                .wrap(TextWrap::Word),
+        ))
+        .style(if focused {
+            theme.focus.border
+        } else {
+            theme.border
+        })
+        .title(Some((
+            " bots ".to_owned(),
+            if focused {
+                theme.focus.title(theme.text)
+            } else {
+                theme.text
+            },
+        ))),
+    )
+}
+
+const HEADER_HEIGHT: usize = 3;
+const MIN_CARD_HEIGHT: usize = 4;
+const MINIMAL_CARD_HEIGHT: usize = 3;
+const TREE_INDENT: usize = 2;
+const MAX_TREE_DEPTH: usize = 3;
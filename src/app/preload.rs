@@ -0,0 +1,92 @@
+use anyhow::Result;
+use chrono::Utc;
+use futures::stream::{self, StreamExt};
+use std::sync::Arc;
+
+use crate::{
+    cache::{self, BoardCache},
+    github::{Client as GithubClient, RepoFullName, ISSUES_PER_QUERY},
+    zenhub::{Client as ZenhubClient, WorkspaceId},
+};
+
+/// A pinned repo to preload, with the workspace whose board was last opened
+/// for it, if any.
+#[derive(Clone, Debug)]
+pub struct PinnedRepo {
+    pub full_name: RepoFullName,
+    pub workspace: Option<WorkspaceId>,
+}
+
+/// Preloads the boards of the pinned repos, at most `max_concurrent` at a
+/// time so the board in view keeps most of the request budget.
+pub async fn preload_boards(
+    github_client: Arc<GithubClient>,
+    zenhub_client: Arc<ZenhubClient>,
+    repos: Vec<PinnedRepo>,
+    max_concurrent: usize,
+) -> Vec<(RepoFullName, Result<()>)> {
+    stream::iter(repos)
+        .map(|repo| {
+            let github_client = github_client.clone();
+            let zenhub_client = zenhub_client.clone();
+            async move {
+                let full_name = repo.full_name.clone();
+                let result = preload_board(github_client, zenhub_client, repo).await;
+                (full_name, result)
+            }
+        })
+        .buffer_unordered(max_concurrent.max(1))
+        .collect()
+        .await
+}
+
+/// Fetches the board of a repo and the issues at the top of its pipelines
+/// into the repo's cache, which opening the repo shows right away.
+async fn preload_board(
+    github_client: Arc<GithubClient>,
+    zenhub_client: Arc<ZenhubClient>,
+    repo: PinnedRepo,
+) -> Result<()> {
+    let path = cache::cache_path(&repo.full_name)?;
+    let github_repo = github_client.get_repo(&repo.full_name).await?;
+    let repo_id = github_repo.id;
+    let mut board = match repo.workspace {
+        Some(workspace) => zenhub_client.get_board(workspace, repo_id).await?,
+        None => zenhub_client.get_oldest_board(repo_id).await?,
+    };
+
+    let mut issue_numbers = Vec::new();
+    for pipeline in board.pipelines.iter_mut() {
+        pipeline.sort_issues();
+        issue_numbers.extend(
+            pipeline
+                .issues
+                .iter()
+                .filter(|issue_ref| issue_ref.foreign_repo(repo_id).is_none())
+                .take(PRELOADED_ISSUES_PER_PIPELINE)
+                .map(|issue_ref| issue_ref.number),
+        );
+    }
+    let full_name = Arc::new(github_repo.full_name);
+    let mut issues = Vec::new();
+    for batch in issue_numbers.chunks(ISSUES_PER_QUERY) {
+        issues.extend(
+            github_client
+                .clone()
+                .get_issues(full_name.clone(), batch.to_vec())
+                .await?,
+        );
+    }
+
+    cache::write_cache(
+        path,
+        &BoardCache {
+            board,
+            issues,
+            saved_at: Some(Utc::now()),
+        },
+    )
+}
+
+/// Enough to fill the pipelines of a tall terminal.
+const PRELOADED_ISSUES_PER_PIPELINE: usize = 10;
@@ -1,35 +1,236 @@
 use palette::{Gradient, Hsv, LinSrgb};
-use std::rc::Rc;
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+use unicode_width::UnicodeWidthStr;
 use zi::{
     components::text::{Text, TextAlign, TextProperties},
     layout,
     terminal::Style,
-    Colour, Component, ComponentLink, Layout, Rect, ShouldRender,
+    Canvas, Colour, Component, ComponentLink, Key, Layout, Rect, ShouldRender,
 };
 
-use super::Base16Theme;
+use super::{picker, Base16Theme};
+use crate::github::IssueNumber;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Theme {
-    pub pending: Style,
-    pub ready: Style,
     pub text: Style,
+    pub question: Style,
+    pub answer: Style,
+    pub cursor: Style,
+    pub option: Style,
+    pub selected_option: Style,
 }
 
 impl From<&Base16Theme> for Theme {
     fn from(theme: &Base16Theme) -> Self {
         Self {
-            pending: Style::bold(theme.base0e, theme.base00),
-            ready: Style::bold(theme.base0e, theme.base00),
             text: Style::bold(theme.base00, theme.base04),
+            question: Style::bold(theme.base01, theme.base0d),
+            answer: Style::normal(theme.base01, theme.base09),
+            cursor: Style::normal(theme.base09, theme.base01),
+            option: Style::normal(theme.base01, theme.base05),
+            selected_option: Style::bold(theme.base02, theme.base08),
+        }
+    }
+}
+
+/// A question asked in the prompt row: free text, a yes/no confirmation or a
+/// choice between a few options. Like a picker, the `action` records what to
+/// do with the answer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Question<ActionT> {
+    pub action: ActionT,
+    label: String,
+    kind: QuestionKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuestionKind {
+    /// The cursor is an index in chars, not bytes.
+    Text {
+        value: String,
+        cursor: usize,
+    },
+    Confirm,
+    Select {
+        options: Vec<String>,
+        selected: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Answer {
+    Text(String),
+    Confirmed,
+    Selected(usize),
+}
+
+/// What a key press did to a question.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Pending,
+    Cancelled,
+    Answered(Answer),
+}
+
+impl<ActionT> Question<ActionT> {
+    pub fn text(label: impl Into<String>, initial: impl Into<String>, action: ActionT) -> Self {
+        let value = initial.into();
+        Self {
+            action,
+            label: label.into(),
+            kind: QuestionKind::Text {
+                cursor: value.chars().count(),
+                value,
+            },
+        }
+    }
+
+    /// A yes/no question; anything but `y` cancels.
+    pub fn confirm(label: impl Into<String>, action: ActionT) -> Self {
+        Self {
+            action,
+            label: label.into(),
+            kind: QuestionKind::Confirm,
+        }
+    }
+
+    pub fn select(label: impl Into<String>, options: Vec<String>, action: ActionT) -> Self {
+        Self {
+            action,
+            label: label.into(),
+            kind: QuestionKind::Select {
+                options,
+                selected: 0,
+            },
+        }
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> Outcome {
+        if let Key::Esc | Key::Ctrl('g') = key {
+            return Outcome::Cancelled;
+        }
+        match self.kind {
+            QuestionKind::Text {
+                ref mut value,
+                ref mut cursor,
+            } => {
+                let byte_index = |value: &str, cursor: usize| {
+                    value
+                        .char_indices()
+                        .nth(cursor)
+                        .map_or(value.len(), |(index, _)| index)
+                };
+                match key {
+                    Key::Char('\n') => return Outcome::Answered(Answer::Text(value.clone())),
+                    Key::Char(character) => {
+                        let index = byte_index(value, *cursor);
+                        value.insert(index, character);
+                        *cursor += 1;
+                    }
+                    Key::Backspace if *cursor > 0 => {
+                        *cursor -= 1;
+                        let index = byte_index(value, *cursor);
+                        value.remove(index);
+                    }
+                    Key::Ctrl('d') if *cursor < value.chars().count() => {
+                        let index = byte_index(value, *cursor);
+                        value.remove(index);
+                    }
+                    Key::Left | Key::Ctrl('b') => *cursor = cursor.saturating_sub(1),
+                    Key::Right | Key::Ctrl('f') => {
+                        *cursor = (*cursor + 1).min(value.chars().count())
+                    }
+                    Key::Ctrl('a') => *cursor = 0,
+                    Key::Ctrl('e') => *cursor = value.chars().count(),
+                    Key::Ctrl('k') => {
+                        let index = byte_index(value, *cursor);
+                        value.truncate(index);
+                    }
+                    Key::Ctrl('u') => {
+                        value.clear();
+                        *cursor = 0;
+                    }
+                    _ => {}
+                }
+                Outcome::Pending
+            }
+            QuestionKind::Confirm => match key {
+                Key::Char('y') | Key::Char('Y') => Outcome::Answered(Answer::Confirmed),
+                _ => Outcome::Cancelled,
+            },
+            QuestionKind::Select {
+                ref options,
+                ref mut selected,
+            } => {
+                match key {
+                    Key::Char('\n') => return Outcome::Answered(Answer::Selected(*selected)),
+                    Key::Char(digit @ '1'..='9') => {
+                        let index = digit as usize - '1' as usize;
+                        if index < options.len() {
+                            return Outcome::Answered(Answer::Selected(index));
+                        }
+                    }
+                    Key::Right | Key::Ctrl('f') | Key::Ctrl('n') | Key::Char('\t') => {
+                        *selected = (*selected + 1) % options.len().max(1)
+                    }
+                    Key::Left | Key::Ctrl('b') | Key::Ctrl('p') => {
+                        *selected = (*selected + options.len().max(1) - 1) % options.len().max(1)
+                    }
+                    _ => {}
+                }
+                Outcome::Pending
+            }
+        }
+    }
+
+    /// The text typed so far, for questions which take text.
+    pub fn typed(&self) -> Option<&str> {
+        match self.kind {
+            QuestionKind::Text { ref value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn properties(&self, theme: Rc<Theme>) -> QuestionProperties {
+        QuestionProperties {
+            theme,
+            label: self.label.clone(),
+            kind: self.kind.clone(),
         }
     }
 }
 
+/// Whether a card matches a search typed in the prompt row, either by number,
+/// e.g. `#12` or `12`, or fuzzily by title.
+pub fn search_matches(query: &str, issue_number: IssueNumber, title: &str) -> bool {
+    let query = query.trim();
+    let digits = query.trim_start_matches('#');
+    if !digits.is_empty() && digits.chars().all(|character| character.is_ascii_digit()) {
+        issue_number.0.to_string().starts_with(digits)
+    } else {
+        !query.is_empty() && picker::fuzzy_score(query, title).is_some()
+    }
+}
+
+/// A piece of the status row, in its own style.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    /// Spins while requests are pending.
+    Sync(Style),
+    /// The status message, which takes the width the other segments leave.
+    Message(String, Style),
+    Text(String, Style),
+}
+
 #[derive(Clone, PartialEq)]
 pub struct PromptProperties {
     pub theme: Rc<Theme>,
     pub pending: bool,
+    pub segments: Vec<Segment>,
 }
 
 pub struct Prompt {
@@ -58,29 +259,147 @@ impl Component for Prompt {
     }
 
     fn view(&self) -> Layout {
-        layout::row([
-            layout::fixed(
-                1,
-                layout::component_with_key::<Status>(
-                    0,
-                    StatusProperties {
-                        style: if self.properties.pending {
-                            self.properties.theme.pending
-                        } else {
-                            self.properties.theme.ready
-                        },
-                        pending: self.properties.pending,
-                    },
-                ),
-            ),
-            layout::auto(layout::component_with_key::<Text>(
-                1,
-                TextProperties::new()
-                    .content("")
-                    .style(self.properties.theme.text)
-                    .align(TextAlign::Left),
-            )),
-        ])
+        let PromptProperties {
+            ref theme,
+            pending,
+            ref segments,
+        } = self.properties;
+        let has_message = segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Message(..)));
+        // Without a message, an empty one fills the rest of the row
+        let filler = if has_message {
+            None
+        } else {
+            Some(layout::auto(layout::component_with_key::<Text>(
+                segments.len(),
+                TextProperties::new().style(theme.text),
+            )))
+        };
+        layout::row_iter(
+            segments
+                .iter()
+                .enumerate()
+                .map(|(index, segment)| match *segment {
+                    Segment::Sync(style) => layout::fixed(
+                        1,
+                        layout::component_with_key::<Status>(
+                            index,
+                            StatusProperties { style, pending },
+                        ),
+                    ),
+                    Segment::Message(ref message, style) => {
+                        layout::auto(layout::component_with_key::<Text>(
+                            index,
+                            TextProperties::new()
+                                .content(format!(" {}", message))
+                                .style(style)
+                                .align(TextAlign::Left),
+                        ))
+                    }
+                    Segment::Text(ref text, style) => layout::fixed(
+                        UnicodeWidthStr::width(text.as_str()) + 2,
+                        layout::component_with_key::<Text>(
+                            index,
+                            TextProperties::new()
+                                .content(format!(" {} ", text))
+                                .style(style)
+                                .align(TextAlign::Left),
+                        ),
+                    ),
+                })
+                .chain(filler),
+        )
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct QuestionProperties {
+    pub theme: Rc<Theme>,
+    pub label: String,
+    pub kind: QuestionKind,
+}
+
+/// Draws a question in the prompt row, with the answer typed so far.
+pub struct QuestionView {
+    properties: QuestionProperties,
+    frame: Rect,
+}
+
+impl Component for QuestionView {
+    type Message = ();
+    type Properties = QuestionProperties;
+
+    fn create(properties: Self::Properties, frame: Rect, _link: ComponentLink<Self>) -> Self {
+        Self { properties, frame }
+    }
+
+    fn change(&mut self, properties: Self::Properties) -> ShouldRender {
+        if self.properties != properties {
+            self.properties = properties;
+            ShouldRender::Yes
+        } else {
+            ShouldRender::No
+        }
+    }
+
+    fn resize(&mut self, frame: Rect) -> ShouldRender {
+        self.frame = frame;
+        ShouldRender::Yes
+    }
+
+    fn view(&self) -> Layout {
+        let Self {
+            properties:
+                QuestionProperties {
+                    ref theme,
+                    ref label,
+                    ref kind,
+                },
+            frame,
+        } = *self;
+
+        let mut canvas = Canvas::new(frame.size);
+        canvas.clear(theme.option);
+
+        let label = match kind {
+            QuestionKind::Confirm => format!(" {} [y/N] ", label),
+            _ => format!(" {}: ", label),
+        };
+        canvas.draw_str(0, 0, theme.question, &label);
+        let mut column = UnicodeWidthStr::width(label.as_str());
+        match kind {
+            QuestionKind::Text { value, cursor } => {
+                let (before, after) = value.split_at(
+                    value
+                        .char_indices()
+                        .nth(*cursor)
+                        .map_or(value.len(), |(index, _)| index),
+                );
+                canvas.draw_str(column, 0, theme.answer, before);
+                column += UnicodeWidthStr::width(before);
+                let mut after = after.chars();
+                let under_cursor = after.next().map_or(" ".to_owned(), String::from);
+                canvas.draw_str(column, 0, theme.cursor, &under_cursor);
+                column += UnicodeWidthStr::width(under_cursor.as_str());
+                canvas.draw_str(column, 0, theme.answer, after.as_str());
+            }
+            QuestionKind::Confirm => {}
+            QuestionKind::Select { options, selected } => {
+                for (index, option) in options.iter().enumerate() {
+                    let option = format!(" {} {} ", index + 1, option);
+                    let style = if index == *selected {
+                        theme.selected_option
+                    } else {
+                        theme.option
+                    };
+                    canvas.draw_str(column, 0, style, &option);
+                    column += UnicodeWidthStr::width(option.as_str());
+                }
+            }
+        }
+
+        canvas.into()
     }
 }
 
@@ -95,6 +414,7 @@ pub struct Status {
     animation_offset: f32,
     progress_index: usize,
     gradient: Gradient<Hsv>,
+    last_frame: Instant,
 }
 
 impl Component for Status {
@@ -107,6 +427,7 @@ impl Component for Status {
             properties,
             animation_offset: 1.0,
             progress_index: 0,
+            last_frame: Instant::now(),
         }
     }
 
@@ -127,6 +448,7 @@ impl Component for Status {
         // `animation_offset` ticks in the interval [0, 2]:
         self.animation_offset = (self.animation_offset + 2.0 / PROGRESS_PATTERN.len() as f32) % 2.0;
         self.progress_index = (self.progress_index + 1) % PROGRESS_PATTERN.len();
+        self.last_frame = Instant::now();
         ShouldRender::Yes
     }
 
@@ -150,7 +472,9 @@ impl Component for Status {
     }
 
     fn tick(&self) -> Option<Self::Message> {
-        if self.properties.pending {
+        // Only animate while there is work in flight and no faster than the
+        // spinner's frame rate; an idle prompt doesn't schedule any updates.
+        if self.properties.pending && self.last_frame.elapsed() >= PROGRESS_FRAME_INTERVAL {
             Some(())
         } else {
             None
@@ -202,6 +526,8 @@ impl Status {
     }
 }
 
+const PROGRESS_FRAME_INTERVAL: Duration = Duration::from_millis(80);
+
 // const PROGRESS_PATTERN: [char; 16] = [
 //     '⣾', '⣽', '⣻', '⢿', '⡿', '⣟', '⣯', '⣷', '⠁', '⠂', '⠄', '⡀', '⢀', '⠠', '⠐', '⠈',
 // ];
@@ -0,0 +1,53 @@
+use im::hashmap::HashMap;
+use regex::Regex;
+
+use super::{BoardView, FutureValue};
+use crate::github::{Issue, IssueNumber};
+
+/// A find and replace across the titles of the issues on the board, e.g. to
+/// rename a component prefix from `ui:` to `tui:`. The replacement can refer
+/// to groups of the pattern, e.g. `$1`.
+#[derive(Clone, Debug)]
+pub struct TitleReplace {
+    pub pattern: Regex,
+    pub replacement: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rename {
+    pub issue_number: IssueNumber,
+    pub old_title: String,
+    pub new_title: String,
+}
+
+/// The titles the replacement would change, in board order. Only issues which
+/// were loaded are renamed.
+pub fn renames(
+    board: &BoardView,
+    issues: &HashMap<IssueNumber, FutureValue<Issue>>,
+    replace: &TitleReplace,
+) -> Vec<Rename> {
+    board
+        .pipelines
+        .iter()
+        .flat_map(|pipeline| pipeline.pipeline.issues.iter())
+        .filter_map(|issue_ref| match issues.get(&issue_ref.number) {
+            Some(FutureValue::Ready(issue)) => Some(issue),
+            _ => None,
+        })
+        .filter_map(|issue| {
+            let new_title = replace
+                .pattern
+                .replace_all(&issue.title, replace.replacement.as_str());
+            if new_title == issue.title {
+                None
+            } else {
+                Some(Rename {
+                    issue_number: issue.number,
+                    old_title: issue.title.clone(),
+                    new_title: new_title.into_owned(),
+                })
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,22 @@
+use crate::github::{Issue, RepoFullName};
+
+/// Fills in a share template from the settings. The placeholders are
+/// `{repo}`, `{number}`, `{title}`, `{author}`, `{labels}`, `{pipeline}` and
+/// `{url}`.
+pub fn render(template: &str, repo: &RepoFullName, issue: &Issue, pipeline: &str) -> String {
+    let labels = issue
+        .labels
+        .iter()
+        .map(|label| label.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let url = format!("https://github.com/{}/issues/{}", repo.0, issue.number.0);
+    template
+        .replace("{repo}", &repo.0)
+        .replace("{number}", &issue.number.0.to_string())
+        .replace("{title}", &issue.title)
+        .replace("{author}", &issue.user.login)
+        .replace("{labels}", &labels)
+        .replace("{pipeline}", pipeline)
+        .replace("{url}", &url)
+}
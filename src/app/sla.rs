@@ -0,0 +1,50 @@
+use chrono::{DateTime, Duration, Utc};
+use im::hashmap::HashMap;
+
+use super::FutureValue;
+use crate::{
+    github::{Issue, IssueNumber, IssueState},
+    settings::{SlaClock, SlaRule},
+};
+
+/// When each open issue with an SLA label is due, for the issues which have
+/// loaded.
+pub fn deadlines(
+    rules: &[SlaRule],
+    issues: &HashMap<IssueNumber, FutureValue<Issue>>,
+) -> HashMap<IssueNumber, DateTime<Utc>> {
+    if rules.is_empty() {
+        return HashMap::new();
+    }
+    issues
+        .iter()
+        .filter_map(|(issue_number, issue)| match issue {
+            FutureValue::Ready(issue) => deadline(rules, issue).map(|due| (*issue_number, due)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The earliest deadline of the rules matching the issue's labels. Closed
+/// issues have none.
+pub fn deadline(rules: &[SlaRule], issue: &Issue) -> Option<DateTime<Utc>> {
+    if issue.state == IssueState::Closed {
+        return None;
+    }
+    rules
+        .iter()
+        .filter(|rule| {
+            issue
+                .labels
+                .iter()
+                .any(|label| label.name.eq_ignore_ascii_case(&rule.label))
+        })
+        .filter_map(|rule| {
+            let start = match rule.from {
+                SlaClock::Created => issue.created_at,
+                SlaClock::Updated => issue.updated_at.or(issue.created_at),
+            }?;
+            Some(start + Duration::hours(rule.hours))
+        })
+        .min()
+}
@@ -0,0 +1,44 @@
+use chrono::Duration;
+
+/// How long to snooze an issue for, typed as a count and a unit, e.g. `4h`,
+/// `2d` or `1w`.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let unit_start = text.find(|character: char| !character.is_ascii_digit())?;
+    let (count, unit) = text.split_at(unit_start);
+    let count: i64 = count
+        .parse()
+        .ok()
+        .filter(|&count| count > 0 && count <= MAX_COUNT)?;
+    match unit.trim() {
+        "h" | "hour" | "hours" => Some(Duration::hours(count)),
+        "d" | "day" | "days" => Some(Duration::days(count)),
+        "w" | "week" | "weeks" => Some(Duration::weeks(count)),
+        _ => None,
+    }
+}
+
+/// Keeps durations far from the limits of `Duration`, which panics past
+/// them. No one snoozes an issue for 10000 weeks.
+const MAX_COUNT: i64 = 9999;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_of_hours_days_and_weeks() {
+        assert_eq!(parse_duration("4h"), Some(Duration::hours(4)));
+        assert_eq!(parse_duration(" 2 days "), Some(Duration::days(2)));
+        assert_eq!(parse_duration("1w"), Some(Duration::weeks(1)));
+    }
+
+    #[test]
+    fn no_duration_without_a_count_or_unit() {
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("3"), None);
+        assert_eq!(parse_duration("0d"), None);
+        assert_eq!(parse_duration("3 fortnights"), None);
+        assert_eq!(parse_duration("99999999999999999999d"), None);
+    }
+}
@@ -0,0 +1,123 @@
+use std::rc::Rc;
+use unicode_width::UnicodeWidthStr;
+use zi::{Canvas, Component, ComponentLink, Layout, Rect, ShouldRender};
+
+use super::overlay::Theme;
+
+/// How far a step of loading the board got.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Progress {
+    Done,
+    Pending,
+    Failed,
+}
+
+/// A step of loading the board, e.g. `board` or `issues 12/80`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub progress: Progress,
+    pub label: String,
+    pub detail: String,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Properties {
+    pub theme: Rc<Theme>,
+    pub title: String,
+    pub steps: Vec<Step>,
+    pub warnings: Vec<String>,
+}
+
+/// Shown instead of the board until it has loaded, with the progress of each
+/// step, so a slow or failing start isn't just a blank screen.
+pub struct Splash {
+    properties: Properties,
+    frame: Rect,
+}
+
+impl Component for Splash {
+    type Message = ();
+    type Properties = Properties;
+
+    fn create(properties: Self::Properties, frame: Rect, _link: ComponentLink<Self>) -> Self {
+        Self { properties, frame }
+    }
+
+    fn change(&mut self, properties: Self::Properties) -> ShouldRender {
+        if self.properties != properties {
+            self.properties = properties;
+            ShouldRender::Yes
+        } else {
+            ShouldRender::No
+        }
+    }
+
+    fn resize(&mut self, frame: Rect) -> ShouldRender {
+        self.frame = frame;
+        ShouldRender::Yes
+    }
+
+    fn view(&self) -> Layout {
+        let Self {
+            properties:
+                Properties {
+                    ref theme,
+                    ref title,
+                    ref steps,
+                    ref warnings,
+                },
+            frame,
+        } = *self;
+
+        let mut canvas = Canvas::new(frame.size);
+        canvas.clear(theme.text);
+
+        let label_width = steps
+            .iter()
+            .map(|step| UnicodeWidthStr::width(step.label.as_str()))
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec![
+            (format!(" {} ", title), theme.heading),
+            (String::new(), theme.text),
+        ];
+        lines.extend(steps.iter().map(|step| {
+            let (symbol, style) = match step.progress {
+                Progress::Done => ("✓", theme.text),
+                Progress::Pending => ("…", theme.dim),
+                Progress::Failed => ("✗", theme.selected),
+            };
+            (
+                format!(
+                    "{} {:width$}  {}",
+                    symbol,
+                    step.label,
+                    step.detail,
+                    width = label_width
+                ),
+                style,
+            )
+        }));
+        if !warnings.is_empty() {
+            lines.push((String::new(), theme.text));
+            lines.extend(
+                warnings
+                    .iter()
+                    .map(|warning| (format!("⚠ {}", warning), theme.bar)),
+            );
+        }
+
+        // Centre the block of lines, keeping them aligned with each other
+        let width = lines
+            .iter()
+            .map(|(line, _)| UnicodeWidthStr::width(line.as_str()))
+            .max()
+            .unwrap_or(0);
+        let x = frame.size.width.saturating_sub(width) / 2;
+        let y = frame.size.height.saturating_sub(lines.len()) / 2;
+        for (row, (line, style)) in lines.iter().enumerate() {
+            canvas.draw_str(x, y + row, *style, line);
+        }
+        canvas.into()
+    }
+}
@@ -0,0 +1,218 @@
+use chrono::{DateTime, Utc};
+use im::hashmap::HashMap;
+use std::cmp;
+
+use super::{BoardView, FutureValue, PipelineIndex};
+use crate::{
+    github::{Issue, IssueNumber, IssueState},
+    settings::StaleSettings,
+    zenhub::PipelineKind,
+};
+
+/// What to do with the stale issues which weren't skipped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SweepAction {
+    Nudge,
+    Label,
+    Icebox,
+    Close,
+}
+
+impl SweepAction {
+    pub fn imperative(&self) -> &'static str {
+        match self {
+            Self::Nudge => "Nudge",
+            Self::Label => "Label",
+            Self::Icebox => "Icebox",
+            Self::Close => "Close",
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            Self::Nudge => "Nudged",
+            Self::Label => "Labelled",
+            Self::Icebox => "Iceboxed",
+            Self::Close => "Closed",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StaleIssue {
+    pub issue: Issue,
+    pub pipeline: PipelineIndex,
+    pub days: i64,
+}
+
+/// The pipelines to look for stale issues in: the ones named in the settings,
+/// or all but the Icebox and Closed.
+pub fn swept_pipelines(board: &BoardView, settings: &StaleSettings) -> Vec<PipelineIndex> {
+    board
+        .pipelines
+        .iter()
+        .enumerate()
+        .filter(|(_, pipeline)| {
+            if settings.pipelines.is_empty() {
+                !matches!(
+                    pipeline.pipeline.kind(),
+                    PipelineKind::Icebox | PipelineKind::Closed
+                )
+            } else {
+                settings.pipelines.iter().any(|name| {
+                    name.trim()
+                        .eq_ignore_ascii_case(pipeline.pipeline.name.trim())
+                })
+            }
+        })
+        .map(|(pipeline_index, _)| pipeline_index)
+        .collect()
+}
+
+/// The open issues in the swept pipelines which haven't been updated for the
+/// number of days in the settings, most neglected first. Issues which haven't
+/// loaded yet are left out.
+pub fn stale_issues(
+    board: &BoardView,
+    issues: &HashMap<IssueNumber, FutureValue<Issue>>,
+    settings: &StaleSettings,
+    now: DateTime<Utc>,
+) -> Vec<StaleIssue> {
+    let mut stale: Vec<_> = swept_pipelines(board, settings)
+        .into_iter()
+        .flat_map(|pipeline_index| {
+            board.pipelines[pipeline_index]
+                .pipeline
+                .issues
+                .iter()
+                .filter_map(move |issue_ref| match issues.get(&issue_ref.number) {
+                    Some(FutureValue::Ready(issue)) if issue.state == IssueState::Open => {
+                        let days = (now - issue.updated_at?).num_days();
+                        if days >= settings.days {
+                            Some(StaleIssue {
+                                issue: issue.clone(),
+                                pipeline: pipeline_index,
+                                days,
+                            })
+                        } else {
+                            None
+                        }
+                    }
+                    _ => None,
+                })
+        })
+        .collect();
+    stale.sort_by_key(|stale_issue| cmp::Reverse(stale_issue.days));
+    stale
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use im::Vector;
+
+    use super::*;
+    use crate::zenhub::{Board, IssueRef, Pipeline};
+
+    fn now() -> DateTime<Utc> {
+        Utc.ymd(2020, 6, 1).and_hms(12, 0, 0)
+    }
+
+    fn board(pipelines: &[(&str, &[usize])]) -> BoardView {
+        BoardView::from(Board {
+            pipelines: pipelines
+                .iter()
+                .enumerate()
+                .map(|(index, (name, numbers))| Pipeline {
+                    id: format!("pipeline-{}", index),
+                    name: (*name).to_owned(),
+                    issues: numbers
+                        .iter()
+                        .enumerate()
+                        .map(|(position, &number)| IssueRef {
+                            number: IssueNumber(number),
+                            estimate: None,
+                            position: Some(position),
+                            is_epic: false,
+                            repo_id: None,
+                        })
+                        .collect::<Vector<_>>(),
+                })
+                .collect(),
+        })
+    }
+
+    fn issue(number: usize, days_old: i64, state: &str) -> (IssueNumber, FutureValue<Issue>) {
+        let issue = serde_json::from_value(serde_json::json!({
+            "number": number,
+            "state": state,
+            "pull_request": null,
+            "updated_at": now() - Duration::days(days_old),
+        }))
+        .expect("issue parses");
+        (IssueNumber(number), FutureValue::Ready(issue))
+    }
+
+    fn stale_numbers(
+        board: &BoardView,
+        issues: &HashMap<IssueNumber, FutureValue<Issue>>,
+        settings: &StaleSettings,
+    ) -> Vec<usize> {
+        stale_issues(board, issues, settings, now())
+            .iter()
+            .map(|stale_issue| stale_issue.issue.number.0)
+            .collect()
+    }
+
+    #[test]
+    fn open_issues_past_the_threshold_most_neglected_first() {
+        let board = board(&[("Backlog", &[1, 2, 3]), ("In Progress", &[4, 5])]);
+        let issues: HashMap<_, _> = vec![
+            issue(1, 61, "open"),
+            issue(2, 59, "open"),
+            issue(3, 90, "closed"),
+            issue(4, 120, "open"),
+            issue(5, 60, "open"),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            stale_numbers(&board, &issues, &StaleSettings::default()),
+            vec![4, 1, 5]
+        );
+    }
+
+    #[test]
+    fn the_icebox_closed_and_unloaded_issues_are_left_out() {
+        let board = board(&[("Icebox", &[1]), ("Backlog", &[2, 3]), ("Closed", &[4])]);
+        let mut issues: HashMap<_, _> = vec![
+            issue(1, 100, "open"),
+            issue(2, 100, "open"),
+            issue(4, 100, "open"),
+        ]
+        .into_iter()
+        .collect();
+        issues.insert(IssueNumber(3), FutureValue::Pending);
+        assert_eq!(
+            stale_numbers(&board, &issues, &StaleSettings::default()),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn only_the_pipelines_named_in_the_settings_are_swept() {
+        let board = board(&[("Icebox", &[1]), ("Backlog", &[2]), ("Review", &[3])]);
+        let issues: HashMap<_, _> = vec![
+            issue(1, 100, "open"),
+            issue(2, 100, "open"),
+            issue(3, 100, "open"),
+        ]
+        .into_iter()
+        .collect();
+        let settings = StaleSettings {
+            pipelines: vec![" icebox".into(), "REVIEW".into()],
+            ..StaleSettings::default()
+        };
+        assert_eq!(stale_numbers(&board, &issues, &settings), vec![1, 3]);
+    }
+}
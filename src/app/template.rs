@@ -0,0 +1,47 @@
+//! A small template engine for the lines configured in the settings, e.g. the
+//! card footer `{assignees}[ · {estimate}pt] · {age}`.
+//!
+//! `{name}` is replaced by the value of the placeholder `name`. Text in
+//! `[...]` is only kept when every placeholder inside it has a value, so
+//! separators and units can disappear together with an empty value. A `\`
+//! makes the next character literal, e.g. `\[`.
+
+use std::{iter::Peekable, str::Chars};
+
+/// Renders `template`, looking up placeholders with `value`. Placeholders
+/// without a value render as nothing.
+pub fn render(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    render_group(&mut template.chars().peekable(), &value, false).0
+}
+
+/// Renders up to the end of the current group, or of the template. Returns
+/// the text and whether every placeholder in it had a value.
+fn render_group(
+    chars: &mut Peekable<Chars>,
+    value: &dyn Fn(&str) -> Option<String>,
+    in_group: bool,
+) -> (String, bool) {
+    let mut rendered = String::new();
+    let mut complete = true;
+    while let Some(character) = chars.next() {
+        match character {
+            '\\' => rendered.extend(chars.next()),
+            '{' => {
+                let name: String = chars.by_ref().take_while(|&next| next != '}').collect();
+                match value(name.trim()) {
+                    Some(text) => rendered.push_str(&text),
+                    None => complete = false,
+                }
+            }
+            '[' => {
+                let (group, group_complete) = render_group(chars, value, true);
+                if group_complete {
+                    rendered.push_str(&group);
+                }
+            }
+            ']' if in_group => return (rendered, complete),
+            character => rendered.push(character),
+        }
+    }
+    (rendered, complete)
+}
@@ -0,0 +1,108 @@
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{Base16Theme, ICY};
+use crate::settings::Settings;
+
+/// The directory base16 scheme files are looked up in, `themes` next to the
+/// settings file, e.g. `~/.config/zentui/themes/ocean.yaml`.
+pub fn themes_dir(settings_path: &Path) -> Option<PathBuf> {
+    settings_path.parent().map(|parent| parent.join("themes"))
+}
+
+/// The names of the schemes in the themes directory, i.e. their file names
+/// without the `.yaml` extension, sorted.
+pub fn scheme_names(themes_dir: &Path) -> Vec<String> {
+    let mut names: Vec<_> = fs::read_dir(themes_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| is_scheme_file(path))
+                .filter_map(|path| Some(path.file_stem()?.to_str()?.to_owned()))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Reads a scheme by name, matching either its file name or the `scheme`
+/// it declares, ignoring case.
+pub fn load_scheme(themes_dir: &Path, name: &str) -> Result<Base16Theme> {
+    let entries = fs::read_dir(themes_dir).with_context(|| {
+        format!(
+            "Could not read the themes directory `{}`",
+            themes_dir.display()
+        )
+    })?;
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_scheme_file(path))
+    {
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Could not read theme `{}`", path.display()))?;
+        let fields = parse_scheme(&contents);
+        let matches_file = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map_or(false, |stem| stem.eq_ignore_ascii_case(name));
+        let matches_scheme = fields
+            .get("scheme")
+            .map_or(false, |scheme| scheme.eq_ignore_ascii_case(name));
+        if matches_file || matches_scheme {
+            let colours = fields
+                .into_iter()
+                .filter(|(key, _)| key.starts_with("base"))
+                .collect();
+            return Ok(ICY.with_overrides(&colours));
+        }
+    }
+    Err(anyhow!("No theme `{}` in `{}`", name, themes_dir.display()))
+}
+
+/// The theme described by the settings: the scheme they name, if any, with
+/// the colours they override on top.
+pub fn from_settings(settings: &Settings, settings_path: Option<&Path>) -> Base16Theme {
+    let scheme = match (settings.scheme.as_ref(), settings_path.and_then(themes_dir)) {
+        (Some(name), Some(themes_dir)) => load_scheme(&themes_dir, name)
+            .map_err(|error| log::error!("{:#}", error))
+            .ok(),
+        _ => None,
+    };
+    scheme.unwrap_or(ICY).with_overrides(&settings.theme)
+}
+
+fn is_scheme_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| {
+            extension.eq_ignore_ascii_case("yaml") || extension.eq_ignore_ascii_case("yml")
+        })
+}
+
+/// Base16 scheme files are flat YAML maps, e.g. `base00: "2b303b"`. Only
+/// that subset of YAML is understood: one `key: value` per line, values
+/// optionally quoted and followed by a comment.
+fn parse_scheme(contents: &str) -> BTreeMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, ':');
+            let key = parts.next()?.trim();
+            let value = parts.next()?.trim();
+            let value = match value.chars().next() {
+                Some(quote @ '"') | Some(quote @ '\'') => value[1..].split(quote).next()?,
+                _ => value.split(" #").next()?.trim(),
+            };
+            Some((key.to_lowercase(), value.to_owned()))
+        })
+        .collect()
+}
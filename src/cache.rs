@@ -0,0 +1,74 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use tempfile::NamedTempFile;
+
+use crate::{
+    github::{Issue, RepoFullName},
+    zenhub::Board,
+};
+
+/// The board and issues of a repo as last seen, shown at startup while the
+/// live ones load. Entries are refreshed in the background as they come into
+/// view, so the cache is never trusted for long.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct BoardCache {
+    pub board: Board,
+    #[serde(default)]
+    pub issues: Vec<Issue>,
+    #[serde(default)]
+    pub saved_at: Option<DateTime<Utc>>,
+}
+
+/// Where the board of a repo is cached, e.g.
+/// `~/.cache/zentui/mcobzarenco/zentui.json`.
+pub fn cache_path(repo: &RepoFullName) -> Result<PathBuf> {
+    let mut path = dirs::cache_dir()
+        .ok_or_else(|| anyhow!("Could not get path to the user's cache directory"))?;
+    path.push("zentui");
+    path.extend(repo.0.split('/'));
+    path.set_extension("json");
+    Ok(path)
+}
+
+/// Reads the cached board, if there is one. A cache which can't be read,
+/// e.g. written by an older version of zentui, is ignored.
+pub fn read_cache(path: impl AsRef<Path>) -> Option<BoardCache> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return None;
+    }
+    fs::read_to_string(path)
+        .with_context(|| format!("Could not read cache file `{}`", path.display()))
+        .and_then(|contents| {
+            serde_json::from_str(&contents)
+                .with_context(|| format!("Could not parse cache file `{}`", path.display()))
+        })
+        .map_err(|error| log::warn!("{:#}", error))
+        .ok()
+}
+
+/// Replaces the cached board in one go, so a crash mid-write doesn't leave a
+/// truncated cache behind.
+pub fn write_cache(path: impl AsRef<Path>, cache: &BoardCache) -> Result<()> {
+    let path = path.as_ref();
+    let parent = path
+        .parent()
+        .ok_or_else(|| anyhow!("Cache file `{}` has no directory", path.display()))?;
+    fs::create_dir_all(parent)
+        .with_context(|| format!("Could not create cache directory `{}`", parent.display()))?;
+    let contents = serde_json::to_string(cache)
+        .with_context(|| format!("Could not serialize cache to `{}`", path.display()))?;
+    let mut file = NamedTempFile::new_in(parent)
+        .with_context(|| format!("Could not write cache file `{}`", path.display()))?;
+    file.write_all(contents.as_bytes())
+        .with_context(|| format!("Could not write cache file `{}`", path.display()))?;
+    file.persist(path)
+        .with_context(|| format!("Could not write cache file `{}`", path.display()))?;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Context, Result};
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+use crate::terminal;
+
+/// Copies text to the system clipboard with the first clipboard tool found,
+/// falling back to asking the terminal to do it with an OSC 52 escape
+/// sequence, which also works over ssh.
+pub fn copy(text: &str) -> Result<()> {
+    for (program, args) in CLIPBOARD_COMMANDS.iter() {
+        if copy_with(program, args, text).is_ok() {
+            return Ok(());
+        }
+    }
+    copy_with_osc52(text)
+}
+
+fn copy_with(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Could not open stdin of `{}`", program))?
+        .write_all(text.as_bytes())?;
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("`{}` exited with {}", program, status))
+    }
+}
+
+fn copy_with_osc52(text: &str) -> Result<()> {
+    let mut stdout = io::stdout();
+    write!(
+        stdout,
+        "\x1b]52;c;{}\x07",
+        terminal::base64(text.as_bytes())
+    )
+    .and_then(|_| stdout.flush())
+    .context("Could not write to the terminal")
+}
+
+const CLIPBOARD_COMMANDS: [(&str, &[&str]); 4] = [
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+];
@@ -0,0 +1,193 @@
+use anyhow::{anyhow, Context, Result};
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::Level;
+
+use crate::logging::LogBuffer;
+
+/// A summary of what the app was doing, kept up to date by the app so it can
+/// be included in a crash report.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppSummary {
+    pub repo: String,
+    pub num_pipelines: usize,
+    pub num_issues: usize,
+    pub num_loaded_issues: usize,
+    pub num_failed_issues: usize,
+    pub num_pending_tasks: usize,
+    pub last_message: &'static str,
+}
+
+/// Writes a diagnostic bundle when zentui hits a fatal error, with secrets
+/// redacted so it can be attached to a bug report as is.
+#[derive(Clone, Debug, Default)]
+pub struct CrashReporter {
+    logs: Option<LogBuffer>,
+    secrets: Arc<Mutex<Vec<String>>>,
+    summary: Arc<Mutex<Option<AppSummary>>>,
+}
+
+impl CrashReporter {
+    pub fn new(logs: Option<LogBuffer>) -> Self {
+        Self {
+            logs,
+            ..Default::default()
+        }
+    }
+
+    /// Registers a value (e.g. an API token) to be redacted from the report.
+    pub fn add_secret(&self, secret: impl Into<String>) {
+        let secret = secret.into();
+        if secret.is_empty() {
+            return;
+        }
+        if let Ok(mut secrets) = self.secrets.lock() {
+            secrets.push(secret);
+        }
+    }
+
+    pub fn set_summary(&self, summary: AppSummary) {
+        if let Ok(mut current) = self.summary.lock() {
+            *current = Some(summary);
+        }
+    }
+
+    /// Writes a report about `error` to the local data directory and returns
+    /// the path to it.
+    pub fn write(&self, error: &str) -> Result<PathBuf> {
+        let path = report_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "Could not create crash report directory `{}`",
+                    parent.display()
+                )
+            })?;
+        }
+        File::create(&path)
+            .and_then(|mut file| file.write_all(self.redact(&self.report(error)).as_bytes()))
+            .with_context(|| format!("Could not write crash report `{}`", path.display()))?;
+        Ok(path)
+    }
+
+    fn report(&self, error: &str) -> String {
+        let mut report = String::new();
+        let _ = writeln!(report, "# Zentui crash report\n");
+        let _ = writeln!(report, "zentui {}", env!("CARGO_PKG_VERSION"));
+        let _ = writeln!(report, "zentui-client {}", zentui_client::VERSION);
+        let _ = writeln!(
+            report,
+            "{} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+
+        let _ = writeln!(report, "\n## Error\n\n{}", error);
+
+        let _ = writeln!(report, "\n## App state\n");
+        match self.summary.lock().ok().and_then(|summary| summary.clone()) {
+            Some(summary) => {
+                let _ = writeln!(report, "repo: {}", summary.repo);
+                let _ = writeln!(report, "pipelines: {}", summary.num_pipelines);
+                let _ = writeln!(
+                    report,
+                    "issues: {} ({} loaded, {} failed)",
+                    summary.num_issues, summary.num_loaded_issues, summary.num_failed_issues
+                );
+                let _ = writeln!(report, "pending tasks: {}", summary.num_pending_tasks);
+                let _ = writeln!(report, "last message: {}", summary.last_message);
+            }
+            None => {
+                let _ = writeln!(report, "The app hadn't started yet.");
+            }
+        }
+
+        let records = self
+            .logs
+            .as_ref()
+            .map(|logs| logs.records(Level::TRACE))
+            .unwrap_or_default();
+        let _ = writeln!(report, "\n## API responses\n");
+        let api_records: Vec<_> = records
+            .iter()
+            .filter(|record| record.target.starts_with("zentui_client"))
+            .collect();
+        for record in &api_records[api_records.len().saturating_sub(NUM_API_RECORDS)..] {
+            let _ = writeln!(report, "{}", record.message);
+        }
+
+        let _ = writeln!(report, "\n## Last log lines\n");
+        if self.logs.is_none() {
+            let _ = writeln!(
+                report,
+                "Logging was off, rerun with `--log` for more details."
+            );
+        }
+        for record in records.iter().rev().take(NUM_LOG_RECORDS).rev() {
+            let _ = writeln!(
+                report,
+                "{:<5} {} {}",
+                record.level.to_string(),
+                record.target,
+                record.message
+            );
+        }
+        report
+    }
+
+    fn redact(&self, report: &str) -> String {
+        let mut report = report.to_owned();
+        if let Ok(secrets) = self.secrets.lock() {
+            for secret in secrets.iter() {
+                report = report.replace(secret.as_str(), REDACTED);
+            }
+        }
+        // Github tokens have well known prefixes, catch any we weren't told about
+        report
+            .lines()
+            .map(|line| {
+                line.split(' ')
+                    .map(|word| {
+                        let trimmed =
+                            word.trim_start_matches(|character: char| !character.is_alphanumeric());
+                        if GITHUB_TOKEN_PREFIXES
+                            .iter()
+                            .any(|prefix| trimmed.starts_with(prefix))
+                        {
+                            REDACTED
+                        } else {
+                            word
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn report_path() -> Result<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    dirs::data_local_dir()
+        .ok_or_else(|| anyhow!("Could not get path to the user's local data directory"))
+        .map(|mut path| {
+            path.push("zentui");
+            path.push(format!("crash-{}.md", timestamp));
+            path
+        })
+}
+
+const REDACTED: &str = "[redacted]";
+const GITHUB_TOKEN_PREFIXES: [&str; 5] = ["ghp_", "gho_", "ghu_", "ghs_", "github_pat_"];
+const NUM_API_RECORDS: usize = 20;
+const NUM_LOG_RECORDS: usize = 100;
@@ -0,0 +1,60 @@
+use anyhow::{anyhow, Context, Result};
+use serde_derive::Serialize;
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use tempfile::NamedTempFile;
+
+/// The selected issue, as written to the focus-follow file for tools
+/// mirroring the selection, e.g. on a second screen while pairing.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct Selection {
+    pub repo: String,
+    pub number: usize,
+    pub title: Option<String>,
+    pub url: String,
+}
+
+/// Where the selection is written when the settings don't say.
+pub fn default_path() -> Result<PathBuf> {
+    let mut path = dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .ok_or_else(|| anyhow!("Could not get path to the user's runtime directory"))?;
+    path.push("zentui");
+    path.push("selection.json");
+    Ok(path)
+}
+
+/// Writes the selection as a single line of JSON, `null` when nothing is
+/// selected. The file is replaced in one go, so readers polling it never see
+/// a partial write.
+pub fn write_selection(path: impl AsRef<Path>, selection: Option<&Selection>) -> Result<()> {
+    let path = path.as_ref();
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(parent)
+        .with_context(|| format!("Could not create directory `{}`", parent.display()))?;
+    let mut contents = serde_json::to_string(&selection)
+        .with_context(|| format!("Could not serialize selection to `{}`", path.display()))?;
+    contents.push('\n');
+    let mut file = NamedTempFile::new_in(parent)
+        .with_context(|| format!("Could not write selection to `{}`", path.display()))?;
+    file.write_all(contents.as_bytes())
+        .with_context(|| format!("Could not write selection to `{}`", path.display()))?;
+    file.persist(path)
+        .with_context(|| format!("Could not write selection to `{}`", path.display()))?;
+    Ok(())
+}
+
+pub fn remove_selection(path: impl AsRef<Path>) -> Result<()> {
+    match fs::remove_file(path.as_ref()) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+            Err(error).with_context(|| format!("Could not remove `{}`", path.as_ref().display()))
+        }
+        _ => Ok(()),
+    }
+}
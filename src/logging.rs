@@ -0,0 +1,147 @@
+use anyhow::{anyhow, Context as _, Result};
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write},
+    fs::OpenOptions,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{
+    fmt as format,
+    layer::{Context, SubscriberExt},
+    util::SubscriberInitExt,
+    EnvFilter, Layer,
+};
+
+use crate::settings::{LogFormat, LogSettings};
+
+/// Starts writing structured logs to the file configured in the settings.
+/// Records from the `log` crate are forwarded too. The most recent records
+/// are also kept in memory, in the returned buffer, to be shown in the app.
+///
+/// The returned guard flushes buffered records when dropped, so it has to be
+/// kept alive until the app exits.
+pub fn configure(settings: &LogSettings, level: Option<&str>) -> Result<(WorkerGuard, LogBuffer)> {
+    let level = level.unwrap_or(&settings.level);
+    let filter = EnvFilter::try_new(level)
+        .with_context(|| format!("Invalid log level filter `{}`", level))?;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&settings.path)
+        .with_context(|| format!("Could not open log file `{}`", settings.path.display()))?;
+    let (writer, guard) = tracing_appender::non_blocking(file);
+    let buffer = LogBuffer::new(LOG_BUFFER_LINES);
+
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(buffer.clone());
+    let file_layer = format::layer().with_writer(writer).with_ansi(false);
+    match settings.format {
+        LogFormat::Pretty => registry.with(file_layer.pretty()).try_init(),
+        LogFormat::Json => registry.with(file_layer.json()).try_init(),
+    }
+    .map_err(|error| anyhow!("Could not configure logging: {}", error))?;
+
+    Ok((guard, buffer))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRecord {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+/// The last few log records, shared between the logger and the app.
+#[derive(Clone, Debug)]
+pub struct LogBuffer {
+    records: Arc<Mutex<VecDeque<LogRecord>>>,
+    generation: Arc<AtomicUsize>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            generation: Arc::new(AtomicUsize::new(0)),
+            capacity,
+        }
+    }
+
+    /// The buffered records at least as severe as `level`, oldest first.
+    pub fn records(&self, level: Level) -> Vec<LogRecord> {
+        self.records
+            .lock()
+            .map(|records| {
+                records
+                    .iter()
+                    .filter(|record| record.level <= level)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Incremented every time a record is added, to cheaply tell whether
+    /// anything changed since the records were last looked at.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Relaxed)
+    }
+}
+
+impl<SubscriberT: Subscriber> Layer<SubscriberT> for LogBuffer {
+    fn on_event(&self, event: &Event<'_>, _context: Context<'_, SubscriberT>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let record = LogRecord {
+            level: *event.metadata().level(),
+            target: event.metadata().target().to_owned(),
+            message: visitor.finish(),
+        };
+        if let Ok(mut records) = self.records.lock() {
+            if records.len() == self.capacity {
+                records.pop_front();
+            }
+            records.push_back(record);
+            self.generation.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Formats an event as its message followed by any other fields.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        if self.fields.is_empty() {
+            self.message
+        } else {
+            format!("{}{}", self.message, self.fields)
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let _ = if field.name() == "message" {
+            write!(self.message, "{:?}", value)
+        } else {
+            write!(self.fields, " {}={:?}", field.name(), value)
+        };
+    }
+}
+
+const LOG_BUFFER_LINES: usize = 1000;
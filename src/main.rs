@@ -1,20 +1,37 @@
 mod app;
+mod cache;
+mod clipboard;
+mod crash;
 mod credentials;
 mod edit;
-mod github;
+mod follow;
+mod logging;
+mod project;
 mod settings;
-mod zenhub;
+mod state;
+mod stats;
+mod terminal;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
 use clap::Clap;
-use flexi_logger::{opt_format, Logger};
-use std::path::PathBuf;
+use std::{
+    panic,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 use tokio::runtime::Builder as RuntimeBuilder;
+use zentui_client::{attachment, github, webhook, zenhub};
 use zi::{self, frontend::crossterm, layout, App as ZiApp};
 
 use crate::{
     app::{App, Properties},
+    attachment::Client as AttachmentClient,
+    crash::CrashReporter,
     github::{Client as GithubClient, RepoFullName, Token as GithubToken},
+    logging::LogBuffer,
+    settings::{Settings, StartupView},
+    webhook::Client as WebhookClient,
     zenhub::{Client as ZenhubClient, Token as ZenhubToken},
 };
 
@@ -24,55 +41,105 @@ struct Args {
     /// Zenhub token to use.
     zenhub_token: Option<ZenhubToken>,
 
+    #[clap(long = "zenhub-endpoint")]
+    /// Base URL of the Zenhub API, e.g. `https://zenhub.example.com/api` for Zenhub Enterprise;
+    /// overrides `zenhub_endpoint` in the settings.
+    zenhub_endpoint: Option<String>,
+
     #[clap(long = "github-token")]
     /// Github token (a personal access token, it should have the `repo` scope enabled).
     github_token: Option<GithubToken>,
 
     #[clap(long = "settings-path", parse(from_os_str))]
-    /// Path to the configuration file. It's usually ~/.config/zee on Linux.
+    /// Path to the configuration file. It's usually ~/.config/zentui/settings.toml on Linux.
     settings_path: Option<PathBuf>,
 
     #[clap(long = "create-settings")]
-    /// Writes the default configuration to file, if the file doesn't exist
+    /// Writes the default configuration to file, if the file doesn't exist, and exits
     create_settings: bool,
 
     #[clap(long = "log")]
-    /// Enable debug logging to `zentui.log` file
+    /// Enable logging to the file configured in the settings (`zentui.log` by default)
     enable_logging: bool,
 
+    #[clap(long = "log-level")]
+    /// Log filter in `RUST_LOG` syntax, e.g. `info,zentui_client=debug`; implies `--log`
+    log_level: Option<String>,
+
+    #[clap(long = "view")]
+    /// View to open: board, my-work, triage, epics or notifications; overrides `startup_view` in
+    /// the settings.
+    view: Option<StartupView>,
+
+    #[clap(long = "workspace")]
+    /// Name or id of the Zenhub workspace whose board to open; defaults to the one picked last
+    /// time, or else the oldest workspace with the repository.
+    workspace: Option<String>,
+
     #[clap(name = "repository")]
-    /// Repository to open; the oldest existing Zenhub board will be used.
-    repository: RepoFullName,
-}
+    /// Repository to open; the oldest existing Zenhub board will be used. Defaults to the last
+    /// repository opened.
+    repository: Option<RepoFullName>,
 
-fn configure_logging() -> Result<()> {
-    Logger::with_env_or_str("myprog=debug, mylib=debug")
-        .log_to_file()
-        .format(opt_format)
-        .suppress_timestamp()
-        .start()
-        .map_err(anyhow::Error::from)?;
-    Ok(())
+    #[clap(subcommand)]
+    command: Option<Command>,
 }
 
-fn start_app() -> Result<()> {
-    let args = Args::parse();
-    if args.enable_logging {
-        configure_logging()?;
-    }
+#[derive(Debug, Clap)]
+enum Command {
+    /// Show your weekly activity in zentui. The stats are only stored locally.
+    Stats,
+}
 
+fn start_app(
+    args: Args,
+    settings: Settings,
+    settings_path: Option<PathBuf>,
+    logs: Option<LogBuffer>,
+    crash_reporter: CrashReporter,
+) -> Result<()> {
+    let state_path = state::state_path()
+        .map_err(|error| log::warn!("{}", error))
+        .ok();
+    let stats_path = stats::stats_path()
+        .map_err(|error| log::warn!("{}", error))
+        .ok();
+    let mut repository = match args.repository {
+        Some(repository) => repository,
+        None => state_path
+            .as_ref()
+            .map_or_else(Default::default, state::read_state)
+            .last_repos()
+            .first()
+            .map(|full_name| RepoFullName((*full_name).to_owned()))
+            .ok_or_else(|| anyhow!("No repository given, e.g. `zentui mcobzarenco/zentui`"))?,
+    };
+    // Checked before asking for tokens, so a typo doesn't cost a round trip
+    let zenhub_endpoint = args
+        .zenhub_endpoint
+        .as_deref()
+        .or_else(|| settings.zenhub_endpoint.as_deref())
+        .map(zenhub::parse_endpoint)
+        .transpose()?;
     let github_token = credentials::from_arg_keyring_or_stdin(args.github_token)?;
     let zenhub_token = credentials::from_arg_keyring_or_stdin(args.zenhub_token)?;
+    crash_reporter.add_secret(github_token.0.as_str());
+    crash_reporter.add_secret(zenhub_token.0.as_str());
+    crash_reporter.add_secret(settings.token.as_str());
+    for webhook in settings.webhooks.iter() {
+        crash_reporter.add_secret(webhook.url.as_str());
+    }
 
-    // Read the current settings. If we cannot for any reason, we'll use the
-    // default ones -- ensure the editor opens in any environment.
-    let settings = args
-        .settings_path
-        .or_else(|| settings::settings_path().map(Some).unwrap_or(None))
-        .map_or_else(Default::default, settings::read_settings);
-
-    let github_client = GithubClient::new(github_token)?;
-    let zenhub_client = ZenhubClient::new(zenhub_token)?;
+    let github_client = Arc::new(GithubClient::new(
+        github_token,
+        settings.max_concurrent_requests,
+    )?);
+    let zenhub_client = Arc::new(match zenhub_endpoint {
+        Some(endpoint) => ZenhubClient::with_endpoint(zenhub_token, endpoint)?,
+        None => ZenhubClient::new(zenhub_token)?,
+    });
+    let webhook_client = Arc::new(WebhookClient::new()?);
+    let attachment_client = Arc::new(AttachmentClient::new()?);
 
     let mut async_runtime = RuntimeBuilder::new()
         .threaded_scheduler()
@@ -80,36 +147,142 @@ fn start_app() -> Result<()> {
         .core_threads(1)
         .build()?;
 
-    let repo = async_runtime.block_on(github_client.get_repo(&args.repository))?;
-
-    //     // Create a default settings file if requested by the user
-    //     if args.create_settings {
-    //         let settings_path = settings::settings_path()?;
-    //         if !settings_path.exists() {
-    //             settings::create_default_file(&settings_path)?;
-    //         } else {
-    //             log::warn!(
-    //                 "Default settings file won't be created; a file already exists `{}`",
-    //                 settings_path.display()
-    //             );
-    //         }
-    //     }
-
-    let mut app = ZiApp::new(layout::component::<App>(Properties {
-        async_runtime: async_runtime.handle().clone(),
-        github_client: github_client.into(),
-        zenhub_client: zenhub_client.into(),
-        repo,
-    }));
+    let user = async_runtime.block_on(github_client.get_current_user())?;
+    // The workspace given on the command line is only meant for the first board
+    let mut workspace = args.workspace;
+
+    // The app exits to switch repositories, reopen it on the next one
+    loop {
+        let repo = async_runtime.block_on(github_client.get_repo(&repository))?;
+        let cache_path = cache::cache_path(&repo.full_name)
+            .map_err(|error| log::warn!("{}", error))
+            .ok();
+        let mut state = state_path
+            .as_ref()
+            .map_or_else(Default::default, state::read_state);
+        state.record_visit(&repo.full_name.0, Utc::now().timestamp());
+        if let Some(path) = state_path.as_ref() {
+            if let Err(error) = state::write_state(path, &state) {
+                log::error!("{:?}", error);
+            }
+        }
+        let stats = stats_path
+            .as_ref()
+            .map_or_else(Default::default, stats::read_stats);
+
+        let next_repo = Arc::new(Mutex::new(None));
+        let mut app = ZiApp::new(layout::component::<App>(Properties {
+            async_runtime: async_runtime.handle().clone(),
+            github_client: github_client.clone(),
+            zenhub_client: zenhub_client.clone(),
+            webhook_client: webhook_client.clone(),
+            attachment_client: attachment_client.clone(),
+            repo,
+            user: user.clone(),
+            settings: settings.clone(),
+            settings_path: settings_path.clone(),
+            state,
+            state_path: state_path.clone(),
+            stats,
+            stats_path: stats_path.clone(),
+            cache_path,
+            logs: logs.clone(),
+            crash_reporter: crash_reporter.clone(),
+            next_repo: next_repo.clone(),
+            workspace: workspace.take(),
+            startup_view: args.view.unwrap_or(settings.startup_view),
+        }));
 
-    // Start the UI loop
-    app.run_event_loop(zi::frontend::crossterm::incremental()?)?;
-    Ok(())
+        // Start the UI loop. Errors before it are mistakes in the arguments,
+        // settings or credentials, only failures of the running app are
+        // worth a crash report
+        app.run_event_loop(zi::frontend::crossterm::incremental()?)
+            .map_err(|error| {
+                report_crash(&crash_reporter, &format!("{:?}", error));
+                error
+            })?;
+
+        let next_repo = next_repo
+            .lock()
+            .expect("next repo lock is not poisoned")
+            .take();
+        match next_repo {
+            Some(next_repo) => repository = next_repo,
+            None => return Ok(()),
+        }
+    }
 }
 
 fn main() -> Result<()> {
-    start_app().map_err(|error| {
+    let args = Args::parse();
+    if let Some(Command::Stats) = args.command {
+        let stats = stats::read_stats(stats::stats_path()?);
+        print!("{}", stats.report(stats::REPORT_WEEKS));
+        return Ok(());
+    }
+
+    // Read the current settings. If we cannot for any reason, we'll use the
+    // default ones -- ensure the editor opens in any environment.
+    let settings_path = args
+        .settings_path
+        .clone()
+        .or_else(|| settings::settings_path().map(Some).unwrap_or(None));
+
+    // Create a default settings file if requested by the user
+    if args.create_settings {
+        let settings_path = settings_path.as_ref().ok_or_else(|| {
+            anyhow!("Could not find where to write the settings, use --settings-path")
+        })?;
+        if settings_path.exists() {
+            eprintln!(
+                "Default settings file won't be created; a file already exists `{}`",
+                settings_path.display()
+            );
+        } else {
+            settings::create_default_file(settings_path)?;
+            println!(
+                "Wrote the default settings to `{}`",
+                settings_path.display()
+            );
+        }
+        return Ok(());
+    }
+
+    let settings = settings_path
+        .as_ref()
+        .map_or_else(Default::default, settings::read_settings);
+
+    // Keep the guard alive until the app exits so buffered records are flushed
+    let (_log_guard, logs) = if args.enable_logging || args.log_level.is_some() {
+        let (guard, logs) = logging::configure(&settings.log, args.log_level.as_deref())?;
+        (Some(guard), Some(logs))
+    } else {
+        (None, None)
+    };
+
+    let crash_reporter = CrashReporter::new(logs.clone());
+    let panic_reporter = crash_reporter.clone();
+    let default_panic_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_panic_hook(info);
+        report_crash(&panic_reporter, &info.to_string());
+    }));
+
+    start_app(args, settings, settings_path, logs, crash_reporter).map_err(|error| {
         log::error!("Zentui exited with: {}", error);
         error
     })
 }
+
+fn report_crash(crash_reporter: &CrashReporter, error: &str) {
+    match crash_reporter.write(error) {
+        Ok(path) => eprintln!(
+            "A crash report was written to `{}`, please attach it when reporting a bug.",
+            terminal::hyperlink(
+                &format!("file://{}", path.display()),
+                &path.display().to_string()
+            )
+        ),
+        Err(error) => log::error!("{:?}", error),
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::{anyhow, Context, Result};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::github::{Issue, RepoFullName};
+
+/// Opens the editor in the local checkout of a repository, together with a
+/// scratch file about the issue. When a git worktree has a branch for the
+/// issue (its name mentions the issue number), that worktree is opened instead
+/// of the main checkout. Returns the directory which was opened.
+pub fn open_in_editor(
+    editor: Option<&str>,
+    repo_path: &Path,
+    repo: &RepoFullName,
+    issue: &Issue,
+) -> Result<PathBuf> {
+    let editor = editor
+        .map(str::to_owned)
+        .or_else(|| env::var("VISUAL").ok())
+        .or_else(|| env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+        .ok_or_else(|| anyhow!("No editor configured, set `editor` in the settings"))?;
+    let project_path = issue_worktree(repo_path, issue).unwrap_or_else(|| repo_path.to_owned());
+    let scratch_path = write_scratch_file(repo, issue)?;
+
+    let mut words = editor.split_whitespace();
+    let program = words.next().expect("editor is not empty");
+    let status = Command::new(program)
+        .args(words)
+        .arg(&project_path)
+        .arg(&scratch_path)
+        .current_dir(&project_path)
+        .status()
+        .with_context(|| format!("Could not run editor `{}`", editor))?;
+    if !status.success() {
+        return Err(anyhow!("Editor `{}` exited with {}", editor, status));
+    }
+    Ok(project_path)
+}
+
+/// Writes a commit message template referencing the issue into the git
+/// directory of the local checkout and makes it the repo's `commit.template`,
+/// so the next commit starts with `Fixes #<n>: <title>`. Returns the path to
+/// the template.
+pub fn write_commit_template(repo_path: &Path, issue: &Issue) -> Result<PathBuf> {
+    // Relative to the checkout, unless the repo is a worktree
+    let git_dir = git(repo_path, &["rev-parse", "--git-common-dir"])?;
+    let mut path = repo_path.join(git_dir.trim());
+    path.push("COMMIT_TEMPLATE");
+    fs::write(
+        &path,
+        format!("Fixes #{}: {}\n", issue.number.0, issue.title),
+    )
+    .with_context(|| format!("Could not write commit template `{}`", path.display()))?;
+    git(
+        repo_path,
+        &["config", "commit.template", &path.to_string_lossy()],
+    )?;
+    Ok(path)
+}
+
+/// Runs git in a repository and returns what it printed.
+fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(args)
+        .output()
+        .context("Could not run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The worktree checked out on a branch for the issue, e.g. `123-fix-login`
+/// or `fix/issue-123`, if there is one.
+fn issue_worktree(repo_path: &Path, issue: &Issue) -> Option<PathBuf> {
+    let worktrees = git(repo_path, &["worktree", "list", "--porcelain"])
+        .map_err(|error| log::warn!("Could not list git worktrees: {}", error))
+        .ok()?;
+
+    let issue_number = issue.number.0.to_string();
+    let mut worktree = None;
+    for line in worktrees.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            worktree = Some(PathBuf::from(path));
+        } else if let Some(branch) = line.strip_prefix("branch ") {
+            let branch = branch.trim_start_matches("refs/heads/");
+            if branch
+                .split(|character: char| !character.is_ascii_alphanumeric())
+                .any(|word| word == issue_number)
+            {
+                return worktree;
+            }
+        }
+    }
+    None
+}
+
+/// Writes a scratch file for notes about the issue, unless one exists already
+/// from a previous session.
+fn write_scratch_file(repo: &RepoFullName, issue: &Issue) -> Result<PathBuf> {
+    let mut path = dirs::data_local_dir()
+        .ok_or_else(|| anyhow!("Could not get path to the user's local data directory"))?;
+    path.push("zentui");
+    path.push("scratch");
+    path.push(repo.0.replace('/', "-"));
+    fs::create_dir_all(&path)
+        .with_context(|| format!("Could not create scratch directory `{}`", path.display()))?;
+
+    path.push(format!("{}.md", issue.number.0));
+    if !path.exists() {
+        fs::write(
+            &path,
+            format!(
+                "#{}: {}\n\nhttps://github.com/{}/issues/{}\n",
+                issue.number.0, issue.title, repo.0, issue.number.0
+            ),
+        )
+        .with_context(|| format!("Could not write scratch file `{}`", path.display()))?;
+    }
+    Ok(path)
+}
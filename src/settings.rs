@@ -1,21 +1,441 @@
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Datelike, Local, Timelike};
 use dirs;
 use serde_derive::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     fs::{self, File},
     io::{Read, Write},
     path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
 };
 
+use crate::{terminal::GraphicsProtocol, webhook::WebhookKind};
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Settings {
+    #[serde(default)]
     pub token: String,
+    /// The base URL of the Zenhub API, for Zenhub Enterprise installations,
+    /// e.g. `https://zenhub.example.com/api`. Defaults to Zenhub's cloud API.
+    #[serde(default)]
+    pub zenhub_endpoint: Option<String>,
+    /// The command used to open projects, e.g. `code` or `nvim`. Falls back to
+    /// `$VISUAL` and `$EDITOR`.
+    #[serde(default)]
+    pub editor: Option<String>,
+    /// How to draw images in the terminal, detected from the environment
+    /// when not set.
+    #[serde(default)]
+    pub graphics_protocol: Option<GraphicsProtocol>,
+    /// The height of issue cards, in lines. Cards shrink on short terminals.
+    #[serde(default = "default_card_height")]
+    pub card_height: usize,
+    /// The most lines a card's title takes, so long titles leave room for
+    /// labels. Longer titles lose their middle to an ellipsis, `z` shows the
+    /// full title. `0` shows titles in full.
+    #[serde(default = "default_max_title_lines")]
+    pub max_title_lines: usize,
+    /// How narrow a pipeline's column may get. When not every pipeline fits,
+    /// the board scrolls sideways to keep the selected one in view. `0` fits
+    /// every pipeline however narrow.
+    #[serde(default = "default_min_column_width")]
+    pub min_column_width: usize,
+    /// How much of each card is shown when zentui starts, cycled with `g d`.
+    #[serde(default)]
+    pub density: Density,
+    /// How the selected card stands out on top of its brighter border, any
+    /// of `inverse`, `bold-title`, `marker` and `tint`, e.g.
+    /// `focus_emphasis = ["bold-title", "marker"]`.
+    #[serde(default)]
+    pub focus_emphasis: Vec<FocusEmphasis>,
+    /// Whether children of an epic shown in another pipeline are folded into
+    /// the epic's card when zentui starts, toggled with `e f`.
+    #[serde(default)]
+    pub fold_epic_children: bool,
+    /// What to show when zentui starts.
+    #[serde(default)]
+    pub startup_view: StartupView,
+    /// Where `n` puts new issues in their pipeline, one of `top`, `bottom`
+    /// and `after-selected`. `C-x n` asks each time.
+    #[serde(default)]
+    pub new_issue_position: NewIssuePosition,
+    /// What `q` does on the board, one of `once`, `twice` and `off`.
+    /// `C-x C-c` always quits.
+    #[serde(default)]
+    pub quit_with_q: QuitKey,
+    /// What the status row shows, in order, and in which colours. The
+    /// `message` segment takes the width the others leave.
+    #[serde(default = "default_status_segments")]
+    pub status_segments: Vec<StatusSegment>,
+    /// How often to reload the board in the background, in seconds, at
+    /// least `MIN_AUTO_REFRESH_SECS`. When not set, the board is only checked
+    /// for cards moved elsewhere every 30 seconds and reloaded with `g r`.
+    #[serde(default)]
+    pub auto_refresh_secs: Option<u64>,
+    /// Where the selected issue is written while focus-follow mode is on
+    /// (`g f`), for tools mirroring the selection. Defaults to
+    /// `selection.json` in zentui's runtime directory.
+    #[serde(default)]
+    pub focus_follow_path: Option<PathBuf>,
+    /// Repos to keep at hand, e.g. `["mcobzarenco/zentui"]`. They're listed
+    /// first when switching repos, and their boards are fetched in the
+    /// background once the board in view has loaded, so switching to them is
+    /// instant.
+    #[serde(default)]
+    pub pinned_repos: Vec<String>,
+    /// How many boards of pinned repos are fetched at once; `0` turns
+    /// preloading off.
+    #[serde(default = "default_max_concurrent_preloads")]
+    pub max_concurrent_preloads: usize,
+    /// How many requests to Github are sent at once; the others wait their
+    /// turn.
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// When you work. Outside working hours the board is polled and
+    /// refreshed less often; it always is at the same pace when not set.
+    #[serde(default)]
+    pub working_hours: Option<WorkingHours>,
+    /// What the last line of issue cards shows, e.g.
+    /// `{assignees}[ · {estimate}pt] · {age}`. The placeholders are `number`,
+    /// `author`, `assignees`, `estimate`, `age`, `updated`, `comments`,
+    /// `reactions`, `state` and `labels`; text in `[...]` is left out when a
+    /// placeholder in it is empty. The footer of a stale issue, see `stale`,
+    /// stands out. Cards have no footer when not set.
+    #[serde(default)]
+    pub card_footer: Option<String>,
+    /// The base16 colour scheme to use, by the name of a scheme file in the
+    /// `themes` directory next to this file, e.g. `ocean` for
+    /// `themes/ocean.yaml`. Switch between them with `C-x t`.
+    #[serde(default)]
+    pub scheme: Option<String>,
+    /// Overrides of the colours of the theme, as hex strings, e.g.
+    /// `base0d = "#00bcd4"`.
+    #[serde(default)]
+    pub theme: BTreeMap<String, String>,
+    #[serde(default)]
+    pub bots: BotSettings,
+    #[serde(default)]
+    pub log: LogSettings,
+    #[serde(default)]
+    pub estimate_labels: EstimateLabelSettings,
+    /// The estimates `+` and `-` step through, in story points.
+    #[serde(default = "default_estimate_sequence")]
+    pub estimate_sequence: Vec<f64>,
+    #[serde(default)]
+    pub pipeline_labels: Vec<PipelineLabel>,
+    #[serde(default)]
+    pub auto_assign: Vec<AutoAssign>,
+    #[serde(default)]
+    pub wip_limits: Vec<WipLimit>,
+    #[serde(default)]
+    pub sla: Vec<SlaRule>,
+    #[serde(default)]
+    pub local_repos: Vec<LocalRepo>,
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    #[serde(default = "default_triage_actions")]
+    pub triage: Vec<TriageAction>,
+    #[serde(default)]
+    pub stale: StaleSettings,
+    #[serde(default)]
+    pub changelog: ChangelogSettings,
+}
+
+impl Settings {
+    /// Rejects settings which parse but can't work, e.g. no requests allowed
+    /// in flight.
+    fn validate(self) -> Result<Self> {
+        if self.max_concurrent_requests == 0 {
+            return Err(anyhow!("`max_concurrent_requests` must be at least 1"));
+        }
+        match self.auto_refresh_secs {
+            Some(secs) if secs < MIN_AUTO_REFRESH_SECS => {
+                return Err(anyhow!(
+                    "`auto_refresh_secs` must be at least {}",
+                    MIN_AUTO_REFRESH_SECS
+                ));
+            }
+            _ => {}
+        }
+        // Every label starts with an empty prefix, so syncing would remove them all
+        if self.estimate_labels.sync && self.estimate_labels.prefix.trim().is_empty() {
+            return Err(anyhow!(
+                "`estimate_labels.prefix` can't be empty while `estimate_labels.sync` is on"
+            ));
+        }
+        if let Some(ref working_hours) = self.working_hours {
+            // An empty window would count every hour as out of hours
+            if working_hours.start_hour == working_hours.end_hour {
+                return Err(anyhow!(
+                    "`working_hours` must end at a different hour than they start"
+                ));
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn triage_action(&self, key: char) -> Option<&TriageAction> {
+        self.triage.iter().find(|action| action.key == key)
+    }
+
+    /// The estimate one step up or down the configured sequence from
+    /// `current`. Stepping up from no estimate starts the sequence, stepping
+    /// down from its first value clears the estimate.
+    pub fn step_estimate(&self, current: Option<f64>, up: bool) -> Option<f64> {
+        let sequence = self.estimate_sequence.iter().copied();
+        let smallest =
+            |min: Option<f64>, points: f64| Some(min.map_or(points, |min| min.min(points)));
+        let largest =
+            |max: Option<f64>, points: f64| Some(max.map_or(points, |max| max.max(points)));
+        match (current, up) {
+            (None, true) => sequence.fold(None, smallest),
+            (None, false) => None,
+            (Some(current), true) => sequence
+                .filter(|&points| points > current)
+                .fold(None, smallest)
+                .or(Some(current)),
+            (Some(current), false) => sequence
+                .filter(|&points| points < current)
+                .fold(None, largest),
+        }
+    }
+
+    pub fn local_repo(&self, full_name: &str) -> Option<&LocalRepo> {
+        self.local_repos
+            .iter()
+            .find(|local_repo| local_repo.repo.eq_ignore_ascii_case(full_name))
+    }
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Settings {
             token: String::new(),
+            zenhub_endpoint: None,
+            editor: None,
+            graphics_protocol: None,
+            card_height: default_card_height(),
+            max_title_lines: default_max_title_lines(),
+            min_column_width: default_min_column_width(),
+            density: Density::default(),
+            focus_emphasis: Vec::new(),
+            fold_epic_children: false,
+            startup_view: StartupView::default(),
+            new_issue_position: NewIssuePosition::default(),
+            quit_with_q: QuitKey::default(),
+            status_segments: default_status_segments(),
+            auto_refresh_secs: None,
+            pinned_repos: Vec::new(),
+            max_concurrent_preloads: default_max_concurrent_preloads(),
+            max_concurrent_requests: default_max_concurrent_requests(),
+            working_hours: None,
+            focus_follow_path: None,
+            card_footer: None,
+            scheme: None,
+            theme: BTreeMap::new(),
+            bots: BotSettings::default(),
+            log: LogSettings::default(),
+            estimate_labels: EstimateLabelSettings::default(),
+            estimate_sequence: default_estimate_sequence(),
+            pipeline_labels: Vec::new(),
+            auto_assign: Vec::new(),
+            wip_limits: Vec::new(),
+            sla: Vec::new(),
+            local_repos: Vec::new(),
+            webhooks: Vec::new(),
+            triage: default_triage_actions(),
+            stale: StaleSettings::default(),
+            changelog: ChangelogSettings::default(),
+        }
+    }
+}
+
+/// How much of each card is shown. All pipelines share the same density.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Density {
+    /// The number and title of the issue on a single line.
+    Minimal,
+    /// The title and labels, and the footer if there is one.
+    Normal,
+    /// Taller cards with the start of the issue's description as well.
+    Detailed,
+}
+
+impl Density {
+    /// The density after this one when cycling through them.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Minimal => Self::Normal,
+            Self::Normal => Self::Detailed,
+            Self::Detailed => Self::Minimal,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Minimal => "minimal",
+            Self::Normal => "normal",
+            Self::Detailed => "detailed",
+        }
+    }
+}
+
+impl Default for Density {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+/// A way of making the selected card stand out, combined with the others
+/// chosen in the settings.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FocusEmphasis {
+    /// The card's title in inverse video.
+    Inverse,
+    /// The card's title in bold.
+    BoldTitle,
+    /// A bar in the column left of the card.
+    Marker,
+    /// A lighter background behind the card's content.
+    Tint,
+}
+
+/// The view zentui opens into, e.g. a manager may start with the triage of
+/// new issues while an engineer starts with their own work.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartupView {
+    /// The whole board.
+    Board,
+    /// Only the issues assigned to you.
+    MyWork,
+    /// The New Issues pipeline, with the detail pane open.
+    Triage,
+    /// Only the epics.
+    Epics,
+    /// Your unread Github notifications about the repo.
+    Notifications,
+}
+
+impl Default for StartupView {
+    fn default() -> Self {
+        Self::Board
+    }
+}
+
+impl std::str::FromStr for StartupView {
+    type Err = String;
+
+    fn from_str(view: &str) -> std::result::Result<Self, Self::Err> {
+        match view {
+            "board" => Ok(Self::Board),
+            "my-work" => Ok(Self::MyWork),
+            "triage" => Ok(Self::Triage),
+            "epics" => Ok(Self::Epics),
+            "notifications" => Ok(Self::Notifications),
+            _ => Err(format!(
+                "unknown view `{}`, expected one of board, my-work, triage, epics or notifications",
+                view
+            )),
+        }
+    }
+}
+
+/// Where a new issue goes in the pipeline it's created in.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum NewIssuePosition {
+    Top,
+    Bottom,
+    /// Right below the selected card, or at the top of an empty pipeline.
+    AfterSelected,
+}
+
+impl Default for NewIssuePosition {
+    fn default() -> Self {
+        Self::Top
+    }
+}
+
+/// A segment of the status row. Its colours default to the scheme's and can
+/// be set to a colour of the scheme by name, e.g. `base0d`, or as a hex
+/// string, e.g. `#00bcd4`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StatusSegment {
+    pub segment: StatusSegmentKind,
+    #[serde(default)]
+    pub fg: Option<String>,
+    #[serde(default)]
+    pub bg: Option<String>,
+    #[serde(default)]
+    pub bold: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusSegmentKind {
+    /// What the keys act on: the board, a search, an overlay, a picker or
+    /// the marked cards.
+    Mode,
+    Repo,
+    /// The filters the board is narrowed by, when there are any.
+    Filter,
+    /// Spins while requests to Github or Zenhub are pending.
+    Sync,
+    /// The Github requests left until the rate limit resets.
+    RateLimit,
+    /// The latest status message.
+    Message,
+}
+
+/// How `q` quits zentui. Pressing it twice by default, as quitting by
+/// accident loses the filters and selection.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuitKey {
+    /// Quits straight away.
+    Once,
+    /// Quits when pressed again within a couple of seconds.
+    Twice,
+    /// `q` does nothing.
+    Off,
+}
+
+impl Default for QuitKey {
+    fn default() -> Self {
+        Self::Twice
+    }
+}
+
+/// Issues and pull requests opened by bots are folded into a single card per
+/// pipeline, so they don't bury the work of humans.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BotSettings {
+    #[serde(default)]
+    pub fold: bool,
+    #[serde(default)]
+    pub accounts: Vec<String>,
+}
+
+impl BotSettings {
+    pub fn is_bot(&self, login: &str) -> bool {
+        self.fold && self.accounts.iter().any(|account| account == login)
+    }
+}
+
+impl Default for BotSettings {
+    fn default() -> Self {
+        Self {
+            fold: true,
+            accounts: vec![
+                "dependabot[bot]".into(),
+                "dependabot-preview[bot]".into(),
+                "renovate[bot]".into(),
+            ],
         }
     }
 }
@@ -24,7 +444,7 @@ pub fn settings_path() -> Result<PathBuf> {
     let mut path = dirs::config_dir()
         .ok_or_else(|| anyhow!("Could not get path to the user's config directory"))
         .map(|mut config_dir| {
-            config_dir.push("zee");
+            config_dir.push("zentui");
             config_dir
         })?;
     path.push("settings.toml");
@@ -33,22 +453,7 @@ pub fn settings_path() -> Result<PathBuf> {
 
 pub fn read_settings(path: impl AsRef<Path>) -> Settings {
     if path.as_ref().exists() {
-        File::open(path.as_ref())
-            .and_then(|mut file| {
-                let mut contents = String::new();
-                file.read_to_string(&mut contents)?;
-                Ok(contents)
-            })
-            .with_context(|| format!("Could not read settings file `{}`", path.as_ref().display()))
-            .and_then(|contents| {
-                log::info!("Reading settings file `{}`", path.as_ref().display());
-                toml::de::from_str(&contents).with_context(|| {
-                    format!(
-                        "Could not parse settings file `{}`",
-                        path.as_ref().display(),
-                    )
-                })
-            })
+        try_read_settings(path)
             .map_err(|err| log::error!("{}", err))
             .unwrap_or_else(|_| Default::default())
     } else {
@@ -56,21 +461,546 @@ pub fn read_settings(path: impl AsRef<Path>) -> Settings {
     }
 }
 
+/// Reads the settings, failing rather than falling back to the defaults.
+pub fn try_read_settings(path: impl AsRef<Path>) -> Result<Settings> {
+    File::open(path.as_ref())
+        .and_then(|mut file| {
+            let mut contents = String::new();
+            file.read_to_string(&mut contents)?;
+            Ok(contents)
+        })
+        .with_context(|| format!("Could not read settings file `{}`", path.as_ref().display()))
+        .and_then(|contents| {
+            log::info!("Reading settings file `{}`", path.as_ref().display());
+            toml::de::from_str(&contents).with_context(|| {
+                format!(
+                    "Could not parse settings file `{}`",
+                    path.as_ref().display(),
+                )
+            })
+        })
+        .and_then(|settings: Settings| {
+            settings
+                .validate()
+                .with_context(|| format!("Invalid settings file `{}`", path.as_ref().display()))
+        })
+}
+
+/// Calls `on_change` whenever the settings file is modified, until it returns
+/// `false`. Polls the modification time, so it should run on its own thread.
+pub fn watch(path: PathBuf, on_change: impl Fn() -> bool) {
+    let modified = |path: &Path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+    let mut last_modified: Option<SystemTime> = modified(&path);
+    loop {
+        thread::sleep(SETTINGS_POLL_INTERVAL);
+        let current = modified(&path);
+        if current != last_modified {
+            last_modified = current;
+            if !on_change() {
+                return;
+            }
+        }
+    }
+}
+
+fn default_card_height() -> usize {
+    10
+}
+
+fn default_max_title_lines() -> usize {
+    2
+}
+
+fn default_min_column_width() -> usize {
+    24
+}
+
+fn default_status_segments() -> Vec<StatusSegment> {
+    [
+        StatusSegmentKind::Sync,
+        StatusSegmentKind::Message,
+        StatusSegmentKind::Filter,
+    ]
+    .iter()
+    .map(|&segment| StatusSegment {
+        segment,
+        fg: None,
+        bg: None,
+        bold: None,
+    })
+    .collect()
+}
+
+fn default_max_concurrent_preloads() -> usize {
+    2
+}
+
+fn default_max_concurrent_requests() -> usize {
+    8
+}
+
+fn default_estimate_sequence() -> Vec<f64> {
+    vec![1.0, 2.0, 3.0, 5.0, 8.0, 13.0]
+}
+
+const SETTINGS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MIN_AUTO_REFRESH_SECS: u64 = 10;
+
 pub fn create_default_file(path: impl AsRef<Path>) -> Result<()> {
     if let Some(parent) = path.as_ref().parent() {
         fs::create_dir_all(parent).with_context(|| {
             format!("Could not create config directory `{}`", parent.display(),)
         })?;
     }
-    let settings_str = toml::to_string_pretty(&Settings::default()).with_context(|| {
-        format!(
-            "Could not serialize settings to file `{}`",
-            path.as_ref().display()
-        )
-    })?;
+    // Going through a `toml::Value` puts plain values before tables, which
+    // TOML requires but the field order of `Settings` doesn't follow
+    let settings_str = toml::Value::try_from(Settings::default())
+        .and_then(|settings| toml::to_string_pretty(&settings))
+        .with_context(|| {
+            format!(
+                "Could not serialize settings to file `{}`",
+                path.as_ref().display()
+            )
+        })?;
     File::create(path.as_ref())
-        .and_then(|mut file| file.write(settings_str.as_bytes()))
-        .with_context(|| format!("Could not read settings file `{}`", path.as_ref().display(),))?;
+        .and_then(|mut file| file.write_all(settings_str.as_bytes()))
+        .with_context(|| {
+            format!(
+                "Could not write settings file `{}`",
+                path.as_ref().display(),
+            )
+        })?;
 
     Ok(())
 }
+
+/// Zenhub estimates can be mirrored into Github labels (e.g. `sp:5`), so
+/// teammates without access to Zenhub can see them too.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EstimateLabelSettings {
+    #[serde(default)]
+    pub sync: bool,
+    #[serde(default = "default_estimate_label_prefix")]
+    pub prefix: String,
+}
+
+impl Default for EstimateLabelSettings {
+    fn default() -> Self {
+        Self {
+            sync: false,
+            prefix: default_estimate_label_prefix(),
+        }
+    }
+}
+
+fn default_estimate_label_prefix() -> String {
+    "sp:".into()
+}
+
+/// Keeps a Github label in sync with a Zenhub pipeline, e.g. "In Progress"
+/// with `status/in-progress`. Moving a card to the pipeline adds the label
+/// and issues given the label on Github are moved to the pipeline.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PipelineLabel {
+    pub pipeline: String,
+    pub label: String,
+}
+
+impl PipelineLabel {
+    pub fn matches(&self, pipeline_name: &str) -> bool {
+        self.pipeline
+            .trim()
+            .eq_ignore_ascii_case(pipeline_name.trim())
+    }
+}
+
+/// Assigns you to the cards you move into a pipeline, e.g. "In Progress",
+/// so starting work on an issue is a single step.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AutoAssign {
+    pub pipeline: String,
+    /// Whether to ask before assigning you, rather than just doing it.
+    #[serde(default)]
+    pub ask: bool,
+}
+
+impl AutoAssign {
+    pub fn matches(&self, pipeline_name: &str) -> bool {
+        self.pipeline
+            .trim()
+            .eq_ignore_ascii_case(pipeline_name.trim())
+    }
+}
+
+/// The most issues a pipeline should hold, e.g. 5 in "In Progress". A
+/// pipeline over its limit shows its count as a warning.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WipLimit {
+    pub pipeline: String,
+    pub limit: usize,
+    /// Whether to ask before moving a card into the pipeline once it's full,
+    /// rather than just warning.
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+impl WipLimit {
+    pub fn matches(&self, pipeline_name: &str) -> bool {
+        self.pipeline
+            .trim()
+            .eq_ignore_ascii_case(pipeline_name.trim())
+    }
+}
+
+/// How soon issues with a label must be dealt with, e.g. `sev1` within 24
+/// hours. Cards of such issues count down to the deadline, then show how
+/// overdue they are.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SlaRule {
+    pub label: String,
+    pub hours: i64,
+    #[serde(default)]
+    pub from: SlaClock,
+}
+
+/// When the clock of an SLA starts.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SlaClock {
+    /// When the issue was opened, for a time to resolve.
+    Created,
+    /// When the issue last changed, for a time to respond.
+    Updated,
+}
+
+impl Default for SlaClock {
+    fn default() -> Self {
+        Self::Created
+    }
+}
+
+/// Where and how logs are written when logging is enabled with `--log`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LogSettings {
+    /// The file logs are appended to.
+    #[serde(default = "default_log_path")]
+    pub path: PathBuf,
+    #[serde(default)]
+    pub format: LogFormat,
+    /// A filter in `RUST_LOG` syntax; UI code logs under the `zentui` target
+    /// and API requests under `zentui_client`, e.g. `info,zentui_client=debug`.
+    #[serde(default = "default_log_level")]
+    pub level: String,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            path: default_log_path(),
+            format: LogFormat::default(),
+            level: default_log_level(),
+        }
+    }
+}
+
+#[serde(rename_all = "lowercase")]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum LogFormat {
+    Pretty,
+    Json,
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        LogFormat::Pretty
+    }
+}
+
+fn default_log_path() -> PathBuf {
+    "zentui.log".into()
+}
+
+fn default_log_level() -> String {
+    "zentui=debug,zentui_client=debug".into()
+}
+
+/// Where a repository is checked out locally, e.g. `mcobzarenco/zentui` in
+/// `~/src/zentui`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LocalRepo {
+    pub repo: String,
+    pub path: PathBuf,
+}
+
+/// An incoming webhook the selected issue can be shared to. See
+/// `app::share::render` for the placeholders available in the template.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Webhook {
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub kind: WebhookKind,
+    #[serde(default = "default_webhook_template")]
+    pub template: String,
+}
+
+fn default_webhook_template() -> String {
+    "#{number} {title} ({pipeline})\n{url}".into()
+}
+
+/// A hotkey which triages the selected issue in one go: it adds the labels,
+/// then optionally comments on and closes the issue. The key must not be
+/// taken by another binding, e.g. `g` starts `g d`, `g f`, `g t` and `g r`,
+/// so "good first issue" is on `G` by default.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TriageAction {
+    pub key: char,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub close: bool,
+}
+
+fn default_triage_actions() -> Vec<TriageAction> {
+    vec![
+        TriageAction {
+            key: 'G',
+            labels: vec!["good first issue".into()],
+            comment: None,
+            close: false,
+        },
+        TriageAction {
+            key: 'd',
+            labels: vec!["duplicate".into()],
+            comment: Some("Closing as this is a duplicate of an existing issue.".into()),
+            close: true,
+        },
+        TriageAction {
+            key: 'w',
+            labels: vec!["wontfix".into()],
+            comment: None,
+            close: true,
+        },
+    ]
+}
+
+/// The hours and days of the week you work, in local time, e.g.
+///
+/// ```toml
+/// [working_hours]
+/// start_hour = 8
+/// end_hour = 17
+/// days = ["mon", "tue", "wed", "thu"]
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorkingHours {
+    #[serde(default = "default_work_start_hour")]
+    pub start_hour: u32,
+    /// The hour work ends, before `start_hour` for a shift past midnight.
+    #[serde(default = "default_work_end_hour")]
+    pub end_hour: u32,
+    #[serde(default = "default_work_days")]
+    pub days: Vec<String>,
+    /// How many times less often the board is polled and refreshed outside
+    /// working hours.
+    #[serde(default = "default_off_hours_slowdown")]
+    pub off_hours_slowdown: u32,
+}
+
+impl WorkingHours {
+    pub fn contains(&self, time: DateTime<Local>) -> bool {
+        // Days may be written in full or abbreviated, in any case
+        let weekday = time.weekday().to_string().to_lowercase();
+        let working_day = self
+            .days
+            .iter()
+            .any(|day| day.to_lowercase().starts_with(&weekday));
+        let hour = time.hour();
+        let working_hour = if self.start_hour <= self.end_hour {
+            self.start_hour <= hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        };
+        working_day && working_hour
+    }
+
+    /// How often to do something done every `interval` during working hours.
+    pub fn interval(&self, interval: Duration, time: DateTime<Local>) -> Duration {
+        if self.contains(time) {
+            interval
+        } else {
+            interval * self.off_hours_slowdown.max(1)
+        }
+    }
+}
+
+impl Default for WorkingHours {
+    fn default() -> Self {
+        Self {
+            start_hour: default_work_start_hour(),
+            end_hour: default_work_end_hour(),
+            days: default_work_days(),
+            off_hours_slowdown: default_off_hours_slowdown(),
+        }
+    }
+}
+
+fn default_work_start_hour() -> u32 {
+    9
+}
+
+fn default_work_end_hour() -> u32 {
+    18
+}
+
+fn default_work_days() -> Vec<String> {
+    ["mon", "tue", "wed", "thu", "fri"]
+        .iter()
+        .map(|&day| day.to_owned())
+        .collect()
+}
+
+fn default_off_hours_slowdown() -> u32 {
+    10
+}
+
+/// How `Y` drafts a changelog from the issues in a pipeline, e.g. "Done
+/// this sprint".
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ChangelogSettings {
+    /// The sections of the changelog, in order.
+    #[serde(default = "default_changelog_groups")]
+    pub groups: Vec<ChangelogGroup>,
+    /// The file the draft is written to, when not copied to the clipboard.
+    #[serde(default = "default_changelog_path")]
+    pub path: PathBuf,
+}
+
+impl Default for ChangelogSettings {
+    fn default() -> Self {
+        Self {
+            groups: default_changelog_groups(),
+            path: default_changelog_path(),
+        }
+    }
+}
+
+/// A section of the changelog, with the issues having any of the labels.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ChangelogGroup {
+    pub title: String,
+    pub labels: Vec<String>,
+}
+
+fn default_changelog_groups() -> Vec<ChangelogGroup> {
+    vec![
+        ChangelogGroup {
+            title: "Features".into(),
+            labels: vec!["enhancement".into(), "feature".into()],
+        },
+        ChangelogGroup {
+            title: "Fixes".into(),
+            labels: vec!["bug".into(), "fix".into()],
+        },
+    ]
+}
+
+fn default_changelog_path() -> PathBuf {
+    "CHANGELOG.draft.md".into()
+}
+
+/// Which issues the stale issue sweep finds, and how it nudges and labels
+/// them.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StaleSettings {
+    #[serde(default = "default_stale_days")]
+    pub days: i64,
+    /// Pipelines to sweep; all but the Icebox and Closed when empty.
+    #[serde(default)]
+    pub pipelines: Vec<String>,
+    #[serde(default = "default_stale_label")]
+    pub label: String,
+    #[serde(default = "default_stale_comment")]
+    pub comment: String,
+}
+
+impl Default for StaleSettings {
+    fn default() -> Self {
+        Self {
+            days: default_stale_days(),
+            pipelines: Vec::new(),
+            label: default_stale_label(),
+            comment: default_stale_comment(),
+        }
+    }
+}
+
+fn default_stale_days() -> i64 {
+    60
+}
+
+fn default_stale_label() -> String {
+    "stale".into()
+}
+
+fn default_stale_comment() -> String {
+    "This issue hasn't seen any activity in a while. Is it still relevant?".into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_sequence(estimate_sequence: &[f64]) -> Settings {
+        Settings {
+            estimate_sequence: estimate_sequence.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stepping_through_the_sequence() {
+        let settings = with_sequence(&[1.0, 2.0, 3.0, 5.0, 8.0]);
+        assert_eq!(settings.step_estimate(None, true), Some(1.0));
+        assert_eq!(settings.step_estimate(Some(2.0), true), Some(3.0));
+        assert_eq!(settings.step_estimate(Some(5.0), false), Some(3.0));
+    }
+
+    #[test]
+    fn stepping_past_either_end() {
+        let settings = with_sequence(&[1.0, 2.0, 3.0, 5.0, 8.0]);
+        assert_eq!(settings.step_estimate(Some(8.0), true), Some(8.0));
+        assert_eq!(settings.step_estimate(Some(1.0), false), None);
+        assert_eq!(settings.step_estimate(None, false), None);
+    }
+
+    #[test]
+    fn stepping_from_estimates_not_in_the_sequence() {
+        let settings = with_sequence(&[1.0, 2.0, 3.0, 5.0, 8.0]);
+        assert_eq!(settings.step_estimate(Some(4.0), true), Some(5.0));
+        assert_eq!(settings.step_estimate(Some(4.0), false), Some(3.0));
+        assert_eq!(settings.step_estimate(Some(0.5), false), None);
+        // Estimates set elsewhere, e.g. on Zenhub, are kept rather than lowered
+        assert_eq!(settings.step_estimate(Some(13.0), true), Some(13.0));
+        assert_eq!(settings.step_estimate(Some(13.0), false), Some(8.0));
+    }
+
+    #[test]
+    fn stepping_with_an_unordered_or_empty_sequence() {
+        let settings = with_sequence(&[5.0, 1.0, 3.0]);
+        assert_eq!(settings.step_estimate(None, true), Some(1.0));
+        assert_eq!(settings.step_estimate(Some(1.0), true), Some(3.0));
+        assert_eq!(settings.step_estimate(Some(5.0), false), Some(3.0));
+
+        let settings = with_sequence(&[]);
+        assert_eq!(settings.step_estimate(None, true), None);
+        assert_eq!(settings.step_estimate(Some(3.0), true), Some(3.0));
+        assert_eq!(settings.step_estimate(Some(3.0), false), None);
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Context, Result};
+use dirs;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::github::{IssueNumber, IssueState, Team};
+use std::{
+    cmp::{self, Ordering},
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// Local UI state which is remembered between sessions. Unlike settings, it
+/// is written by zentui itself and not meant to be edited by hand.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct State {
+    #[serde(default)]
+    pub detail_pane_width: Option<usize>,
+    #[serde(default)]
+    pub repos: BTreeMap<String, RepoState>,
+}
+
+impl State {
+    pub fn repo(&self, full_name: &str) -> Option<&RepoState> {
+        self.repos.get(full_name)
+    }
+
+    /// Remembers that a repository's board was opened at `now` (in seconds
+    /// since the Unix epoch).
+    pub fn record_visit(&mut self, full_name: &str, now: i64) {
+        let repo = self.repo_mut(full_name);
+        repo.visits += 1;
+        repo.last_opened = Some(now);
+    }
+
+    /// The repositories opened before, most frecent first: visits count for
+    /// less as they age, so a board opened daily this week beats one opened
+    /// often last year.
+    pub fn recent_repos(&self, now: i64) -> Vec<&str> {
+        let mut repos: Vec<_> = self
+            .repos
+            .iter()
+            .filter_map(|(full_name, repo)| {
+                repo.last_opened
+                    .map(|last_opened| (full_name.as_str(), repo.frecency(now - last_opened)))
+            })
+            .collect();
+        repos.sort_by(|(_, left), (_, right)| right.partial_cmp(left).unwrap_or(Ordering::Equal));
+        repos.into_iter().map(|(full_name, _)| full_name).collect()
+    }
+
+    /// The repositories opened before, most recently opened first.
+    pub fn last_repos(&self) -> Vec<&str> {
+        let mut repos: Vec<_> = self
+            .repos
+            .iter()
+            .filter_map(|(full_name, repo)| {
+                repo.last_opened
+                    .map(|last_opened| (full_name.as_str(), last_opened))
+            })
+            .collect();
+        repos.sort_by_key(|&(_, last_opened)| cmp::Reverse(last_opened));
+        repos.into_iter().map(|(full_name, _)| full_name).collect()
+    }
+
+    pub fn repo_mut(&mut self, full_name: &str) -> &mut RepoState {
+        self.repos.entry(full_name.to_owned()).or_default()
+    }
+}
+
+/// State remembered separately for each repository.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RepoState {
+    #[serde(default)]
+    pub bookmarks: BTreeMap<char, IssueNumber>,
+    #[serde(default)]
+    pub visits: u32,
+    /// When the board was last opened, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub last_opened: Option<i64>,
+    /// The id of the Zenhub workspace whose board was picked.
+    #[serde(default)]
+    pub workspace: Option<String>,
+    /// Issues hidden from the board for a while.
+    #[serde(default)]
+    pub snoozed: Vec<Snooze>,
+    /// How the board was left when zentui last exited, restored when it's
+    /// opened again.
+    #[serde(default)]
+    pub board: Option<BoardState>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct BoardState {
+    /// The pipelines hidden or collapsed, by id.
+    #[serde(default)]
+    pub pipelines: Vec<PipelineState>,
+    /// The id of the selected pipeline.
+    #[serde(default)]
+    pub selected_pipeline: Option<String>,
+    #[serde(default)]
+    pub selected_issue: Option<IssueNumber>,
+    #[serde(default)]
+    pub filter: FilterState,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PipelineState {
+    pub id: String,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+/// The filters of the board. Only the team is kept for a team filter, its
+/// members are loaded again.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct FilterState {
+    #[serde(default)]
+    pub team: Option<Team>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub state: Option<IssueState>,
+    /// The kind of card shown, e.g. `prs`.
+    #[serde(default)]
+    pub kind: Option<String>,
+}
+
+/// An issue hidden from the board until `until`, in seconds since the Unix
+/// epoch.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Snooze {
+    pub issue_number: IssueNumber,
+    pub until: i64,
+}
+
+impl RepoState {
+    fn frecency(&self, age_secs: i64) -> f64 {
+        let weight = match age_secs {
+            age if age < DAY_SECS => 4.0,
+            age if age < 7 * DAY_SECS => 2.0,
+            age if age < 30 * DAY_SECS => 1.0,
+            _ => 0.5,
+        };
+        f64::from(self.visits) * weight
+    }
+}
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+pub fn state_path() -> Result<PathBuf> {
+    let mut path = dirs::data_local_dir()
+        .ok_or_else(|| anyhow!("Could not get path to the user's local data directory"))
+        .map(|mut data_dir| {
+            data_dir.push("zentui");
+            data_dir
+        })?;
+    path.push("state.json");
+    Ok(path)
+}
+
+pub fn read_state(path: impl AsRef<Path>) -> State {
+    if path.as_ref().exists() {
+        File::open(path.as_ref())
+            .and_then(|mut file| {
+                let mut contents = String::new();
+                file.read_to_string(&mut contents)?;
+                Ok(contents)
+            })
+            .with_context(|| format!("Could not read state file `{}`", path.as_ref().display()))
+            .and_then(|contents| {
+                serde_json::from_str(&contents).with_context(|| {
+                    format!("Could not parse state file `{}`", path.as_ref().display())
+                })
+            })
+            .map_err(|err| log::error!("{}", err))
+            .unwrap_or_else(|_| Default::default())
+    } else {
+        Default::default()
+    }
+}
+
+pub fn write_state(path: impl AsRef<Path>, state: &State) -> Result<()> {
+    if let Some(parent) = path.as_ref().parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Could not create state directory `{}`", parent.display()))?;
+    }
+    let state_str = serde_json::to_string_pretty(state).with_context(|| {
+        format!(
+            "Could not serialize state to file `{}`",
+            path.as_ref().display()
+        )
+    })?;
+    File::create(path.as_ref())
+        .and_then(|mut file| file.write_all(state_str.as_bytes()))
+        .with_context(|| format!("Could not write state file `{}`", path.as_ref().display()))?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, Local};
+use dirs;
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// Counts of what the user did in zentui, grouped by ISO week. The stats are
+/// only ever stored locally and shown with `zentui stats`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Stats {
+    #[serde(default)]
+    pub weeks: BTreeMap<String, WeekStats>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct WeekStats {
+    #[serde(default)]
+    pub issues_moved: usize,
+    #[serde(default)]
+    pub issues_closed: usize,
+    #[serde(default)]
+    pub comments_posted: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Activity {
+    MovedIssue,
+    ClosedIssue,
+    PostedComment,
+}
+
+impl Stats {
+    pub fn record(&mut self, activity: Activity) {
+        let week = self.weeks.entry(current_week()).or_default();
+        match activity {
+            Activity::MovedIssue => week.issues_moved += 1,
+            Activity::ClosedIssue => week.issues_closed += 1,
+            Activity::PostedComment => week.comments_posted += 1,
+        }
+    }
+
+    /// A table of the most recent weeks, for printing to the terminal.
+    pub fn report(&self, num_weeks: usize) -> String {
+        if self.weeks.is_empty() {
+            return "No activity recorded yet.\n".into();
+        }
+
+        let mut report = format!(
+            "{:<10} {:>8} {:>8} {:>9}\n",
+            "Week", "Moved", "Closed", "Comments"
+        );
+        let mut total = WeekStats::default();
+        for (week, stats) in self.weeks.iter().rev().take(num_weeks).rev() {
+            report.push_str(&format!(
+                "{:<10} {:>8} {:>8} {:>9}\n",
+                week, stats.issues_moved, stats.issues_closed, stats.comments_posted
+            ));
+            total.issues_moved += stats.issues_moved;
+            total.issues_closed += stats.issues_closed;
+            total.comments_posted += stats.comments_posted;
+        }
+        report.push_str(&format!(
+            "{:<10} {:>8} {:>8} {:>9}\n",
+            "Total", total.issues_moved, total.issues_closed, total.comments_posted
+        ));
+        report
+    }
+}
+
+/// The current ISO week, e.g. `2020-W27`, which sorts chronologically.
+fn current_week() -> String {
+    let week = Local::now().iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+pub fn stats_path() -> Result<PathBuf> {
+    let mut path = dirs::data_local_dir()
+        .ok_or_else(|| anyhow!("Could not get path to the user's local data directory"))
+        .map(|mut data_dir| {
+            data_dir.push("zentui");
+            data_dir
+        })?;
+    path.push("stats.json");
+    Ok(path)
+}
+
+pub fn read_stats(path: impl AsRef<Path>) -> Stats {
+    if path.as_ref().exists() {
+        File::open(path.as_ref())
+            .and_then(|mut file| {
+                let mut contents = String::new();
+                file.read_to_string(&mut contents)?;
+                Ok(contents)
+            })
+            .with_context(|| format!("Could not read stats file `{}`", path.as_ref().display()))
+            .and_then(|contents| {
+                serde_json::from_str(&contents).with_context(|| {
+                    format!("Could not parse stats file `{}`", path.as_ref().display())
+                })
+            })
+            .map_err(|err| log::error!("{}", err))
+            .unwrap_or_else(|_| Default::default())
+    } else {
+        Default::default()
+    }
+}
+
+pub fn write_stats(path: impl AsRef<Path>, stats: &Stats) -> Result<()> {
+    if let Some(parent) = path.as_ref().parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Could not create stats directory `{}`", parent.display()))?;
+    }
+    let stats_str = serde_json::to_string_pretty(stats).with_context(|| {
+        format!(
+            "Could not serialize stats to file `{}`",
+            path.as_ref().display()
+        )
+    })?;
+    File::create(path.as_ref())
+        .and_then(|mut file| file.write_all(stats_str.as_bytes()))
+        .with_context(|| format!("Could not write stats file `{}`", path.as_ref().display()))?;
+    Ok(())
+}
+
+pub const REPORT_WEEKS: usize = 12;
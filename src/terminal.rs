@@ -0,0 +1,190 @@
+use anyhow::{anyhow, Context, Result};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    env,
+    io::{self, BufRead, Write},
+    process::{Command, Stdio},
+};
+
+/// A protocol for drawing images in the terminal.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphicsProtocol {
+    Kitty,
+    Iterm2,
+    /// Sixels, drawn by piping the image through `img2sixel`.
+    Sixel,
+}
+
+impl GraphicsProtocol {
+    /// Guesses the protocol supported by the terminal from its environment.
+    pub fn detect() -> Option<Self> {
+        let term = env::var("TERM").unwrap_or_default();
+        let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+        if term == "xterm-kitty" || env::var_os("KITTY_WINDOW_ID").is_some() {
+            Some(Self::Kitty)
+        } else if term_program == "iTerm.app" || term_program == "WezTerm" {
+            Some(Self::Iterm2)
+        } else if term.contains("mlterm") || term.contains("foot") {
+            Some(Self::Sixel)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether the terminal is known to turn OSC 8 escape codes into clickable
+/// links. Set `FORCE_HYPERLINK=1` (or `0`) to override the detection.
+pub fn supports_hyperlinks() -> bool {
+    if let Ok(force) = env::var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+    let vte_version = env::var("VTE_VERSION")
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok())
+        .unwrap_or(0);
+    env::var("TERM").map_or(false, |term| term == "xterm-kitty")
+        || ["iTerm.app", "WezTerm", "vscode", "Hyper"].contains(&term_program.as_str())
+        || vte_version >= 5000
+        || env::var_os("WT_SESSION").is_some()
+        || env::var_os("KONSOLE_VERSION").is_some()
+        || env::var_os("DOMTERM").is_some()
+}
+
+/// Wraps text in an OSC 8 hyperlink to `url`, if the terminal supports them.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    if supports_hyperlinks() {
+        format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Opens a URL in the default browser.
+pub fn open_url(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(&["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Could not open `{}` in the browser", url))?;
+    Ok(())
+}
+
+/// Clears the screen and draws the images one after the other, each with its
+/// caption linking to the image, then waits for Enter. Must only be called while the UI isn't
+/// drawing, e.g. from `ComponentLink::run_exclusive`.
+pub fn show_images(protocol: GraphicsProtocol, images: &[(String, String, Vec<u8>)]) -> Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b[2J\x1b[H")?;
+    for (caption, url, bytes) in images.iter() {
+        match protocol {
+            GraphicsProtocol::Kitty => {
+                if let Err(error) = write_kitty(&mut stdout, bytes) {
+                    write!(stdout, "{}", error)?;
+                }
+            }
+            GraphicsProtocol::Iterm2 => write!(
+                stdout,
+                "\x1b]1337;File=inline=1;size={};preserveAspectRatio=1:{}\x07",
+                bytes.len(),
+                base64(bytes)
+            )?,
+            GraphicsProtocol::Sixel => {
+                stdout.flush()?;
+                write_sixel(bytes)?;
+            }
+        }
+        write!(stdout, "\r\n{}\r\n\r\n", hyperlink(url, caption))?;
+    }
+    write!(stdout, "Press Enter to go back")?;
+    stdout.flush()?;
+    io::stdin().lock().read_line(&mut String::new())?;
+    Ok(())
+}
+
+fn write_kitty(stdout: &mut impl Write, bytes: &[u8]) -> Result<()> {
+    // Kitty can only decode PNGs by itself
+    if !bytes.starts_with(PNG_SIGNATURE) {
+        return Err(anyhow!("Only PNG images can be shown in kitty"));
+    }
+    let encoded = base64(bytes);
+    let chunks: Vec<_> = encoded.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = if index + 1 < chunks.len() { 1 } else { 0 };
+        let chunk = std::str::from_utf8(chunk).expect("base64 is ascii");
+        if index == 0 {
+            write!(stdout, "\x1b_Gf=100,a=T,m={};{}\x1b\\", more, chunk)?;
+        } else {
+            write!(stdout, "\x1b_Gm={};{}\x1b\\", more, chunk)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_sixel(bytes: &[u8]) -> Result<()> {
+    let mut child = Command::new("img2sixel")
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Could not run `img2sixel`, is libsixel installed?")?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Could not open stdin of `img2sixel`"))?
+        .write_all(bytes)?;
+    child.wait()?;
+    Ok(())
+}
+
+/// Standard base64 with padding, as expected by the terminal escape codes.
+pub fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | (u32::from(*byte) << (16 - 8 * index))
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(ALPHABET[((group >> (18 - 6 * index)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_is_padded_to_groups_of_four() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn base64_uses_the_standard_alphabet() {
+        assert_eq!(base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+        assert_eq!(base64(PNG_SIGNATURE), "iVBORw0KGgo=");
+    }
+}
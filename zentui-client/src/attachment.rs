@@ -0,0 +1,44 @@
+use reqwest::{Client as HttpClient, Method, Url};
+use std::sync::Arc;
+
+use crate::{
+    error::{Error, Result},
+    request,
+};
+
+/// Downloads files attached to issues, e.g. screenshots. No credentials are
+/// sent, attachments can be hosted anywhere.
+#[derive(Debug)]
+pub struct Client {
+    http_client: HttpClient,
+}
+
+impl Client {
+    pub fn new() -> Result<Client> {
+        Ok(Client {
+            http_client: HttpClient::builder()
+                .gzip(true)
+                .build()
+                .map_err(Error::Client)?,
+        })
+    }
+
+    /// Download an attachment.
+    pub async fn get_bytes(self: Arc<Self>, url: String) -> Result<Vec<u8>> {
+        let url = Url::parse(&url).map_err(|source| Error::Url {
+            description: format!("attachment `{}`", url),
+            source,
+        })?;
+        let response =
+            request::send(Method::GET, url.clone(), self.http_client.get(url.clone())).await?;
+        response
+            .bytes()
+            .await
+            .map(|bytes| bytes.to_vec())
+            .map_err(|source| Error::Request {
+                method: Method::GET,
+                url,
+                source,
+            })
+    }
+}
@@ -0,0 +1,154 @@
+use reqwest::{header::InvalidHeaderValue, Method, StatusCode, Url};
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Everything that can go wrong when talking to the Github or Zenhub APIs.
+#[derive(Debug)]
+pub enum Error {
+    /// The HTTP client could not be created.
+    Client(reqwest::Error),
+    /// The token cannot be sent as a header value.
+    InvalidToken(InvalidHeaderValue),
+    /// The base URL of an API given in the settings is not one requests can
+    /// be sent to.
+    InvalidEndpoint { endpoint: String, reason: String },
+    /// The URL of an endpoint could not be built.
+    Url {
+        description: String,
+        source: url::ParseError,
+    },
+    /// The request could not be sent or the response could not be read.
+    Request {
+        method: Method,
+        url: Url,
+        source: reqwest::Error,
+    },
+    /// The API responded with a non-success status code.
+    Status {
+        method: Method,
+        url: Url,
+        status: StatusCode,
+    },
+    /// The response body is not the JSON we expected, usually because the API
+    /// changed. `field` is the offending field, when serde reports it.
+    Json {
+        url: Url,
+        field: Option<String>,
+        source: serde_json::Error,
+    },
+    /// A GraphQL query returned errors and no data.
+    GraphQl { url: Url, messages: Vec<String> },
+    /// The token is missing an OAuth scope required by the endpoint.
+    MissingScope { scope: &'static str, reason: String },
+    /// The organization owning the resource enforces SAML single sign-on and
+    /// the token wasn't authorized for it. Github includes the URL to
+    /// authorize it at, when it knows which organization it is.
+    SamlEnforced {
+        method: Method,
+        url: Url,
+        authorize_url: Option<String>,
+    },
+    /// The endpoint is only on Zenhub's REST API, which doesn't accept the
+    /// newer GraphQL API keys.
+    RestOnly { method: Method, url: Url },
+}
+
+impl Error {
+    /// The status code the API responded with, if the request got that far.
+    pub fn status(&self) -> Option<StatusCode> {
+        match *self {
+            Error::Status { status, .. } => Some(status),
+            Error::SamlEnforced { .. } => Some(StatusCode::FORBIDDEN),
+            Error::Request { ref source, .. } => source.status(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Client(_) => write!(formatter, "Could not create the HTTP client."),
+            Error::InvalidToken(_) => write!(formatter, "The API token is not a valid header."),
+            Error::InvalidEndpoint { endpoint, reason } => write!(
+                formatter,
+                "`{}` is not a valid API endpoint: {}. It should be the base URL of the API, \
+                 e.g. `https://zenhub.example.com/api` for Zenhub Enterprise.",
+                endpoint, reason
+            ),
+            Error::Url { description, .. } => {
+                write!(formatter, "Could not build URL for {}.", description)
+            }
+            Error::Request { method, url, .. } => {
+                write!(formatter, "{} `{}` operation failed.", method, url)
+            }
+            Error::Status {
+                method,
+                url,
+                status,
+            } => write!(
+                formatter,
+                "{} `{}` returned non-success status code {}.",
+                method, url, status
+            ),
+            Error::Json { url, field, .. } => {
+                write!(formatter, "Schema mismatch in the response from `{}`", url)?;
+                if let Some(field) = field {
+                    write!(formatter, " (field `{}`)", field)?;
+                }
+                write!(formatter, ", please report it.")
+            }
+            Error::GraphQl { url, messages } => write!(
+                formatter,
+                "GraphQL query to `{}` failed: {}",
+                url,
+                messages.join("; ")
+            ),
+            Error::MissingScope { scope, reason } => write!(
+                formatter,
+                "The token doesn't have the `{}` scope required to {}.",
+                scope, reason
+            ),
+            Error::SamlEnforced {
+                authorize_url: Some(authorize_url),
+                ..
+            } => write!(
+                formatter,
+                "The organization enforces SAML single sign-on and the token isn't authorized \
+                 for it. Authorize it at {} and try again.",
+                authorize_url
+            ),
+            Error::SamlEnforced { url, .. } => write!(
+                formatter,
+                "The organization owning `{}` enforces SAML single sign-on and the token \
+                 isn't authorized for it. Authorize it with \"Configure SSO\" next to the \
+                 token in Github's developer settings and try again.",
+                url
+            ),
+            Error::RestOnly { method, url } => write!(
+                formatter,
+                "{} `{}` isn't supported with a Zenhub GraphQL API key yet, it needs a token \
+                 from Zenhub's dashboard.",
+                method, url
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Client(source) | Error::Request { source, .. } => Some(source),
+            Error::InvalidToken(source) => Some(source),
+            Error::Url { source, .. } => Some(source),
+            Error::Json { source, .. } => Some(source),
+            Error::InvalidEndpoint { .. }
+            | Error::GraphQl { .. }
+            | Error::Status { .. }
+            | Error::MissingScope { .. }
+            | Error::SamlEnforced { .. }
+            | Error::RestOnly { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use reqwest::{header::HeaderValue, Url};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// The last response to each GET request with an `ETag`, revalidated with
+/// `If-None-Match` the next time the URL is requested. Github answers `304
+/// Not Modified` when nothing changed, which doesn't count against the rate
+/// limit, so refreshing a board of mostly unchanged issues is nearly free.
+#[derive(Debug, Default)]
+pub(crate) struct EtagCache {
+    responses: Mutex<HashMap<Url, Cached>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Cached {
+    pub etag: HeaderValue,
+    pub body: Arc<str>,
+    /// The next page of a list endpoint, from the `Link` header.
+    pub next: Option<Url>,
+}
+
+impl EtagCache {
+    pub(crate) fn get(&self, url: &Url) -> Option<Cached> {
+        self.responses
+            .lock()
+            .expect("etag cache lock is poisoned")
+            .get(url)
+            .cloned()
+    }
+
+    pub(crate) fn insert(&self, url: Url, cached: Cached) {
+        self.responses
+            .lock()
+            .expect("etag cache lock is poisoned")
+            .insert(url, cached);
+    }
+}
@@ -0,0 +1,1358 @@
+use chrono::{DateTime, TimeZone, Utc};
+use im::Vector;
+use once_cell::sync::Lazy;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, ACCEPT, ETAG, IF_NONE_MATCH, LINK, USER_AGENT},
+    Client as HttpClient, Method, RequestBuilder, StatusCode, Url,
+};
+use serde::{de::Deserializer, Deserialize, Serialize, Serializer};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    error::{Error, Result},
+    etag::{Cached, EtagCache},
+    graphql::{self, Nodes},
+    lenient,
+    pages::{self, Pages},
+    request::{self, Limiter},
+};
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RepoId(pub u64);
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Repo {
+    pub id: RepoId,
+    pub full_name: RepoFullName,
+    #[serde(default)]
+    pub archived: bool,
+    /// What the authenticated user may do in the repo, only set when the
+    /// request was authenticated.
+    #[serde(default)]
+    pub permissions: Option<RepoPermissions>,
+}
+
+impl Repo {
+    /// Whether issues can be opened, edited and commented on, i.e. the repo
+    /// isn't archived and the user has push access. Assumed when Github
+    /// doesn't say.
+    pub fn is_writable(&self) -> bool {
+        !self.archived
+            && self
+                .permissions
+                .as_ref()
+                .map_or(true, |permissions| permissions.push)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct RepoPermissions {
+    #[serde(default)]
+    pub admin: bool,
+    #[serde(default)]
+    pub push: bool,
+    #[serde(default)]
+    pub pull: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IssueNumber(pub usize);
+
+#[serde(rename_all = "lowercase")]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum IssueState {
+    Open,
+    Closed,
+}
+
+impl Default for IssueState {
+    fn default() -> Self {
+        Self::Open
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct User {
+    pub login: String,
+}
+
+/// A notification for the authenticated user, e.g. about a mention or a
+/// review request.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Notification {
+    pub id: String,
+    #[serde(default)]
+    pub reason: String,
+    pub updated_at: DateTime<Utc>,
+    pub subject: NotificationSubject,
+}
+
+impl Notification {
+    /// The issue or pull request the notification is about, if any.
+    pub fn issue_number(&self) -> Option<IssueNumber> {
+        self.subject
+            .url
+            .as_ref()?
+            .rsplit('/')
+            .next()?
+            .parse()
+            .ok()
+            .map(IssueNumber)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct NotificationSubject {
+    #[serde(default)]
+    pub title: String,
+    /// The API URL of the issue or pull request, not set for e.g. releases.
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Milestone {
+    pub number: usize,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub due_on: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub open_issues: usize,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct ReleaseDraft {
+    pub tag_name: String,
+    pub html_url: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Team {
+    pub id: u64,
+    pub slug: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Issue {
+    pub number: IssueNumber,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub user: User,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub body: String,
+    #[serde(default)]
+    pub state: IssueState,
+    #[serde(default, deserialize_with = "lenient::skip_invalid")]
+    pub labels: Vector<Label>,
+    #[serde(default, deserialize_with = "lenient::skip_invalid")]
+    pub assignees: Vector<User>,
+    #[serde(default)]
+    pub reactions: Reactions,
+    /// The number of comments.
+    #[serde(default)]
+    pub comments: usize,
+    pub pull_request: Option<PullRequestRefs>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Comment {
+    pub id: u64,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub user: User,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct PullRequest {
+    pub number: IssueNumber,
+    #[serde(default, deserialize_with = "lenient::skip_invalid")]
+    pub requested_reviewers: Vector<User>,
+    #[serde(default, deserialize_with = "lenient::skip_invalid")]
+    pub requested_teams: Vector<Team>,
+}
+
+/// Whether a pull request is ready to merge, as far as its reviews and the
+/// checks on its last commit go.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PullRequestStatus {
+    /// `None` when the repo doesn't require reviews.
+    pub review: Option<ReviewDecision>,
+    /// `None` when no checks ran on the last commit.
+    pub checks: Option<CheckStatus>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    ReviewRequired,
+}
+
+/// The combined state of the checks and commit statuses of a commit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Success,
+    Failure,
+    Pending,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Reactions {
+    #[serde(default)]
+    pub total_count: usize,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Label {
+    pub name: String,
+    #[serde(
+        default,
+        deserialize_with = "from_hex_colour",
+        serialize_with = "to_hex_colour"
+    )]
+    pub color: Colour,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PullRequestRefs {}
+
+/// An RGB colour, as used by Github for labels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Colour {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Default for Colour {
+    /// The grey Github gives labels created without a colour.
+    fn default() -> Self {
+        Self {
+            red: 0xed,
+            green: 0xed,
+            blue: 0xed,
+        }
+    }
+}
+
+/// Writes a colour the way Github does, e.g. `d73a4a`.
+fn to_hex_colour<SerializerT>(
+    colour: &Colour,
+    serializer: SerializerT,
+) -> std::result::Result<SerializerT::Ok, SerializerT::Error>
+where
+    SerializerT: Serializer,
+{
+    serializer.serialize_str(&format!(
+        "{:02x}{:02x}{:02x}",
+        colour.red, colour.green, colour.blue
+    ))
+}
+
+/// Parses a colour like `d73a4a`, falling back to the default colour if it
+/// is malformed or missing, rather than failing the whole issue.
+fn from_hex_colour<'de, DeserializerT>(
+    deserializer: DeserializerT,
+) -> std::result::Result<Colour, DeserializerT::Error>
+where
+    DeserializerT: Deserializer<'de>,
+{
+    // Owned, as borrowing fails when parsing from a `serde_json::Value`
+    let hex_str: Option<String> = Deserialize::deserialize(deserializer)?;
+    let hex_str = hex_str.unwrap_or_default();
+    let hex_str = hex_str.trim_start_matches('#');
+    match u32::from_str_radix(hex_str, 16) {
+        Ok(colour) if hex_str.len() == 6 => Ok(Colour {
+            red: ((colour >> 16) & 0xff) as u8,
+            green: ((colour >> 8) & 0xff) as u8,
+            blue: (colour & 0xff) as u8,
+        }),
+        _ => {
+            tracing::warn!(colour = hex_str, "ignoring malformed label colour");
+            Ok(Colour::default())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Client {
+    endpoints: Endpoints,
+    http_client: HttpClient,
+    authorization_token: HeaderValue,
+    limiter: Limiter,
+    etags: EtagCache,
+    rate_limit: Mutex<Option<RateLimit>>,
+}
+
+/// How many requests the token has left, as of the last response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    /// When the remaining requests are reset to the limit.
+    pub reset: Option<DateTime<Utc>>,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let number = |name: &str| -> Option<u64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+        Some(Self {
+            limit: number(RATE_LIMIT_HEADER)?,
+            remaining: number(RATE_LIMIT_REMAINING_HEADER)?,
+            reset: number(RATE_LIMIT_RESET_HEADER).map(|reset| Utc.timestamp(reset as i64, 0)),
+        })
+    }
+}
+
+impl Client {
+    /// Create a new Github client, which sends at most
+    /// `max_concurrent_requests` requests at once.
+    pub fn new(token: Token, max_concurrent_requests: usize) -> Result<Client> {
+        Ok(Client {
+            endpoints: Endpoints::new(DEFAULT_ENDPOINT.clone())?,
+            http_client: HttpClient::builder()
+                .gzip(true)
+                .timeout(request::REQUEST_TIMEOUT)
+                .connect_timeout(request::CONNECT_TIMEOUT)
+                .build()
+                .map_err(Error::Client)?,
+            authorization_token: HeaderValue::from_str(&format!("token {}", token.0))
+                .map_err(Error::InvalidToken)?,
+            limiter: Limiter::new(max_concurrent_requests),
+            etags: EtagCache::default(),
+            rate_limit: Mutex::new(None),
+        })
+    }
+
+    /// The rate limit of the token, once a request was sent.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        *self.rate_limit.lock().expect("rate limit lock is poisoned")
+    }
+
+    /// Create a new API client.
+    pub async fn get_repo(&self, repo: &RepoFullName) -> Result<Repo> {
+        self.get::<Repo>(self.endpoints.repo(repo)?).await
+    }
+
+    /// Get a repo by its id, which is all Zenhub knows about the repos on a
+    /// board.
+    pub async fn get_repo_by_id(self: Arc<Self>, repo_id: RepoId) -> Result<Repo> {
+        self.get::<Repo>(self.endpoints.repo_by_id(repo_id)?).await
+    }
+
+    /// Get an issue.
+    pub async fn get_issue(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+    ) -> Result<Issue> {
+        self.get::<Issue>(self.endpoints.issue(&repo, &issue_number)?)
+            .await
+    }
+
+    /// Get several issues of a repo with a single GraphQL query rather than a
+    /// REST request each, which loads a board much faster. Ask for at most
+    /// `ISSUES_PER_QUERY` issues at a time. Issues which don't exist are left
+    /// out; `get_issue` tells why.
+    pub async fn get_issues(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_numbers: Vec<IssueNumber>,
+    ) -> Result<Vec<Issue>> {
+        let url = self.endpoints.graphql()?;
+        let query = issues_query(&issue_numbers);
+        let variables = RepoVariables {
+            owner: repo.owner(),
+            name: repo.name(),
+        };
+        let data: IssuesData =
+            request::with_retries(&url, || self.graphql(url.clone(), &query, &variables)).await?;
+        Ok(data
+            .repository
+            .map(|repository| {
+                repository
+                    .into_iter()
+                    .filter_map(|(_, issue)| issue)
+                    .map(Issue::from)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Get the authenticated user.
+    pub async fn get_current_user(&self) -> Result<User> {
+        self.get::<User>(self.endpoints.current_user()?).await
+    }
+
+    /// Get a pull request. The issue endpoint doesn't include pull request
+    /// specific details like the requested reviewers.
+    pub async fn get_pull_request(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+    ) -> Result<PullRequest> {
+        self.get::<PullRequest>(self.endpoints.pull_request(&repo, &issue_number)?)
+            .await
+    }
+
+    /// Get the review decision of a pull request and the status of the checks
+    /// on its last commit, which only the GraphQL API has in one place.
+    pub async fn get_pull_request_status(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+    ) -> Result<PullRequestStatus> {
+        let url = self.endpoints.graphql()?;
+        let variables = PullRequestVariables {
+            owner: repo.owner(),
+            name: repo.name(),
+            number: issue_number.0,
+        };
+        let data: PullRequestStatusData = request::with_retries(&url, || {
+            self.graphql(url.clone(), PULL_REQUEST_STATUS_QUERY, &variables)
+        })
+        .await?;
+        Ok(data
+            .repository
+            .and_then(|repository| repository.pull_request)
+            .map(PullRequestStatus::from)
+            .unwrap_or_default())
+    }
+
+    /// Get the OAuth scopes granted to the token, if Github reports them.
+    ///
+    /// Fine-grained personal access tokens and Github App tokens don't have
+    /// scopes, in which case `None` is returned.
+    pub async fn get_token_scopes(&self) -> Result<Option<Vec<String>>> {
+        let url = self.endpoints.base.clone();
+        let response = self
+            .limiter
+            .send(Method::GET, url.clone(), self.request(Method::GET, url))
+            .await?;
+        Ok(response
+            .headers()
+            .get(OAUTH_SCOPES_HEADER)
+            .and_then(|scopes| scopes.to_str().ok())
+            .map(|scopes| {
+                scopes
+                    .split(',')
+                    .map(|scope| scope.trim().to_owned())
+                    .filter(|scope| !scope.is_empty())
+                    .collect()
+            }))
+    }
+
+    /// The pages of open milestones of a repository.
+    pub fn milestones(&self, repo: &RepoFullName) -> Result<Pages<'_, Milestone>> {
+        Ok(Pages::new(self, self.endpoints.milestones(repo)?))
+    }
+
+    /// Get all the open milestones of a repository.
+    pub async fn get_milestones(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+    ) -> Result<Vector<Milestone>> {
+        self.milestones(&repo)?.collect().await
+    }
+
+    /// The pages of the authenticated user's notifications about a
+    /// repository, unread ones only.
+    pub fn notifications(&self, repo: &RepoFullName) -> Result<Pages<'_, Notification>> {
+        Ok(Pages::new(self, self.endpoints.repo_notifications(repo)?))
+    }
+
+    /// Get the authenticated user's unread notifications about a repository.
+    pub async fn get_notifications(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+    ) -> Result<Vector<Notification>> {
+        self.notifications(&repo)?.collect().await
+    }
+
+    /// The pages of labels defined in a repository.
+    pub fn labels(&self, repo: &RepoFullName) -> Result<Pages<'_, Label>> {
+        Ok(Pages::new(self, self.endpoints.repo_labels(repo)?))
+    }
+
+    /// Get all the labels defined in a repository.
+    pub async fn get_labels(self: Arc<Self>, repo: Arc<RepoFullName>) -> Result<Vector<Label>> {
+        self.labels(&repo)?.collect().await
+    }
+
+    /// The pages of teams of an organisation. Requires the `read:org` scope.
+    pub fn org_teams(&self, org: &str) -> Result<Pages<'_, Team>> {
+        Ok(Pages::new(self, self.endpoints.org_teams(org)?))
+    }
+
+    /// The pages of members of an organisation's team. Requires the
+    /// `read:org` scope.
+    pub fn team_members(&self, org: &str, team_slug: &str) -> Result<Pages<'_, User>> {
+        Ok(Pages::new(
+            self,
+            self.endpoints.team_members(org, team_slug)?,
+        ))
+    }
+
+    /// Get all the teams of an organisation, checking the token has the
+    /// required scope first.
+    pub async fn get_org_teams(self: Arc<Self>, org: String) -> Result<Vector<Team>> {
+        self.ensure_org_scope().await?;
+        self.org_teams(&org)?.collect().await
+    }
+
+    /// Get all the members of an organisation's team, checking the token has
+    /// the required scope first.
+    pub async fn get_team_members(
+        self: Arc<Self>,
+        org: String,
+        team: Team,
+    ) -> Result<Vector<User>> {
+        self.ensure_org_scope().await?;
+        self.team_members(&org, &team.slug)?.collect().await
+    }
+
+    /// Request a review from a team on a pull request.
+    pub async fn request_team_review(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+        team: Team,
+    ) -> Result<()> {
+        self.post(
+            self.endpoints.requested_reviewers(&repo, &issue_number)?,
+            &RequestedReviewers {
+                team_reviewers: vec![team.slug],
+            },
+        )
+        .await
+    }
+
+    /// Add labels to an issue, returning all the labels of the issue.
+    pub async fn add_labels(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+        labels: Vec<String>,
+    ) -> Result<Vector<Label>> {
+        let url = self.endpoints.issue_labels(&repo, &issue_number)?;
+        self.limiter
+            .json(
+                Method::POST,
+                url.clone(),
+                self.request(Method::POST, url.clone())
+                    .json(&IssueLabels { labels }),
+            )
+            .await
+    }
+
+    /// Assign users to an issue by their logins, returning the updated issue.
+    pub async fn add_assignees(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+        assignees: Vec<String>,
+    ) -> Result<Issue> {
+        let url = self.endpoints.issue_assignees(&repo, &issue_number)?;
+        self.limiter
+            .json(
+                Method::POST,
+                url.clone(),
+                self.request(Method::POST, url.clone())
+                    .json(&IssueAssignees { assignees }),
+            )
+            .await
+    }
+
+    /// Replace all the labels of an issue, returning the updated issue.
+    pub async fn set_labels(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+        labels: Vec<String>,
+    ) -> Result<Issue> {
+        let url = self.endpoints.issue(&repo, &issue_number)?;
+        self.limiter
+            .json(
+                Method::PATCH,
+                url.clone(),
+                self.request(Method::PATCH, url.clone())
+                    .json(&IssueLabels { labels }),
+            )
+            .await
+    }
+
+    /// Remove a label from an issue, returning the remaining labels.
+    pub async fn remove_label(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+        label: String,
+    ) -> Result<Vector<Label>> {
+        let url = self.endpoints.issue_label(&repo, &issue_number, &label)?;
+        self.limiter
+            .json(
+                Method::DELETE,
+                url.clone(),
+                self.request(Method::DELETE, url.clone()),
+            )
+            .await
+    }
+
+    /// The pages of comments on an issue or pull request, oldest first.
+    pub fn comments(
+        &self,
+        repo: &RepoFullName,
+        issue_number: &IssueNumber,
+    ) -> Result<Pages<'_, Comment>> {
+        Ok(Pages::new(
+            self,
+            self.endpoints.issue_comments(repo, issue_number)?,
+        ))
+    }
+
+    /// Get all the comments on an issue or pull request.
+    pub async fn get_comments(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+    ) -> Result<Vector<Comment>> {
+        self.comments(&repo, &issue_number)?.collect().await
+    }
+
+    /// Comment on an issue or pull request.
+    pub async fn add_comment(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+        body: String,
+    ) -> Result<()> {
+        self.post(
+            self.endpoints.issue_comments(&repo, &issue_number)?,
+            &NewComment { body },
+        )
+        .await
+    }
+
+    /// Open a new issue, returning it.
+    pub async fn create_issue(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        title: String,
+        body: String,
+    ) -> Result<Issue> {
+        let url = self.endpoints.issues(&repo)?;
+        self.limiter
+            .json(
+                Method::POST,
+                url.clone(),
+                self.request(Method::POST, url.clone())
+                    .json(&NewIssue { title, body }),
+            )
+            .await
+    }
+
+    /// Create a draft release, which stays hidden until it's published from
+    /// Github. The tag is created when the release is published, if it
+    /// doesn't exist yet.
+    pub async fn create_release_draft(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        tag_name: String,
+        body: String,
+    ) -> Result<ReleaseDraft> {
+        let url = self.endpoints.releases(&repo)?;
+        self.limiter
+            .json(
+                Method::POST,
+                url.clone(),
+                self.request(Method::POST, url.clone()).json(&NewRelease {
+                    name: tag_name.clone(),
+                    tag_name,
+                    body,
+                    draft: true,
+                }),
+            )
+            .await
+    }
+
+    /// Close an issue, returning the updated issue.
+    pub async fn close_issue(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+    ) -> Result<Issue> {
+        let url = self.endpoints.issue(&repo, &issue_number)?;
+        self.limiter
+            .json(
+                Method::PATCH,
+                url.clone(),
+                self.request(Method::PATCH, url.clone()).json(&IssueUpdate {
+                    state: IssueState::Closed,
+                }),
+            )
+            .await
+    }
+
+    /// Change the title of an issue, returning the updated issue.
+    pub async fn rename_issue(
+        self: Arc<Self>,
+        repo: Arc<RepoFullName>,
+        issue_number: IssueNumber,
+        title: String,
+    ) -> Result<Issue> {
+        let url = self.endpoints.issue(&repo, &issue_number)?;
+        self.limiter
+            .json(
+                Method::PATCH,
+                url.clone(),
+                self.request(Method::PATCH, url.clone())
+                    .json(&TitleUpdate { title }),
+            )
+            .await
+    }
+
+    async fn ensure_org_scope(&self) -> Result<()> {
+        match self.get_token_scopes().await? {
+            Some(scopes)
+                if !scopes
+                    .iter()
+                    .any(|scope| ORG_SCOPES.contains(&scope.as_str())) =>
+            {
+                Err(Error::MissingScope {
+                    scope: ORG_SCOPES[0],
+                    reason: "list teams".into(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Get a single page of a list endpoint and the URL of the next page.
+    pub(crate) async fn get_page<ItemT>(&self, url: Url) -> Result<(Vector<ItemT>, Option<Url>)>
+    where
+        for<'de> ItemT: Deserialize<'de> + Clone,
+    {
+        // Skip malformed items rather than losing the whole page
+        let (items, next): (Vec<serde_json::Value>, _) =
+            request::with_retries(&url, || self.get_revalidated(url.clone())).await?;
+        Ok((lenient::from_values(items), next))
+    }
+
+    async fn graphql<VariablesT, DataT>(
+        &self,
+        url: Url,
+        query: &str,
+        variables: &VariablesT,
+    ) -> Result<DataT>
+    where
+        VariablesT: Serialize,
+        for<'de> DataT: Deserialize<'de>,
+    {
+        let body = self
+            .limiter
+            .text(
+                Method::POST,
+                url.clone(),
+                self.request(Method::POST, url.clone())
+                    .json(&graphql::Query { query, variables }),
+            )
+            .await?;
+        request::parse::<graphql::Response<DataT>>(url.clone(), &body)?.into_data(url)
+    }
+
+    async fn post<BodyT>(&self, url: Url, body: &BodyT) -> Result<()>
+    where
+        BodyT: Serialize,
+    {
+        self.limiter
+            .send(
+                Method::POST,
+                url.clone(),
+                self.request(Method::POST, url).json(body),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get<SuccessT>(&self, url: Url) -> Result<SuccessT>
+    where
+        for<'de> SuccessT: Deserialize<'de>,
+    {
+        Ok(
+            request::with_retries(&url, || self.get_revalidated(url.clone()))
+                .await?
+                .0,
+        )
+    }
+
+    /// Sends a GET request with the `ETag` of the last response from the URL,
+    /// if any, reusing that response when Github says it's still current.
+    /// Also returns the next page of a list endpoint. A response which
+    /// doesn't parse is fetched once more before giving up, like
+    /// `request::get_json`, and never cached.
+    async fn get_revalidated<SuccessT>(&self, url: Url) -> Result<(SuccessT, Option<Url>)>
+    where
+        for<'de> SuccessT: Deserialize<'de>,
+    {
+        let cached = self.etags.get(&url);
+        let mut attempt = 0;
+        loop {
+            let mut request = self.request(Method::GET, url.clone());
+            if let Some(ref cached) = cached {
+                request = request.header(IF_NONE_MATCH, cached.etag.clone());
+            }
+            // Hold the permit until the body is read, not just the headers
+            let _permit = self.limiter.acquire().await;
+            let response = request::send(Method::GET, url.clone(), request).await?;
+            if let Some(rate_limit) = RateLimit::from_headers(response.headers()) {
+                *self.rate_limit.lock().expect("rate limit lock is poisoned") = Some(rate_limit);
+            }
+            if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), cached.as_ref()) {
+                tracing::debug!(%url, "response not modified");
+                return Ok((request::parse(url, &cached.body)?, cached.next.clone()));
+            }
+
+            let etag = response.headers().get(ETAG).cloned();
+            let next = response
+                .headers()
+                .get(LINK)
+                .and_then(|link| link.to_str().ok())
+                .and_then(pages::next_link);
+            let body = request::text(Method::GET, url.clone(), response).await?;
+            match request::parse(url.clone(), &body) {
+                Ok(value) => {
+                    if let Some(etag) = etag {
+                        self.etags.insert(
+                            url,
+                            Cached {
+                                etag,
+                                body: body.into(),
+                                next: next.clone(),
+                            },
+                        );
+                    }
+                    return Ok((value, next));
+                }
+                Err(Error::Json { .. }) if attempt < request::JSON_RETRIES => {
+                    attempt += 1;
+                    tracing::debug!(%url, attempt, "retrying request after a schema mismatch");
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    fn request(&self, method: Method, url: Url) -> RequestBuilder {
+        self.http_client
+            .request(method, url)
+            .header(ACCEPT, ACCEPT_API_V3)
+            .header(USER_AGENT, USER_AGENT_VALUE)
+            .header("authorization", &self.authorization_token)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RepoVariables<'a> {
+    owner: &'a str,
+    name: &'a str,
+}
+
+/// The issues asked for by `get_issues`, by their alias in the query.
+#[derive(Debug, Deserialize)]
+struct IssuesData {
+    repository: Option<HashMap<String, Option<GraphQlIssue>>>,
+}
+
+/// An issue or pull request as returned by the GraphQL API, which names and
+/// nests fields differently to the REST API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlIssue {
+    #[serde(rename = "__typename")]
+    typename: String,
+    number: IssueNumber,
+    #[serde(default)]
+    title: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    author: User,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    body: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    labels: Nodes<Label>,
+    #[serde(default)]
+    assignees: Nodes<User>,
+    #[serde(default)]
+    reactions: TotalCount,
+    #[serde(default)]
+    comments: TotalCount,
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<GraphQlIssue> for Issue {
+    fn from(issue: GraphQlIssue) -> Self {
+        Self {
+            number: issue.number,
+            title: issue.title,
+            user: issue.author,
+            body: issue.body,
+            // Merged pull requests are closed as far as the REST API goes
+            state: if issue.state == "OPEN" {
+                IssueState::Open
+            } else {
+                IssueState::Closed
+            },
+            labels: issue.labels.nodes,
+            assignees: issue.assignees.nodes,
+            reactions: Reactions {
+                total_count: issue.reactions.total_count,
+            },
+            comments: issue.comments.total_count,
+            pull_request: if issue.typename == "PullRequest" {
+                Some(PullRequestRefs {})
+            } else {
+                None
+            },
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PullRequestVariables<'a> {
+    owner: &'a str,
+    name: &'a str,
+    number: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestStatusData {
+    repository: Option<PullRequestStatusRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PullRequestStatusRepository {
+    pull_request: Option<GraphQlPullRequestStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlPullRequestStatus {
+    #[serde(default)]
+    review_decision: Option<String>,
+    #[serde(default)]
+    commits: Nodes<GraphQlCommitNode>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct GraphQlCommitNode {
+    commit: GraphQlCommit,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlCommit {
+    #[serde(default)]
+    status_check_rollup: Option<GraphQlStatusCheckRollup>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct GraphQlStatusCheckRollup {
+    state: String,
+}
+
+impl From<GraphQlPullRequestStatus> for PullRequestStatus {
+    fn from(status: GraphQlPullRequestStatus) -> Self {
+        let review = match status.review_decision.as_deref() {
+            Some("APPROVED") => Some(ReviewDecision::Approved),
+            Some("CHANGES_REQUESTED") => Some(ReviewDecision::ChangesRequested),
+            Some("REVIEW_REQUIRED") => Some(ReviewDecision::ReviewRequired),
+            _ => None,
+        };
+        let checks = status
+            .commits
+            .nodes
+            .last()
+            .and_then(|node| node.commit.status_check_rollup.as_ref())
+            .map(|rollup| match rollup.state.as_str() {
+                "SUCCESS" => CheckStatus::Success,
+                "FAILURE" | "ERROR" => CheckStatus::Failure,
+                _ => CheckStatus::Pending,
+            });
+        Self { review, checks }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TotalCount {
+    #[serde(default)]
+    total_count: usize,
+}
+
+/// A query for the issues or pull requests with the given numbers, each
+/// under an alias, as GraphQL has no way to ask for a list of numbers.
+fn issues_query(issue_numbers: &[IssueNumber]) -> String {
+    let mut query = String::from(
+        "query($owner: String!, $name: String!) { repository(owner: $owner, name: $name) {",
+    );
+    for issue_number in issue_numbers {
+        query.push_str(&format!(
+            " issue{0}: issueOrPullRequest(number: {0}) {{ ...issue ...pullRequest }}",
+            issue_number.0
+        ));
+    }
+    query.push_str(&format!(
+        " }} }} fragment issue on Issue {{ {0} }} fragment pullRequest on PullRequest {{ {0} }}",
+        ISSUE_FIELDS
+    ));
+    query
+}
+
+#[derive(Debug, Serialize)]
+struct RequestedReviewers {
+    team_reviewers: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct NewIssue {
+    title: String,
+    body: String,
+}
+
+#[derive(Debug, Serialize)]
+struct NewRelease {
+    tag_name: String,
+    name: String,
+    body: String,
+    draft: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct NewComment {
+    body: String,
+}
+
+#[derive(Debug, Serialize)]
+struct IssueUpdate {
+    state: IssueState,
+}
+
+#[derive(Debug, Serialize)]
+struct TitleUpdate {
+    title: String,
+}
+
+#[derive(Debug, Serialize)]
+struct IssueLabels {
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct IssueAssignees {
+    assignees: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct RepoFullName(pub String);
+
+impl RepoFullName {
+    /// The owner (user or organisation) of the repository.
+    pub fn owner(&self) -> &str {
+        self.0.split('/').next().unwrap_or(&self.0)
+    }
+
+    /// The name of the repository, without its owner.
+    pub fn name(&self) -> &str {
+        self.0.splitn(2, '/').nth(1).unwrap_or(&self.0)
+    }
+}
+
+impl std::str::FromStr for RepoFullName {
+    type Err = std::convert::Infallible;
+
+    fn from_str(full_name: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self(full_name.into()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token(pub String);
+
+impl std::str::FromStr for Token {
+    type Err = std::convert::Infallible;
+
+    fn from_str(token: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(token.into())
+    }
+}
+
+impl<T> From<T> for Token
+where
+    T: Into<String>,
+{
+    fn from(token: T) -> Self {
+        Self(token.into())
+    }
+}
+
+#[derive(Debug)]
+struct Endpoints {
+    base: Url,
+}
+
+impl Endpoints {
+    pub fn new(base: Url) -> Result<Self> {
+        Ok(Endpoints { base })
+    }
+
+    fn repo(&self, full_name: &RepoFullName) -> Result<Url> {
+        self.base
+            .join(&format!("/repos/{}", full_name.0,))
+            .map_err(|source| Error::Url {
+                description: format!("Github repo `{}`", full_name.0),
+                source,
+            })
+    }
+
+    fn repo_by_id(&self, repo_id: RepoId) -> Result<Url> {
+        self.base
+            .join(&format!("/repositories/{}", repo_id.0))
+            .map_err(|source| Error::Url {
+                description: format!("Github repo with id {}", repo_id.0),
+                source,
+            })
+    }
+
+    fn issues(&self, repo: &RepoFullName) -> Result<Url> {
+        self.base
+            .join(&format!("/repos/{}/issues", repo.0))
+            .map_err(|source| Error::Url {
+                description: format!("issues of Github repo `{}`", repo.0),
+                source,
+            })
+    }
+
+    fn releases(&self, repo: &RepoFullName) -> Result<Url> {
+        self.base
+            .join(&format!("/repos/{}/releases", repo.0))
+            .map_err(|source| Error::Url {
+                description: format!("releases of Github repo `{}`", repo.0),
+                source,
+            })
+    }
+
+    fn issue(&self, repo: &RepoFullName, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "/repos/{repo}/issues/{issue_number}",
+                repo = repo.0,
+                issue_number = issue_number.0,
+            ))
+            .map_err(|source| Error::Url {
+                description: format!("Github issue `{}` for repo `{}`", issue_number.0, repo.0),
+                source,
+            })
+    }
+
+    fn graphql(&self) -> Result<Url> {
+        self.base.join("/graphql").map_err(|source| Error::Url {
+            description: "the GraphQL API".into(),
+            source,
+        })
+    }
+
+    fn current_user(&self) -> Result<Url> {
+        self.base.join("/user").map_err(|source| Error::Url {
+            description: "the authenticated Github user".into(),
+            source,
+        })
+    }
+
+    fn pull_request(&self, repo: &RepoFullName, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "/repos/{repo}/pulls/{issue_number}",
+                repo = repo.0,
+                issue_number = issue_number.0,
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "Github pull request `{}` for repo `{}`",
+                    issue_number.0, repo.0
+                ),
+                source,
+            })
+    }
+
+    fn requested_reviewers(&self, repo: &RepoFullName, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "/repos/{repo}/pulls/{issue_number}/requested_reviewers",
+                repo = repo.0,
+                issue_number = issue_number.0,
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "reviewers of pull request `{}` for repo `{}`",
+                    issue_number.0, repo.0
+                ),
+                source,
+            })
+    }
+
+    fn issue_labels(&self, repo: &RepoFullName, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "/repos/{repo}/issues/{issue_number}/labels",
+                repo = repo.0,
+                issue_number = issue_number.0,
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "labels of Github issue `{}` for repo `{}`",
+                    issue_number.0, repo.0
+                ),
+                source,
+            })
+    }
+
+    fn issue_assignees(&self, repo: &RepoFullName, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "/repos/{repo}/issues/{issue_number}/assignees",
+                repo = repo.0,
+                issue_number = issue_number.0,
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "assignees of Github issue `{}` for repo `{}`",
+                    issue_number.0, repo.0
+                ),
+                source,
+            })
+    }
+
+    fn issue_comments(&self, repo: &RepoFullName, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "/repos/{repo}/issues/{issue_number}/comments",
+                repo = repo.0,
+                issue_number = issue_number.0,
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "comments of Github issue `{}` for repo `{}`",
+                    issue_number.0, repo.0
+                ),
+                source,
+            })
+    }
+
+    fn issue_label(
+        &self,
+        repo: &RepoFullName,
+        issue_number: &IssueNumber,
+        label: &str,
+    ) -> Result<Url> {
+        let mut url = self.issue_labels(repo, issue_number)?;
+        // Label names can contain spaces and slashes, let `Url` escape them
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.push(label);
+        }
+        Ok(url)
+    }
+
+    fn repo_labels(&self, repo: &RepoFullName) -> Result<Url> {
+        self.base
+            .join(&format!("/repos/{}/labels", repo.0))
+            .map_err(|source| Error::Url {
+                description: format!("labels of Github repo `{}`", repo.0),
+                source,
+            })
+    }
+
+    fn repo_notifications(&self, repo: &RepoFullName) -> Result<Url> {
+        self.base
+            .join(&format!("/repos/{}/notifications", repo.0))
+            .map_err(|source| Error::Url {
+                description: format!("notifications about Github repo `{}`", repo.0),
+                source,
+            })
+    }
+
+    fn milestones(&self, repo: &RepoFullName) -> Result<Url> {
+        self.base
+            .join(&format!("/repos/{}/milestones", repo.0))
+            .map_err(|source| Error::Url {
+                description: format!("milestones of Github repo `{}`", repo.0),
+                source,
+            })
+    }
+
+    fn org_teams(&self, org: &str) -> Result<Url> {
+        self.base
+            .join(&format!("/orgs/{}/teams", org))
+            .map_err(|source| Error::Url {
+                description: format!("teams of Github org `{}`", org),
+                source,
+            })
+    }
+
+    fn team_members(&self, org: &str, team_slug: &str) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "/orgs/{org}/teams/{team}/members",
+                org = org,
+                team = team_slug,
+            ))
+            .map_err(|source| Error::Url {
+                description: format!("members of team `{}` in Github org `{}`", team_slug, org),
+                source,
+            })
+    }
+}
+
+static DEFAULT_ENDPOINT: Lazy<Url> =
+    Lazy::new(|| Url::parse("https://api.github.com").expect("Default URL is well-formed"));
+
+const ACCEPT_API_V3: &str = "application/vnd.github.v3+json";
+
+/// How many issues `get_issues` asks for in one query, well within the limits
+/// Github puts on the cost of a query.
+pub const ISSUES_PER_QUERY: usize = 50;
+
+/// The fields of an issue or pull request `get_issues` asks for, those the
+/// REST API returns and zentui uses.
+const ISSUE_FIELDS: &str = "__typename number title body author { login } state \
+     labels(first: 50) { nodes { name color } } assignees(first: 20) { nodes { login } } \
+     reactions { totalCount } comments { totalCount } createdAt updatedAt";
+const PULL_REQUEST_STATUS_QUERY: &str = "query($owner: String!, $name: String!, $number: Int!) { \
+    repository(owner: $owner, name: $name) { pullRequest(number: $number) { reviewDecision \
+    commits(last: 1) { nodes { commit { statusCheckRollup { state } } } } } } }";
+const USER_AGENT_VALUE: &str = "zentui/0.0.1";
+const OAUTH_SCOPES_HEADER: &str = "x-oauth-scopes";
+const RATE_LIMIT_HEADER: &str = "x-ratelimit-limit";
+const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+const RATE_LIMIT_RESET_HEADER: &str = "x-ratelimit-reset";
+const ORG_SCOPES: [&str; 3] = ["read:org", "write:org", "admin:org"];
@@ -0,0 +1,88 @@
+//! The envelope of GraphQL requests and responses, shared by the clients of
+//! APIs with a GraphQL endpoint.
+
+use im::Vector;
+use reqwest::Url;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt::{self, Debug};
+
+use crate::{
+    error::{Error, Result},
+    lenient,
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Query<'a, VariablesT> {
+    pub query: &'a str,
+    pub variables: VariablesT,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Response<DataT> {
+    #[serde(default = "Option::default")]
+    data: Option<DataT>,
+    #[serde(default)]
+    errors: Vec<ErrorMessage>,
+}
+
+/// A list in a response, e.g. the labels of an issue. Items which don't
+/// parse are skipped.
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "ItemT: serde::de::DeserializeOwned + Clone"))]
+pub(crate) struct Nodes<ItemT> {
+    #[serde(default, deserialize_with = "lenient::skip_invalid")]
+    pub nodes: Vector<ItemT>,
+}
+
+// im only implements `Debug` for vectors of `Clone` items
+impl<ItemT: Clone + Debug> Debug for Nodes<ItemT> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Nodes")
+            .field("nodes", &self.nodes)
+            .finish()
+    }
+}
+
+impl<ItemT: Clone> Default for Nodes<ItemT> {
+    fn default() -> Self {
+        Self {
+            nodes: Vector::new(),
+        }
+    }
+}
+
+/// Where a page of a paginated list ends, to ask for the next one.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PageInfo {
+    #[serde(default)]
+    pub has_next_page: bool,
+    #[serde(default)]
+    pub end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorMessage {
+    message: String,
+}
+
+impl<DataT> Response<DataT> {
+    /// The data of the response. Errors only fail the request when there is
+    /// no data at all; with partial data, e.g. when one of several issues
+    /// doesn't exist, the rest is still usable.
+    pub(crate) fn into_data(self, url: Url) -> Result<DataT> {
+        match self.data {
+            Some(data) => {
+                for error in self.errors.iter() {
+                    tracing::debug!(%url, message = %error.message, "partial GraphQL response");
+                }
+                Ok(data)
+            }
+            None => Err(Error::GraphQl {
+                url,
+                messages: self.errors.into_iter().map(|error| error.message).collect(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use serde_json::Value;
+use std::iter::FromIterator;
+
+/// Deserializes a list, skipping the items which don't have the expected
+/// shape instead of failing the whole response. Skipped items are logged, so
+/// one odd issue doesn't take the rest of the board down with it.
+pub(crate) fn skip_invalid<'de, DeserializerT, ItemT, CollectionT>(
+    deserializer: DeserializerT,
+) -> std::result::Result<CollectionT, DeserializerT::Error>
+where
+    DeserializerT: Deserializer<'de>,
+    ItemT: DeserializeOwned,
+    CollectionT: FromIterator<ItemT>,
+{
+    let values: Vec<Value> = Deserialize::deserialize(deserializer)?;
+    Ok(from_values(values))
+}
+
+/// Deserializes `null` as the default value, e.g. an empty string for an
+/// issue without a description.
+pub(crate) fn null_as_default<'de, DeserializerT, ValueT>(
+    deserializer: DeserializerT,
+) -> std::result::Result<ValueT, DeserializerT::Error>
+where
+    DeserializerT: Deserializer<'de>,
+    ValueT: Deserialize<'de> + Default,
+{
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Parses each value as an item, skipping and logging those which fail.
+pub(crate) fn from_values<ItemT, CollectionT>(values: Vec<Value>) -> CollectionT
+where
+    ItemT: DeserializeOwned,
+    CollectionT: FromIterator<ItemT>,
+{
+    values
+        .into_iter()
+        .filter_map(|value| match serde_json::from_value(value.clone()) {
+            Ok(item) => Some(item),
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    item = %value,
+                    "skipping an item which doesn't match the expected schema, please report it"
+                );
+                None
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,23 @@
+//! Typed clients for the Github and Zenhub REST APIs, for posting to chat
+//! webhooks and for downloading issue attachments.
+//!
+//! The clients are shared by the terminal UI and any headless subcommands;
+//! they know nothing about how the data is displayed.
+
+mod error;
+mod etag;
+mod graphql;
+mod lenient;
+mod pages;
+mod request;
+
+pub mod attachment;
+pub mod github;
+pub mod webhook;
+pub mod zenhub;
+
+pub use error::{Error, Result};
+pub use pages::Pages;
+
+/// The version of the client crate, for diagnostics.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,105 @@
+use im::Vector;
+use reqwest::Url;
+use serde::Deserialize;
+use std::marker::PhantomData;
+
+use crate::{error::Result, github::Client};
+
+/// The pages of a paginated Github list endpoint. Each page is fetched on
+/// demand, following the `next` link Github returns with the previous page.
+#[derive(Debug)]
+pub struct Pages<'a, ItemT> {
+    client: &'a Client,
+    next: Option<Url>,
+    _item: PhantomData<ItemT>,
+}
+
+impl<'a, ItemT> Pages<'a, ItemT>
+where
+    for<'de> ItemT: Deserialize<'de> + Clone,
+{
+    pub(crate) fn new(client: &'a Client, first: Url) -> Self {
+        Self {
+            client,
+            next: Some(first),
+            _item: PhantomData,
+        }
+        .per_page(MAX_PER_PAGE)
+    }
+
+    /// Sets the number of items requested per page. Github caps it at 100.
+    pub fn per_page(mut self, per_page: usize) -> Self {
+        if let Some(url) = self.next.as_mut() {
+            let pairs: Vec<(String, String)> = url
+                .query_pairs()
+                .filter(|(key, _)| key != "per_page")
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                .collect();
+            url.query_pairs_mut()
+                .clear()
+                .extend_pairs(pairs)
+                .append_pair("per_page", &per_page.min(MAX_PER_PAGE).to_string());
+        }
+        self
+    }
+
+    /// Fetches the next page, or returns `None` after the last one.
+    pub async fn next_page(&mut self) -> Option<Result<Vector<ItemT>>> {
+        let url = self.next.take()?;
+        let client = self.client;
+        Some(client.get_page(url).await.map(|(items, next)| {
+            self.next = next;
+            items
+        }))
+    }
+
+    /// Fetches all remaining pages and concatenates their items.
+    pub async fn collect(mut self) -> Result<Vector<ItemT>> {
+        let mut items = Vector::new();
+        while let Some(page) = self.next_page().await {
+            items.append(page?);
+        }
+        Ok(items)
+    }
+}
+
+/// Extracts the URL with `rel="next"` from a `Link` header, e.g.
+/// `<https://api.github.com/orgs/o/teams?page=2>; rel="next", <...>; rel="last"`.
+pub(crate) fn next_link(link: &str) -> Option<Url> {
+    link.split(',').find_map(|link| {
+        let mut parts = link.split(';');
+        let url = parts.next()?.trim().strip_prefix('<')?.strip_suffix('>')?;
+        if parts.any(|param| param.trim() == r#"rel="next""#) {
+            Url::parse(url).ok()
+        } else {
+            None
+        }
+    })
+}
+
+const MAX_PER_PAGE: usize = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_link_is_found_among_other_relations() {
+        let link = concat!(
+            r#"<https://api.github.com/orgs/o/teams?page=1>; rel="prev", "#,
+            r#"<https://api.github.com/orgs/o/teams?page=3>; rel="next", "#,
+            r#"<https://api.github.com/orgs/o/teams?page=5>; rel="last""#
+        );
+        assert_eq!(
+            next_link(link).map(String::from),
+            Some("https://api.github.com/orgs/o/teams?page=3".to_owned())
+        );
+    }
+
+    #[test]
+    fn no_next_link_on_the_last_page() {
+        let link = r#"<https://api.github.com/orgs/o/teams?page=1>; rel="first""#;
+        assert_eq!(next_link(link), None);
+        assert_eq!(next_link(""), None);
+    }
+}
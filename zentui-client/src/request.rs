@@ -0,0 +1,257 @@
+use reqwest::{Method, RequestBuilder, Response, StatusCode, Url};
+use serde::Deserialize;
+use std::{future::Future, time::Duration};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::Instrument;
+
+use crate::error::{Error, Result};
+
+/// Sends a request, turning transport failures and non-success status codes
+/// into errors which record the method and URL of the request. `304 Not
+/// Modified` isn't an error, the answer to a conditional request whose cached
+/// response is still current.
+pub(crate) async fn send(method: Method, url: Url, request: RequestBuilder) -> Result<Response> {
+    let span = tracing::debug_span!("api_request", %method, %url);
+    let response = async {
+        tracing::debug!("sending request");
+        request.send().await
+    }
+    .instrument(span.clone())
+    .await
+    .map_err(|source| Error::Request {
+        method: method.clone(),
+        url: url.clone(),
+        source,
+    })?;
+    let status = response.status();
+    span.in_scope(|| tracing::debug!(%method, %url, status = status.as_u16(), "received response"));
+    if status.is_success() || status == StatusCode::NOT_MODIFIED {
+        Ok(response)
+    } else if let Some(sso) = response
+        .headers()
+        .get(SSO_HEADER)
+        .and_then(|sso| sso.to_str().ok())
+        .filter(|sso| status == StatusCode::FORBIDDEN && sso.starts_with("required"))
+    {
+        Err(Error::SamlEnforced {
+            method,
+            url,
+            authorize_url: sso_authorize_url(sso),
+        })
+    } else {
+        Err(Error::Status {
+            method,
+            url,
+            status,
+        })
+    }
+}
+
+/// The URL to authorize a token for an organization at, from Github's SSO
+/// header, e.g. `required; url=https://github.com/orgs/acme/sso?...`.
+fn sso_authorize_url(sso: &str) -> Option<String> {
+    sso.split(';')
+        .map(str::trim)
+        .find_map(|part| part.strip_prefix("url="))
+        .map(str::to_owned)
+}
+
+/// Caps how many requests a client has in flight at once, from sending them
+/// until their body is read. Loading a big board queues its requests here
+/// rather than sending them all together.
+#[derive(Debug)]
+pub(crate) struct Limiter {
+    semaphore: Semaphore,
+}
+
+impl Limiter {
+    /// A limiter of `max_in_flight` requests, which must be at least 1.
+    pub(crate) fn new(max_in_flight: usize) -> Self {
+        debug_assert!(max_in_flight > 0, "no request could ever be sent");
+        Self {
+            semaphore: Semaphore::new(max_in_flight),
+        }
+    }
+
+    /// Waits until fewer than the maximum requests are in flight. The request
+    /// counts as in flight until the permit is dropped.
+    pub(crate) async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.semaphore.acquire().await
+    }
+
+    /// Sends a request whose body isn't read, once fewer than the maximum are
+    /// in flight.
+    pub(crate) async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        request: RequestBuilder,
+    ) -> Result<Response> {
+        let _permit = self.acquire().await;
+        send(method, url, request).await
+    }
+
+    /// Sends a request and reads its body, once fewer than the maximum are
+    /// in flight.
+    pub(crate) async fn text(
+        &self,
+        method: Method,
+        url: Url,
+        request: RequestBuilder,
+    ) -> Result<String> {
+        let _permit = self.acquire().await;
+        let response = send(method.clone(), url.clone(), request).await?;
+        text(method, url, response).await
+    }
+
+    /// Sends a request and parses its body as JSON, once fewer than the
+    /// maximum are in flight.
+    pub(crate) async fn json<SuccessT>(
+        &self,
+        method: Method,
+        url: Url,
+        request: RequestBuilder,
+    ) -> Result<SuccessT>
+    where
+        for<'de> SuccessT: Deserialize<'de>,
+    {
+        let body = self.text(method, url.clone(), request).await?;
+        parse(url, &body)
+    }
+}
+
+/// Sends a request with `send` until it succeeds or fails for good, waiting
+/// twice as long after each transient failure, e.g. a 502 from a deploy or a
+/// dropped connection. Otherwise a single flaky request leaves a card showing
+/// an error until the board is reloaded.
+pub(crate) async fn with_retries<SuccessT, SendT, ResponseT>(
+    url: &Url,
+    send: SendT,
+) -> Result<SuccessT>
+where
+    SendT: Fn() -> ResponseT,
+    ResponseT: Future<Output = Result<SuccessT>>,
+{
+    let mut attempt = 0;
+    loop {
+        match send().await {
+            Err(error) if attempt < MAX_RETRIES && is_transient(&error) => {
+                let delay = FIRST_RETRY_DELAY * 2u32.pow(attempt);
+                attempt += 1;
+                tracing::debug!(
+                    %url,
+                    attempt,
+                    delay_ms = delay.as_millis() as u64,
+                    %error,
+                    "retrying request after a transient failure"
+                );
+                tokio::time::delay_for(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether a request which failed may succeed if sent again: the server
+/// errored, or the connection failed or timed out.
+fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::Status { status, .. } => status.is_server_error(),
+        Error::Request { source, .. } => source.is_timeout() || source.is_connect(),
+        _ => false,
+    }
+}
+
+/// Sends a GET request with `send` and parses the response as JSON. A
+/// response which doesn't parse is fetched once more before giving up, as it
+/// may have been served while the API was being deployed.
+pub(crate) async fn get_json<SuccessT, SendT, ResponseT>(url: Url, send: SendT) -> Result<SuccessT>
+where
+    for<'de> SuccessT: Deserialize<'de>,
+    SendT: Fn() -> ResponseT,
+    ResponseT: Future<Output = Result<Response>>,
+{
+    let mut attempt = 0;
+    loop {
+        let response = send().await?;
+        match json(Method::GET, url.clone(), response).await {
+            Err(Error::Json { .. }) if attempt < JSON_RETRIES => {
+                attempt += 1;
+                tracing::debug!(%url, attempt, "retrying request after a schema mismatch");
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Reads the body of a response to a `method` request and parses it as
+/// JSON.
+pub(crate) async fn json<SuccessT>(method: Method, url: Url, response: Response) -> Result<SuccessT>
+where
+    for<'de> SuccessT: Deserialize<'de>,
+{
+    let body = text(method, url.clone(), response).await?;
+    parse(url, &body)
+}
+
+/// Reads the body of a response to a `method` request.
+pub(crate) async fn text(method: Method, url: Url, response: Response) -> Result<String> {
+    response.text().await.map_err(|source| Error::Request {
+        method,
+        url,
+        source,
+    })
+}
+
+/// Parses a response body as JSON. When the body doesn't have the expected
+/// shape, it is logged to help report the API change.
+pub(crate) fn parse<SuccessT>(url: Url, body: &str) -> Result<SuccessT>
+where
+    for<'de> SuccessT: Deserialize<'de>,
+{
+    serde_json::from_str(body).map_err(|source| {
+        tracing::error!(
+            %url,
+            error = %source,
+            body = %truncate(body, MAX_LOGGED_BODY_LEN),
+            "response doesn't match the expected schema"
+        );
+        Error::Json {
+            url,
+            field: mismatched_field(&source),
+            source,
+        }
+    })
+}
+
+/// The field named in a serde error, e.g. `labels` in "missing field
+/// `labels` at line 1 column 20".
+fn mismatched_field(error: &serde_json::Error) -> Option<String> {
+    let message = error.to_string();
+    if !["missing field", "unknown field", "duplicate field"]
+        .iter()
+        .any(|prefix| message.starts_with(prefix))
+    {
+        return None;
+    }
+    let start = message.find('`')? + 1;
+    let end = start + message[start..].find('`')?;
+    Some(message[start..end].to_owned())
+}
+
+fn truncate(text: &str, max_len: usize) -> &str {
+    match text.char_indices().nth(max_len) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+pub(crate) const JSON_RETRIES: usize = 1;
+/// Requests taking longer than these fail with a timeout, which is retried
+/// like any other transient failure rather than hanging the app.
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+pub(crate) const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_RETRIES: u32 = 3;
+const FIRST_RETRY_DELAY: Duration = Duration::from_millis(500);
+const SSO_HEADER: &str = "x-github-sso";
+const MAX_LOGGED_BODY_LEN: usize = 16 * 1024;
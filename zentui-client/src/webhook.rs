@@ -0,0 +1,63 @@
+use reqwest::{Client as HttpClient, Method, Url};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::{
+    error::{Error, Result},
+    request,
+};
+
+/// The chat service behind an incoming webhook, which decides the shape of
+/// the payload.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    Slack,
+    Teams,
+    Discord,
+}
+
+impl Default for WebhookKind {
+    fn default() -> Self {
+        Self::Slack
+    }
+}
+
+/// Posts messages to incoming webhooks of Slack, Microsoft Teams or Discord.
+#[derive(Debug)]
+pub struct Client {
+    http_client: HttpClient,
+}
+
+impl Client {
+    pub fn new() -> Result<Client> {
+        Ok(Client {
+            http_client: HttpClient::builder().build().map_err(Error::Client)?,
+        })
+    }
+
+    /// Post a plain text (or markdown, where supported) message.
+    pub async fn post_message(
+        self: Arc<Self>,
+        kind: WebhookKind,
+        url: String,
+        text: String,
+    ) -> Result<()> {
+        let url = Url::parse(&url).map_err(|source| Error::Url {
+            description: "webhook".into(),
+            source,
+        })?;
+        let payload = match kind {
+            WebhookKind::Slack | WebhookKind::Teams => json!({ "text": text }),
+            WebhookKind::Discord => json!({ "content": text }),
+        };
+        request::send(
+            Method::POST,
+            url.clone(),
+            self.http_client.post(url).json(&payload),
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,1317 @@
+use chrono::{DateTime, Utc};
+use im::Vector;
+use once_cell::sync::Lazy;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION},
+    Client as HttpClient, Method, StatusCode, Url,
+};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::{Error, Result},
+    github::{IssueNumber, RepoId},
+    graphql::{self, Nodes, PageInfo},
+    lenient, request,
+};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Board {
+    #[serde(deserialize_with = "lenient::skip_invalid")]
+    pub pipelines: Vec<Pipeline>,
+}
+
+/// A Zenhub workspace, which has its own board for each of its repos.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Workspace {
+    pub id: WorkspaceId,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub description: String,
+    #[serde(default)]
+    pub repositories: Vec<RepoId>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct WorkspaceId(pub String);
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Pipeline {
+    pub id: String,
+    pub name: String,
+    #[serde(default, deserialize_with = "lenient::skip_invalid")]
+    pub issues: Vector<IssueRef>,
+}
+
+impl Pipeline {
+    /// Orders the issues by their priority in Zenhub. Ties, e.g. closed
+    /// issues which have no position, are broken by issue number and then
+    /// repo, so the same pipeline always shows in the same order whatever
+    /// order the API returned its issues in.
+    pub fn sort_issues(&mut self) {
+        self.issues
+            .sort_by(|left, right| left.board_order().cmp(&right.board_order()));
+    }
+}
+
+/// The special pipelines Zenhub creates for every workspace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PipelineKind {
+    NewIssues,
+    Icebox,
+    Closed,
+    Other,
+}
+
+impl Pipeline {
+    pub fn kind(&self) -> PipelineKind {
+        match self.name.trim().to_lowercase().as_str() {
+            "new issues" => PipelineKind::NewIssues,
+            "icebox" => PipelineKind::Icebox,
+            "closed" => PipelineKind::Closed,
+            _ => PipelineKind::Other,
+        }
+    }
+}
+
+/// Where to place an issue when moving it to a pipeline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Position {
+    Top,
+    Bottom,
+    Index(usize),
+}
+
+impl Serialize for Position {
+    fn serialize<SerializerT>(
+        &self,
+        serializer: SerializerT,
+    ) -> std::result::Result<SerializerT::Ok, SerializerT::Error>
+    where
+        SerializerT: Serializer,
+    {
+        match *self {
+            Position::Top => serializer.serialize_str("top"),
+            Position::Bottom => serializer.serialize_str("bottom"),
+            Position::Index(index) => serializer.serialize_u64(index as u64),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Connection {
+    issue_number: IssueNumber,
+    connected_repo_id: RepoId,
+    pull_request_number: IssueNumber,
+}
+
+#[derive(Debug, Serialize)]
+struct EpicConversion<'a> {
+    issues: &'a [EpicIssue],
+}
+
+#[derive(Debug, Deserialize)]
+struct Dependencies {
+    #[serde(default, deserialize_with = "lenient::skip_invalid")]
+    dependencies: Vector<Dependency>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReleaseIssuesUpdate<'a> {
+    add_issues: &'a [EpicIssue],
+    remove_issues: &'a [EpicIssue],
+}
+
+#[derive(Debug, Serialize)]
+struct EstimateUpdate {
+    estimate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct IssueMove<'a> {
+    pipeline_id: &'a str,
+    position: Position,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct IssueRef {
+    #[serde(rename = "issue_number")]
+    pub number: IssueNumber,
+    #[serde(default)]
+    pub estimate: Option<Estimate>,
+    /// The priority of the issue in its pipeline, as ordered in Zenhub. Not
+    /// set for closed issues.
+    #[serde(default)]
+    pub position: Option<usize>,
+    #[serde(default)]
+    pub is_epic: bool,
+    /// The repo the issue belongs to. Workspace boards can hold issues from
+    /// several repos, older boards leave it out.
+    #[serde(default)]
+    pub repo_id: Option<RepoId>,
+}
+
+impl IssueRef {
+    /// Where the issue goes in its pipeline, see `Pipeline::sort_issues`.
+    pub fn board_order(&self) -> (usize, IssueNumber, Option<RepoId>) {
+        (
+            self.position.unwrap_or(usize::MAX),
+            self.number,
+            self.repo_id,
+        )
+    }
+
+    /// The repo of the issue, if it isn't the board's repo.
+    pub fn foreign_repo(&self, board_repo: RepoId) -> Option<RepoId> {
+        self.repo_id.filter(|&repo_id| repo_id != board_repo)
+    }
+
+    /// Whether this is issue `issue_number` of the board's repo. Numbers
+    /// alone are ambiguous on boards spanning several repos.
+    pub fn refers_to(&self, board_repo: RepoId, issue_number: IssueNumber) -> bool {
+        self.number == issue_number && self.foreign_repo(board_repo).is_none()
+    }
+}
+
+/// What Zenhub knows about a single issue, fetched on its own rather than as
+/// part of the board.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct IssueData {
+    #[serde(default)]
+    pub estimate: Option<Estimate>,
+    #[serde(default)]
+    pub pipeline: Option<PipelineName>,
+    #[serde(default)]
+    pub is_epic: bool,
+}
+
+/// The issues grouped under an epic.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Epic {
+    #[serde(deserialize_with = "lenient::skip_invalid")]
+    pub issues: Vector<EpicIssue>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EpicIssue {
+    #[serde(rename = "issue_number")]
+    pub number: IssueNumber,
+    pub repo_id: RepoId,
+}
+
+/// A Zenhub dependency: the `blocked` issue can't go ahead until the
+/// `blocking` one is done. Either may live in another repo.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Dependency {
+    pub blocking: EpicIssue,
+    pub blocked: EpicIssue,
+}
+
+/// A Zenhub release, which groups issues from one or more repos that ship
+/// together.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Release {
+    #[serde(rename = "release_id")]
+    pub id: ReleaseId,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    pub description: String,
+    #[serde(default)]
+    pub desired_end_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub state: ReleaseState,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReleaseId(pub String);
+
+#[serde(rename_all = "lowercase")]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum ReleaseState {
+    Open,
+    Closed,
+}
+
+impl Default for ReleaseState {
+    fn default() -> Self {
+        Self::Open
+    }
+}
+
+/// Something which happened to an issue on the board, e.g. it was moved to
+/// another pipeline by a teammate.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct IssueEvent {
+    #[serde(default)]
+    pub user_id: u64,
+    #[serde(rename = "type", default)]
+    pub kind: IssueEventKind,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub from_pipeline: Option<PipelineName>,
+    #[serde(default)]
+    pub to_pipeline: Option<PipelineName>,
+}
+
+#[serde(rename_all = "camelCase")]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum IssueEventKind {
+    TransferIssue,
+    EstimateIssue,
+    #[serde(other)]
+    Other,
+}
+
+impl Default for IssueEventKind {
+    fn default() -> Self {
+        Self::Other
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct PipelineName {
+    pub name: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+struct MilestoneStartDate {
+    start_date: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Estimate {
+    pub value: f64,
+}
+
+/// Boards loaded recently, keyed by their URL. Loading a board again soon
+/// after is answered from memory, unless zentui changed one of its issues in
+/// the meantime.
+#[derive(Debug, Default)]
+struct BoardCache {
+    boards: Mutex<CachedBoards>,
+}
+
+#[derive(Debug, Default)]
+struct CachedBoards {
+    by_url: HashMap<Url, CachedBoard>,
+    /// Bumped whenever boards are forgotten, so that a board requested
+    /// before isn't cached when its response arrives after.
+    generation: u64,
+}
+
+#[derive(Debug)]
+struct CachedBoard {
+    repo_id: RepoId,
+    loaded_at: Instant,
+    board: Board,
+}
+
+impl BoardCache {
+    fn get(&self, url: &Url) -> Option<Board> {
+        self.boards
+            .lock()
+            .expect("board cache lock is not poisoned")
+            .by_url
+            .get(url)
+            .filter(|cached| cached.loaded_at.elapsed() < BOARD_CACHE_TTL)
+            .map(|cached| cached.board.clone())
+    }
+
+    /// The generation to `insert` a board with, taken before requesting it.
+    fn generation(&self) -> u64 {
+        self.boards
+            .lock()
+            .expect("board cache lock is not poisoned")
+            .generation
+    }
+
+    /// Caches a board requested at `generation`, unless boards were forgotten
+    /// while it was loading.
+    fn insert(&self, url: Url, repo_id: RepoId, board: Board, generation: u64) {
+        let mut boards = self
+            .boards
+            .lock()
+            .expect("board cache lock is not poisoned");
+        if boards.generation != generation {
+            return;
+        }
+        boards.by_url.insert(
+            url,
+            CachedBoard {
+                repo_id,
+                loaded_at: Instant::now(),
+                board,
+            },
+        );
+    }
+
+    /// Forgets the boards which show issues of a repo, as one of them is
+    /// about to change.
+    fn invalidate(&self, repo_id: RepoId) {
+        let mut boards = self
+            .boards
+            .lock()
+            .expect("board cache lock is not poisoned");
+        boards.generation += 1;
+        boards.by_url.retain(|_, cached| {
+            cached.repo_id != repo_id
+                && !cached
+                    .board
+                    .pipelines
+                    .iter()
+                    .flat_map(|pipeline| pipeline.issues.iter())
+                    .any(|issue_ref| issue_ref.repo_id == Some(repo_id))
+        });
+    }
+}
+
+/// Which of Zenhub's APIs the client talks to, chosen by the kind of token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Api {
+    /// The legacy REST API, for the tokens made in Zenhub's dashboard.
+    Rest,
+    /// The GraphQL API, for the newer personal API keys. Only boards,
+    /// workspaces and connected pull requests are loaded from it so far. The
+    /// REST API doesn't accept these keys, so everything else fails with
+    /// `Error::RestOnly` without sending a request.
+    GraphQl,
+}
+
+impl Api {
+    fn for_token(token: &Token) -> Self {
+        if token.0.starts_with(GRAPHQL_KEY_PREFIX) {
+            Self::GraphQl
+        } else {
+            Self::Rest
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Client {
+    api: Api,
+    endpoints: Endpoints,
+    http_client: HttpClient,
+    headers: HeaderMap,
+    board_cache: BoardCache,
+}
+
+impl Client {
+    /// Create a new API client.
+    pub fn new(token: Token) -> Result<Client> {
+        Self::with_endpoint(token, DEFAULT_ENDPOINT.clone())
+    }
+
+    /// Create a new API client for the API at `endpoint`, e.g. a Zenhub
+    /// Enterprise installation.
+    pub fn with_endpoint(token: Token, endpoint: Url) -> Result<Client> {
+        let api = Api::for_token(&token);
+        Ok(Client {
+            api,
+            endpoints: Endpoints::new(endpoint)?,
+            http_client: build_http_client()?,
+            headers: build_headers(&token, api)?,
+            board_cache: BoardCache::default(),
+        })
+    }
+
+    /// Forgets the boards showing issues of a repo loaded recently, so the
+    /// next request for them reaches Zenhub, e.g. when reloading on demand.
+    pub fn forget_boards(&self, repo_id: RepoId) {
+        self.board_cache.invalidate(repo_id);
+    }
+
+    /// Get the board of the oldest workspace which has the repo.
+    pub async fn get_oldest_board(self: Arc<Self>, repo_id: RepoId) -> Result<Board> {
+        if self.api == Api::GraphQl {
+            let workspace = self
+                .get_graphql_workspaces(repo_id)
+                .await?
+                .into_iter()
+                .min_by_key(|workspace| workspace.created_at)
+                .ok_or_else(|| Error::GraphQl {
+                    url: self
+                        .endpoints
+                        .graphql()
+                        .expect("GraphQL URL is well-formed"),
+                    messages: vec![format!("no workspace has the repo with id {}", repo_id.0)],
+                })?;
+            return self.get_board(workspace.id, repo_id).await;
+        }
+        self.get_cached_board(self.endpoints.oldest_board(&repo_id)?, repo_id)
+            .await
+    }
+
+    /// Get the estimate and pipeline of a single issue.
+    pub async fn get_issue_data(
+        self: Arc<Self>,
+        repo_id: RepoId,
+        issue_number: IssueNumber,
+    ) -> Result<IssueData> {
+        self.get::<IssueData>(self.endpoints.issue(&repo_id, &issue_number)?)
+            .await
+    }
+
+    /// Set the estimate of an issue, in story points. `None` clears it.
+    pub async fn set_estimate(
+        self: Arc<Self>,
+        repo_id: RepoId,
+        issue_number: IssueNumber,
+        estimate: Option<f64>,
+    ) -> Result<()> {
+        self.board_cache.invalidate(repo_id);
+        let result = self
+            .put(
+                self.endpoints.issue_estimate(&repo_id, &issue_number)?,
+                &EstimateUpdate { estimate },
+            )
+            .await;
+        // A board loaded while the estimate was changing may be stale
+        self.board_cache.invalidate(repo_id);
+        result
+    }
+
+    /// Get the board of a repo in a workspace.
+    pub async fn get_board(
+        self: Arc<Self>,
+        workspace_id: WorkspaceId,
+        repo_id: RepoId,
+    ) -> Result<Board> {
+        let url = self.endpoints.board(&workspace_id, &repo_id)?;
+        if self.api == Api::GraphQl {
+            if let Some(board) = self.board_cache.get(&url) {
+                return Ok(board);
+            }
+            let generation = self.board_cache.generation();
+            let board = self.get_graphql_board(&workspace_id).await?;
+            self.board_cache
+                .insert(url, repo_id, board.clone(), generation);
+            return Ok(board);
+        }
+        self.get_cached_board(url, repo_id).await
+    }
+
+    /// Get the workspaces which have the repo.
+    pub async fn get_workspaces(self: Arc<Self>, repo_id: RepoId) -> Result<Vector<Workspace>> {
+        if self.api == Api::GraphQl {
+            return Ok(self
+                .get_graphql_workspaces(repo_id)
+                .await?
+                .into_iter()
+                .map(|workspace| Workspace {
+                    id: workspace.id,
+                    name: workspace.name,
+                    description: workspace.description,
+                    repositories: vec![repo_id],
+                })
+                .collect());
+        }
+        self.get::<Vector<Workspace>>(self.endpoints.workspaces(&repo_id)?)
+            .await
+    }
+
+    async fn get_graphql_workspaces(&self, repo_id: RepoId) -> Result<Vec<GraphQlWorkspace>> {
+        let data: RepositoriesData = self
+            .graphql(WORKSPACES_QUERY, &RepoVariables { repo_id: repo_id.0 })
+            .await?;
+        Ok(data
+            .repositories_by_gh_id
+            .into_iter()
+            .flat_map(|repository| repository.workspaces_connection.nodes)
+            .collect())
+    }
+
+    /// Loads a board from the GraphQL API, a page of issues at a time for
+    /// each pipeline. Its issues come in their order on the board, which
+    /// stands in for the positions of the REST API. Closed issues aren't on
+    /// boards loaded this way.
+    async fn get_graphql_board(&self, workspace_id: &WorkspaceId) -> Result<Board> {
+        let data: PipelinesData = self
+            .graphql(
+                PIPELINES_QUERY,
+                &WorkspaceVariables {
+                    workspace_id: &workspace_id.0,
+                },
+            )
+            .await?;
+        let pipelines = data
+            .workspace
+            .map(|workspace| workspace.pipelines_connection.nodes)
+            .unwrap_or_default();
+
+        let mut board = Board::default();
+        for pipeline in pipelines {
+            let mut issues = Vector::new();
+            let mut after = None;
+            loop {
+                let data: PipelineIssuesData = self
+                    .graphql(
+                        PIPELINE_ISSUES_QUERY,
+                        &PipelineIssuesVariables {
+                            pipeline_id: &pipeline.id,
+                            after: after.as_deref(),
+                        },
+                    )
+                    .await?;
+                let page = data.search_issues_by_pipeline;
+                for issue in page.nodes {
+                    let position = issues.len();
+                    issues.push_back(IssueRef {
+                        number: issue.number,
+                        estimate: issue.estimate,
+                        position: Some(position),
+                        is_epic: issue.epic.is_some(),
+                        repo_id: Some(issue.repository.gh_id),
+                    });
+                }
+                match page.page_info {
+                    PageInfo {
+                        has_next_page: true,
+                        end_cursor: Some(end_cursor),
+                    } => after = Some(end_cursor),
+                    _ => break,
+                }
+            }
+            board.pipelines.push(Pipeline {
+                id: pipeline.id,
+                name: pipeline.name,
+                issues,
+            });
+        }
+        Ok(board)
+    }
+
+    async fn graphql<VariablesT, DataT>(&self, query: &str, variables: &VariablesT) -> Result<DataT>
+    where
+        VariablesT: Serialize,
+        for<'de> DataT: Deserialize<'de>,
+    {
+        let url = self.endpoints.graphql()?;
+        request::with_retries(&url, || self.send_graphql(url.clone(), query, variables)).await
+    }
+
+    async fn send_graphql<VariablesT, DataT>(
+        &self,
+        url: Url,
+        query: &str,
+        variables: &VariablesT,
+    ) -> Result<DataT>
+    where
+        VariablesT: Serialize,
+        for<'de> DataT: Deserialize<'de>,
+    {
+        let response = request::send(
+            Method::POST,
+            url.clone(),
+            self.http_client
+                .post(url.clone())
+                .headers(self.headers.clone())
+                .json(&graphql::Query { query, variables }),
+        )
+        .await?;
+        let body = request::text(Method::POST, url.clone(), response).await?;
+        request::parse::<graphql::Response<DataT>>(url.clone(), &body)?.into_data(url)
+    }
+
+    /// Get the issues belonging to an epic, which may live in other repos.
+    pub async fn get_epic(
+        self: Arc<Self>,
+        repo_id: RepoId,
+        issue_number: IssueNumber,
+    ) -> Result<Epic> {
+        self.get::<Epic>(self.endpoints.epic(&repo_id, &issue_number)?)
+            .await
+    }
+
+    /// Turn an issue into an epic and add issues to it, which may live in
+    /// other repos.
+    pub async fn convert_to_epic(
+        self: Arc<Self>,
+        repo_id: RepoId,
+        issue_number: IssueNumber,
+        issues: Vec<EpicIssue>,
+    ) -> Result<()> {
+        self.board_cache.invalidate(repo_id);
+        let result = self
+            .post(
+                self.endpoints.convert_to_epic(&repo_id, &issue_number)?,
+                &EpicConversion { issues: &issues },
+            )
+            .await;
+        self.board_cache.invalidate(repo_id);
+        result
+    }
+
+    /// Connect a pull request to the issue it works on. The pull request may
+    /// live in another repo.
+    pub async fn connect_pull_request(
+        self: Arc<Self>,
+        repo_id: RepoId,
+        issue_number: IssueNumber,
+        pull_request_repo_id: RepoId,
+        pull_request_number: IssueNumber,
+    ) -> Result<()> {
+        self.post(
+            self.endpoints.connection(&repo_id)?,
+            &Connection {
+                issue_number,
+                connected_repo_id: pull_request_repo_id,
+                pull_request_number,
+            },
+        )
+        .await
+    }
+
+    /// Whether pull requests connected to issues on Zenhub can be loaded,
+    /// which only the GraphQL API has.
+    pub fn loads_connections(&self) -> bool {
+        self.api == Api::GraphQl
+    }
+
+    /// Get the pull requests connected to each of the issues on Zenhub, by
+    /// their number, with a single query. Ask for at most
+    /// `ISSUES_PER_QUERY` issues at a time. Only the GraphQL API has
+    /// connections, none are returned for legacy REST tokens.
+    pub async fn get_connected_pull_requests(
+        self: Arc<Self>,
+        repo_id: RepoId,
+        issue_numbers: Vec<IssueNumber>,
+    ) -> Result<HashMap<IssueNumber, Vector<IssueNumber>>> {
+        if self.api != Api::GraphQl || issue_numbers.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let data: HashMap<String, Option<GraphQlConnections>> = self
+            .graphql(
+                &connections_query(&issue_numbers),
+                &RepoVariables { repo_id: repo_id.0 },
+            )
+            .await?;
+        Ok(data
+            .into_iter()
+            .filter_map(|(_, issue)| issue)
+            .map(|issue| {
+                let mut pull_requests: Vector<IssueNumber> = issue
+                    .connected_prs
+                    .nodes
+                    .into_iter()
+                    .map(|pull_request| pull_request.number)
+                    .collect();
+                pull_requests.sort();
+                (issue.number, pull_requests)
+            })
+            .collect())
+    }
+
+    /// Get the releases of a repository, open and closed.
+    pub async fn get_releases(self: Arc<Self>, repo_id: RepoId) -> Result<Vector<Release>> {
+        self.get::<Vector<Release>>(self.endpoints.releases(&repo_id)?)
+            .await
+    }
+
+    /// Get the dependencies between the issues of a repository and issues in
+    /// any repo.
+    pub async fn get_dependencies(self: Arc<Self>, repo_id: RepoId) -> Result<Vector<Dependency>> {
+        Ok(self
+            .get::<Dependencies>(self.endpoints.dependencies(&repo_id)?)
+            .await?
+            .dependencies)
+    }
+
+    /// Get the issues in a release, which may live in other repos.
+    pub async fn get_release_issues(
+        self: Arc<Self>,
+        release_id: ReleaseId,
+    ) -> Result<Vector<EpicIssue>> {
+        self.get::<Vector<EpicIssue>>(self.endpoints.release_issues(&release_id)?)
+            .await
+    }
+
+    /// Add issues to a release and remove others from it.
+    pub async fn update_release_issues(
+        self: Arc<Self>,
+        release_id: ReleaseId,
+        add_issues: Vec<EpicIssue>,
+        remove_issues: Vec<EpicIssue>,
+    ) -> Result<()> {
+        let url = self.endpoints.release_issues(&release_id)?;
+        self.check_rest(&Method::PATCH, &url)?;
+        request::send(
+            Method::PATCH,
+            url.clone(),
+            self.http_client
+                .patch(url)
+                .headers(self.headers.clone())
+                .json(&ReleaseIssuesUpdate {
+                    add_issues: &add_issues,
+                    remove_issues: &remove_issues,
+                }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Get the events of an issue, most recent first.
+    pub async fn get_issue_events(
+        self: Arc<Self>,
+        repo_id: RepoId,
+        issue_number: IssueNumber,
+    ) -> Result<Vector<IssueEvent>> {
+        self.get::<Vector<IssueEvent>>(self.endpoints.issue_events(&repo_id, &issue_number)?)
+            .await
+    }
+
+    /// Get the start date Zenhub adds to a Github milestone, which turns it
+    /// into a sprint. Returns `None` if the milestone has no start date.
+    pub async fn get_milestone_start_date(
+        self: Arc<Self>,
+        repo_id: RepoId,
+        milestone_number: usize,
+    ) -> Result<Option<DateTime<Utc>>> {
+        match self
+            .get::<MilestoneStartDate>(
+                self.endpoints
+                    .milestone_start_date(&repo_id, milestone_number)?,
+            )
+            .await
+        {
+            Ok(start_date) => Ok(Some(start_date.start_date)),
+            Err(error) if error.status() == Some(StatusCode::NOT_FOUND) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Move an issue to a different pipeline.
+    pub async fn move_issue(
+        self: Arc<Self>,
+        repo_id: RepoId,
+        issue_number: IssueNumber,
+        pipeline_id: String,
+        position: Position,
+    ) -> Result<()> {
+        self.board_cache.invalidate(repo_id);
+        let result = self
+            .post(
+                self.endpoints.issue_moves(&repo_id, &issue_number)?,
+                &IssueMove {
+                    pipeline_id: &pipeline_id,
+                    position,
+                },
+            )
+            .await;
+        self.board_cache.invalidate(repo_id);
+        result
+    }
+
+    async fn get_cached_board(&self, url: Url, repo_id: RepoId) -> Result<Board> {
+        if let Some(board) = self.board_cache.get(&url) {
+            return Ok(board);
+        }
+        let generation = self.board_cache.generation();
+        let board = self.get::<Board>(url.clone()).await?;
+        self.board_cache
+            .insert(url, repo_id, board.clone(), generation);
+        Ok(board)
+    }
+
+    /// Fails requests to the REST API when the token is a GraphQL API key,
+    /// which the REST API would reject anyway.
+    fn check_rest(&self, method: &Method, url: &Url) -> Result<()> {
+        match self.api {
+            Api::Rest => Ok(()),
+            Api::GraphQl => Err(Error::RestOnly {
+                method: method.clone(),
+                url: url.clone(),
+            }),
+        }
+    }
+
+    async fn post<BodyT>(&self, url: Url, body: &BodyT) -> Result<()>
+    where
+        BodyT: Serialize,
+    {
+        self.check_rest(&Method::POST, &url)?;
+        request::send(
+            Method::POST,
+            url.clone(),
+            self.http_client
+                .post(url)
+                .headers(self.headers.clone())
+                .json(body),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn put<BodyT>(&self, url: Url, body: &BodyT) -> Result<()>
+    where
+        BodyT: Serialize,
+    {
+        self.check_rest(&Method::PUT, &url)?;
+        request::send(
+            Method::PUT,
+            url.clone(),
+            self.http_client
+                .put(url)
+                .headers(self.headers.clone())
+                .json(body),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn get<SuccessT>(&self, url: Url) -> Result<SuccessT>
+    where
+        for<'de> SuccessT: Deserialize<'de>,
+    {
+        self.check_rest(&Method::GET, &url)?;
+        request::with_retries(&url, || {
+            request::get_json(url.clone(), || {
+                request::send(
+                    Method::GET,
+                    url.clone(),
+                    self.http_client
+                        .get(url.clone())
+                        .headers(self.headers.clone()),
+                )
+            })
+        })
+        .await
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token(pub String);
+
+impl std::str::FromStr for Token {
+    type Err = std::convert::Infallible;
+
+    fn from_str(token: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self(token.into()))
+    }
+}
+
+impl<T> From<T> for Token
+where
+    T: Into<String>,
+{
+    fn from(token: T) -> Self {
+        Self(token.into())
+    }
+}
+
+#[derive(Debug)]
+struct Endpoints {
+    base: Url,
+}
+
+impl Endpoints {
+    pub fn new(mut base: Url) -> Result<Self> {
+        // Endpoints are joined to the base, which would replace its last
+        // segment, e.g. `api` in `https://zenhub.example.com/api`
+        if !base.path().ends_with('/') {
+            base.set_path(&format!("{}/", base.path()));
+        }
+        // let datasets = base
+        //     .join("")
+        //     .chain_err(|| ErrorKind::Unknown {
+        //         message: "Could not build URL for dataset resources.".to_owned(),
+        //     })?;
+        // let sources = base
+        //     .join("/api/v1/sources")
+        //     .chain_err(|| ErrorKind::Unknown {
+        //         message: "Could not build URL for source resources.".to_owned(),
+        //     })?;
+        // let buckets = base
+        //     .join("/api/_private/buckets")
+        //     .chain_err(|| ErrorKind::Unknown {
+        //         message: "Could not build URL for bucket resources.".to_owned(),
+        //     })?;
+        // let users = base
+        //     .join("/api/_private/users")
+        //     .chain_err(|| ErrorKind::Unknown {
+        //         message: "Could not build URL for users resources.".to_owned(),
+        //     })?;
+        // let current_user = base.join("/auth/user").chain_err(|| ErrorKind::Unknown {
+        //     message: "Could not build URL for users resources.".to_owned(),
+        // })?;
+        Ok(Endpoints { base })
+    }
+
+    fn issue(&self, repo_id: &RepoId, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "p1/repositories/{}/issues/{}",
+                repo_id.0, issue_number.0
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "issue with repo_id `{}`, issue_number `{}`",
+                    repo_id.0, issue_number.0
+                ),
+                source,
+            })
+    }
+
+    fn epic(&self, repo_id: &RepoId, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "p1/repositories/{}/epics/{}",
+                repo_id.0, issue_number.0
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "epic with repo_id `{}`, issue_number `{}`",
+                    repo_id.0, issue_number.0
+                ),
+                source,
+            })
+    }
+
+    fn connection(&self, repo_id: &RepoId) -> Result<Url> {
+        self.base
+            .join(&format!("p1/repositories/{}/connection", repo_id.0))
+            .map_err(|source| Error::Url {
+                description: format!("issue connections with repo_id `{}`", repo_id.0),
+                source,
+            })
+    }
+
+    fn convert_to_epic(&self, repo_id: &RepoId, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "p1/repositories/{}/issues/{}/convert_to_epic",
+                repo_id.0, issue_number.0
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "converting to an epic the issue with repo_id `{}`, issue_number `{}`",
+                    repo_id.0, issue_number.0
+                ),
+                source,
+            })
+    }
+
+    fn issue_estimate(&self, repo_id: &RepoId, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "p1/repositories/{}/issues/{}/estimate",
+                repo_id.0, issue_number.0
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "estimate of issue with repo_id `{}`, issue_number `{}`",
+                    repo_id.0, issue_number.0
+                ),
+                source,
+            })
+    }
+
+    fn issue_events(&self, repo_id: &RepoId, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "p1/repositories/{}/issues/{}/events",
+                repo_id.0, issue_number.0
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "events of issue with repo_id `{}`, issue_number `{}`",
+                    repo_id.0, issue_number.0
+                ),
+                source,
+            })
+    }
+
+    fn issue_moves(&self, repo_id: &RepoId, issue_number: &IssueNumber) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "p1/repositories/{}/issues/{}/moves",
+                repo_id.0, issue_number.0
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "moving issue with repo_id `{}`, issue_number `{}`",
+                    repo_id.0, issue_number.0
+                ),
+                source,
+            })
+    }
+
+    fn milestone_start_date(&self, repo_id: &RepoId, milestone_number: usize) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "p1/repositories/{}/milestones/{}/start_date",
+                repo_id.0, milestone_number
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "start date of milestone `{}` with repo_id `{}`",
+                    milestone_number, repo_id.0
+                ),
+                source,
+            })
+    }
+
+    fn releases(&self, repo_id: &RepoId) -> Result<Url> {
+        self.base
+            .join(&format!("p1/repositories/{}/reports/releases", repo_id.0))
+            .map_err(|source| Error::Url {
+                description: format!("releases with repo_id `{}`", repo_id.0),
+                source,
+            })
+    }
+
+    fn dependencies(&self, repo_id: &RepoId) -> Result<Url> {
+        self.base
+            .join(&format!("p1/repositories/{}/dependencies", repo_id.0))
+            .map_err(|source| Error::Url {
+                description: format!("dependencies with repo_id `{}`", repo_id.0),
+                source,
+            })
+    }
+
+    fn release_issues(&self, release_id: &ReleaseId) -> Result<Url> {
+        self.base
+            .join(&format!("p1/reports/release/{}/issues", release_id.0))
+            .map_err(|source| Error::Url {
+                description: format!("issues of release `{}`", release_id.0),
+                source,
+            })
+    }
+
+    fn board(&self, workspace_id: &WorkspaceId, repo_id: &RepoId) -> Result<Url> {
+        self.base
+            .join(&format!(
+                "p2/workspaces/{}/repositories/{}/board",
+                workspace_id.0, repo_id.0
+            ))
+            .map_err(|source| Error::Url {
+                description: format!(
+                    "board with workspace_id `{}`, repo_id `{}`",
+                    workspace_id.0, repo_id.0
+                ),
+                source,
+            })
+    }
+
+    fn workspaces(&self, repo_id: &RepoId) -> Result<Url> {
+        self.base
+            .join(&format!("p2/repositories/{}/workspaces", repo_id.0))
+            .map_err(|source| Error::Url {
+                description: format!("workspaces with repo_id `{}`", repo_id.0),
+                source,
+            })
+    }
+
+    fn graphql(&self) -> Result<Url> {
+        self.base
+            .join("public/graphql")
+            .map_err(|source| Error::Url {
+                description: "the GraphQL API".into(),
+                source,
+            })
+    }
+
+    fn oldest_board(&self, repo_id: &RepoId) -> Result<Url> {
+        self.base
+            .join(&format!("p1/repositories/{}/board", repo_id.0))
+            .map_err(|source| Error::Url {
+                description: format!("oldest board with repo_id `{}`", repo_id.0),
+                source,
+            })
+    }
+}
+
+fn build_http_client() -> Result<HttpClient> {
+    HttpClient::builder()
+        .gzip(true)
+        .timeout(request::REQUEST_TIMEOUT)
+        .connect_timeout(request::CONNECT_TIMEOUT)
+        .build()
+        .map_err(Error::Client)
+}
+
+fn build_headers(token: &Token, api: Api) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    match api {
+        Api::Rest => headers.insert(
+            HeaderName::from_static("x-authentication-token"),
+            HeaderValue::from_str(&token.0).map_err(Error::InvalidToken)?,
+        ),
+        Api::GraphQl => headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token.0)).map_err(Error::InvalidToken)?,
+        ),
+    };
+    Ok(headers)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RepoVariables {
+    repo_id: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceVariables<'a> {
+    workspace_id: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PipelineIssuesVariables<'a> {
+    pipeline_id: &'a str,
+    after: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoriesData {
+    #[serde(default)]
+    repositories_by_gh_id: Vec<GraphQlRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlRepository {
+    #[serde(default)]
+    workspaces_connection: Nodes<GraphQlWorkspace>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlWorkspace {
+    id: WorkspaceId,
+    #[serde(default)]
+    name: String,
+    #[serde(default, deserialize_with = "lenient::null_as_default")]
+    description: String,
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipelinesData {
+    workspace: Option<GraphQlPipelines>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlPipelines {
+    #[serde(default)]
+    pipelines_connection: Nodes<PipelineRef>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct PipelineRef {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PipelineIssuesData {
+    search_issues_by_pipeline: IssuesPage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssuesPage {
+    #[serde(default)]
+    page_info: PageInfo,
+    #[serde(default, deserialize_with = "lenient::skip_invalid")]
+    nodes: Vec<GraphQlIssueRef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlIssueRef {
+    number: IssueNumber,
+    #[serde(default)]
+    estimate: Option<Estimate>,
+    repository: RepositoryRef,
+    /// Set when the issue is an epic.
+    #[serde(default)]
+    epic: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepositoryRef {
+    gh_id: RepoId,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlConnections {
+    number: IssueNumber,
+    #[serde(default)]
+    connected_prs: Nodes<PullRequestRef>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct PullRequestRef {
+    number: IssueNumber,
+}
+
+/// A query for the connected pull requests of the issues with the given
+/// numbers, each under an alias.
+fn connections_query(issue_numbers: &[IssueNumber]) -> String {
+    let mut query = String::from("query($repoId: Int!) {");
+    for issue_number in issue_numbers {
+        query.push_str(&format!(
+            " issue{0}: issueByInfo(repositoryGhId: $repoId, issueNumber: {0}) \
+             {{ number connectedPrs(first: 20) {{ nodes {{ number }} }} }}",
+            issue_number.0
+        ));
+    }
+    query.push_str(" }");
+    query
+}
+
+/// Parses the base URL of a Zenhub API, e.g. `https://zenhub.example.com/api`
+/// for Zenhub Enterprise, checking requests can be sent to it.
+pub fn parse_endpoint(endpoint: &str) -> Result<Url> {
+    let invalid = |reason: &str| Error::InvalidEndpoint {
+        endpoint: endpoint.to_owned(),
+        reason: reason.to_owned(),
+    };
+    let url = Url::parse(endpoint.trim()).map_err(|error| invalid(&error.to_string()))?;
+    if url.scheme() != "https" && url.scheme() != "http" {
+        return Err(invalid("only http and https URLs are supported"));
+    }
+    if url.host_str().map_or(true, str::is_empty) {
+        return Err(invalid("it has no host"));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(invalid("it can't have a query or fragment"));
+    }
+    Ok(url)
+}
+
+/// Zenhub's personal API keys start with this, its legacy REST tokens don't.
+const GRAPHQL_KEY_PREFIX: &str = "zh_";
+
+const WORKSPACES_QUERY: &str = "query($repoId: Int!) { repositoriesByGhId(ghIds: [$repoId]) { \
+     workspacesConnection(first: 100) { nodes { id name description createdAt } } } }";
+
+const PIPELINES_QUERY: &str = "query($workspaceId: ID!) { workspace(id: $workspaceId) { \
+     pipelinesConnection(first: 100) { nodes { id name } } } }";
+
+const PIPELINE_ISSUES_QUERY: &str =
+    "query($pipelineId: ID!, $after: String) { searchIssuesByPipeline(pipelineId: $pipelineId, \
+     filters: {}, first: 100, after: $after) { pageInfo { hasNextPage endCursor } \
+     nodes { number estimate { value } repository { ghId } epic { id } } } }";
+
+static DEFAULT_ENDPOINT: Lazy<Url> =
+    Lazy::new(|| Url::parse("https://api.zenhub.com").expect("Default URL is well-formed"));
+
+/// How long a loaded board is reused for. Shorter than the interval the app
+/// polls the board at, so polls always see the latest board.
+const BOARD_CACHE_TTL: Duration = Duration::from_secs(10);
@@ -0,0 +1,198 @@
+//! Regression tests parsing real-world API payloads, including the odd
+//! shapes Github and Zenhub return in practice: `null` bodies, deleted users,
+//! malformed label colours and list items missing required fields.
+
+use chrono::{TimeZone, Utc};
+use im::Vector;
+use zentui_client::{
+    github::{Colour, Comment, Issue, IssueNumber, IssueState, Repo, RepoId},
+    zenhub::{Board, Epic, IssueData, IssueEvent, IssueEventKind},
+};
+
+fn parse<ModelT>(fixture: &str) -> ModelT
+where
+    for<'de> ModelT: serde::Deserialize<'de>,
+{
+    serde_json::from_str(fixture).expect("fixture parses")
+}
+
+#[test]
+fn issue_ignores_unknown_fields_and_null_body() {
+    let issue: Issue = parse(include_str!("fixtures/github_issue.json"));
+    assert_eq!(issue.number, IssueNumber(42));
+    assert_eq!(issue.title, "Cards flicker when the board reloads");
+    assert_eq!(issue.user.login, "octocat");
+    assert_eq!(issue.body, "");
+    assert_eq!(issue.state, IssueState::Open);
+    assert_eq!(issue.reactions.total_count, 5);
+    assert_eq!(issue.comments, 3);
+    assert!(issue.pull_request.is_none());
+    assert_eq!(
+        issue.created_at,
+        Some(Utc.ymd(2020, 7, 2).and_hms(10, 11, 12))
+    );
+    assert_eq!(issue.updated_at, Some(Utc.ymd(2020, 7, 3).and_hms(8, 0, 0)));
+}
+
+#[test]
+fn issue_keeps_labels_with_malformed_colours_and_skips_invalid_ones() {
+    let issue: Issue = parse(include_str!("fixtures/github_issue.json"));
+    let names: Vec<_> = issue
+        .labels
+        .iter()
+        .map(|label| label.name.as_str())
+        .collect();
+    assert_eq!(names, ["bug", "needs triage"]);
+    assert_eq!(
+        issue.labels[0].color,
+        Colour {
+            red: 0xd7,
+            green: 0x3a,
+            blue: 0x4a
+        }
+    );
+    assert_eq!(issue.labels[1].color, Colour::default());
+}
+
+#[test]
+fn pull_request_issue_tolerates_deleted_author_and_partial_assignees() {
+    let issue: Issue = parse(include_str!("fixtures/github_pull_request_issue.json"));
+    assert!(issue.pull_request.is_some());
+    assert_eq!(issue.state, IssueState::Closed);
+    assert_eq!(issue.user.login, "");
+    assert_eq!(issue.body, "Fixes #42");
+    let assignees: Vec<_> = issue
+        .assignees
+        .iter()
+        .map(|assignee| assignee.login.as_str())
+        .collect();
+    assert_eq!(assignees, ["mcobzarenco"]);
+    assert_eq!(issue.reactions.total_count, 0);
+    assert_eq!(issue.updated_at, None);
+}
+
+#[test]
+fn comments_tolerate_null_user_and_body() {
+    let comments: Vector<Comment> = parse(include_str!("fixtures/github_comments.json"));
+    assert_eq!(comments.len(), 2);
+    assert_eq!(comments[0].user.login, "octocat");
+    assert_eq!(comments[0].body, "Reproduced on a 300 issue board.");
+    assert_eq!(comments[1].user.login, "");
+    assert_eq!(comments[1].body, "");
+}
+
+#[test]
+fn board_skips_malformed_pipelines_and_issues() {
+    let board: Board = parse(include_str!("fixtures/zenhub_board.json"));
+    let names: Vec<_> = board
+        .pipelines
+        .iter()
+        .map(|pipeline| pipeline.name.as_str())
+        .collect();
+    assert_eq!(names, ["New Issues", "Closed", "Review/QA"]);
+
+    let new_issues = &board.pipelines[0];
+    let numbers: Vec<_> = new_issues
+        .issues
+        .iter()
+        .map(|issue_ref| issue_ref.number.0)
+        .collect();
+    assert_eq!(numbers, [42, 7, 43]);
+    assert_eq!(
+        new_issues.issues[0].estimate.map(|estimate| estimate.value),
+        Some(3.0)
+    );
+    assert!(new_issues.issues[1].is_epic);
+    assert!(!new_issues.issues[2].is_epic);
+    assert_eq!(new_issues.issues[2].position, None);
+    assert_eq!(new_issues.issues[0].repo_id, None);
+    assert_eq!(
+        new_issues.issues[2].foreign_repo(RepoId(270_123_456)),
+        Some(RepoId(270_999_999))
+    );
+    assert_eq!(new_issues.issues[2].foreign_repo(RepoId(270_999_999)), None);
+    assert!(board.pipelines[2].issues.is_empty());
+}
+
+#[test]
+fn epic_skips_children_without_a_repo() {
+    let epic: Epic = parse(include_str!("fixtures/zenhub_epic.json"));
+    let children: Vec<_> = epic
+        .issues
+        .iter()
+        .map(|child| (child.number.0, child.repo_id))
+        .collect();
+    assert_eq!(
+        children,
+        [(42, RepoId(270_123_456)), (12, RepoId(270_999_999))]
+    );
+}
+
+#[test]
+fn issue_data_reads_estimate_and_pipeline() {
+    let issue_data: IssueData = parse(include_str!("fixtures/zenhub_issue_data.json"));
+    assert_eq!(
+        issue_data.estimate.map(|estimate| estimate.value),
+        Some(5.0)
+    );
+    assert_eq!(
+        issue_data.pipeline.map(|pipeline| pipeline.name),
+        Some("In Progress".to_owned())
+    );
+    assert!(!issue_data.is_epic);
+
+    let unestimated: IssueData = parse(r#"{ "estimate": null, "plus_ones": [] }"#);
+    assert_eq!(unestimated, IssueData::default());
+}
+
+#[test]
+fn issue_events_map_unknown_kinds_to_other() {
+    let events: Vector<IssueEvent> = parse(include_str!("fixtures/zenhub_issue_events.json"));
+    let kinds: Vec<_> = events.iter().map(|event| event.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            IssueEventKind::TransferIssue,
+            IssueEventKind::EstimateIssue,
+            IssueEventKind::Other
+        ]
+    );
+    assert_eq!(
+        events[0]
+            .to_pipeline
+            .as_ref()
+            .map(|pipeline| pipeline.name.as_str()),
+        Some("In Progress")
+    );
+    assert_eq!(events[2].user_id, 0);
+}
+
+#[test]
+fn repo_reads_archived_state_and_permissions() {
+    let repo: Repo = parse(include_str!("fixtures/github_repo.json"));
+    assert_eq!(repo.id, RepoId(270_123_456));
+    assert!(repo.archived);
+    let permissions = repo.permissions.as_ref().expect("permissions are set");
+    assert!(permissions.pull);
+    assert!(!permissions.push);
+    assert!(!repo.is_writable());
+
+    let anonymous: Repo = parse(r#"{"id": 1, "full_name": "octocat/hello-world"}"#);
+    assert!(anonymous.is_writable());
+}
+
+#[test]
+fn issues_and_boards_round_trip_through_json() {
+    for fixture in &[
+        include_str!("fixtures/github_issue.json"),
+        include_str!("fixtures/github_pull_request_issue.json"),
+    ] {
+        let issue: Issue = parse(fixture);
+        let cached = serde_json::to_string(&issue).expect("issue serializes");
+        assert_eq!(parse::<Issue>(&cached), issue);
+    }
+
+    let board: Board = parse(include_str!("fixtures/zenhub_board.json"));
+    let cached = serde_json::to_string(&board).expect("board serializes");
+    assert_eq!(parse::<Board>(&cached), board);
+}
@@ -0,0 +1,141 @@
+//! Properties of the order issues are shown in within a pipeline. Refreshing
+//! the board must not reshuffle cards, whatever order the API returns them in
+//! and however many of them share a position.
+
+use im::Vector;
+use zentui_client::{
+    github::{IssueNumber, RepoId},
+    zenhub::{IssueRef, Pipeline},
+};
+
+fn issue_ref(number: usize, position: Option<usize>, repo_id: Option<u64>) -> IssueRef {
+    IssueRef {
+        number: IssueNumber(number),
+        estimate: None,
+        position,
+        is_epic: false,
+        repo_id: repo_id.map(RepoId),
+    }
+}
+
+fn sorted(issues: &[IssueRef]) -> Vec<IssueRef> {
+    let mut pipeline = Pipeline {
+        id: "pipeline".into(),
+        name: "In Progress".into(),
+        issues: issues.iter().cloned().collect::<Vector<_>>(),
+    };
+    pipeline.sort_issues();
+    pipeline.issues.into_iter().collect()
+}
+
+fn permutations(issues: &[IssueRef]) -> Vec<Vec<IssueRef>> {
+    if issues.len() <= 1 {
+        return vec![issues.to_vec()];
+    }
+    (0..issues.len())
+        .flat_map(|index| {
+            let mut rest = issues.to_vec();
+            let first = rest.remove(index);
+            permutations(&rest).into_iter().map(move |mut permutation| {
+                permutation.insert(0, first.clone());
+                permutation
+            })
+        })
+        .collect()
+}
+
+/// Pipelines with lots of ties: few distinct positions, missing positions
+/// and issues from two repos sharing numbers. Generated from a fixed seed so
+/// failures reproduce.
+fn generated_pipelines() -> Vec<Vec<IssueRef>> {
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move |bound: u64| {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 33) % bound
+    };
+    (0..200)
+        .map(|_| {
+            let len = next(12) as usize;
+            (0..len)
+                .map(|_| {
+                    let position = match next(4) {
+                        0 => None,
+                        _ => Some(next(4) as usize),
+                    };
+                    let repo_id = match next(3) {
+                        0 => None,
+                        repo => Some(repo),
+                    };
+                    issue_ref(next(8) as usize + 1, position, repo_id)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn assert_in_board_order(issues: &[IssueRef]) {
+    for pair in issues.windows(2) {
+        let (left, right) = (&pair[0], &pair[1]);
+        match (left.position, right.position) {
+            (Some(left_position), Some(right_position)) => {
+                assert!(left_position <= right_position, "{:?}", issues)
+            }
+            // Issues without a position go last
+            (None, Some(_)) => panic!("unpositioned issue first: {:?}", issues),
+            _ => {}
+        }
+        if left.position == right.position {
+            assert!(left.number <= right.number, "{:?}", issues);
+        }
+    }
+}
+
+#[test]
+fn order_does_not_depend_on_the_order_issues_arrive_in() {
+    let issues = vec![
+        issue_ref(7, Some(1), None),
+        issue_ref(3, Some(1), None),
+        issue_ref(5, Some(0), None),
+        issue_ref(3, Some(1), Some(2)),
+        issue_ref(9, None, None),
+        issue_ref(2, None, None),
+    ];
+    let expected = sorted(&issues);
+    for permutation in permutations(&issues) {
+        assert_eq!(sorted(&permutation), expected);
+    }
+    assert_eq!(
+        expected
+            .iter()
+            .map(|issue_ref| issue_ref.number.0)
+            .collect::<Vec<_>>(),
+        vec![5, 3, 3, 7, 2, 9]
+    );
+}
+
+#[test]
+fn generated_pipelines_sort_the_same_whatever_the_arrival_order() {
+    for issues in generated_pipelines() {
+        let expected = sorted(&issues);
+        assert_in_board_order(&expected);
+
+        let mut reversed = issues.clone();
+        reversed.reverse();
+        assert_eq!(sorted(&reversed), expected);
+
+        let mut rotated = issues.clone();
+        let half = rotated.len() / 2;
+        rotated.rotate_left(half);
+        assert_eq!(sorted(&rotated), expected);
+    }
+}
+
+#[test]
+fn sorting_a_sorted_pipeline_changes_nothing() {
+    for issues in generated_pipelines() {
+        let once = sorted(&issues);
+        assert_eq!(sorted(&once), once);
+    }
+}